@@ -0,0 +1,58 @@
+/*!
+The Rialight concurrency API.
+
+# Synchronization
+
+The [`sync`] module provides asynchronous channels and synchronization
+primitives that work on every Rialight runtime.
+
+# Workers
+
+A [`Worker`] runs a function on a background thread, exchanging byte
+messages with the thread that spawned it. A [`WorkerPool`] runs jobs
+on a fixed number of background threads, reporting their progress
+and allowing them to be cancelled. A [`SharedBuffer`] shares bytes
+between threads without copying.
+
+Workers are not yet supported on the browser export.
+
+# Scripting
+
+With the `scripting` feature, enabled by default, a worker can also be
+created from a [Rhai](https://rhai.rs/book) script, allowing logic to be
+shipped without recompiling the application. Scripts run under sandboxing
+limits and can use a subset of the utilities API. See [`Worker::new`].
+
+```ignore
+use rialight_concurrent::worker;
+
+let worker = worker!("./worker_script.rhai").unwrap();
+```
+*/
+#![feature(decl_macro)]
+
+pub mod sync;
+
+mod worker;
+pub use worker::{
+    Worker,
+    WorkerScope,
+    WorkerError,
+    MessageError,
+    SharedBuffer,
+    WorkerPool,
+    JobContext,
+    JobHandle,
+};
+#[cfg(feature = "scripting")]
+pub use worker::{ScriptError, ScriptLimits};
+
+/// Creates a worker from a Rhai script file, with a path relative
+/// to the current file.
+///
+/// `worker!("./worker_script.rhai")` expands to
+/// `Worker::new(include_str!("./worker_script.rhai"))`.
+#[cfg(feature = "scripting")]
+pub macro worker($path:expr $(,)?) {
+    $crate::Worker::new(include_str!($path))
+}
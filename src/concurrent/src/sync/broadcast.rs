@@ -0,0 +1,299 @@
+/*!
+Multi-producer, multi-consumer channels where every receiver
+receives every value sent after it was created.
+
+The channel retains the last `capacity` values. A receiver that falls
+further behind misses the oldest values and is notified through
+[`RecvError::Lagged`].
+
+```
+use rialight_concurrent::sync::broadcast;
+
+async fn example_fn() {
+    let (sender, mut receiver_1) = broadcast::channel(16);
+    let mut receiver_2 = sender.subscribe();
+    sender.send(10).unwrap();
+    assert_eq!(receiver_1.recv().await, Ok(10));
+    assert_eq!(receiver_2.recv().await, Ok(10));
+}
+```
+*/
+
+use std::{
+    collections::VecDeque,
+    fmt::{Debug, Display},
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Poll, Waker},
+};
+use super::{register_waker, wake_all};
+
+/// Error returned when sending to a channel without receivers.
+/// Contains the value that was not sent.
+#[derive(PartialEq, Clone, Copy)]
+pub struct SendError<T>(pub T);
+
+impl<T> Debug for SendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SendError(..)")
+    }
+}
+
+impl<T> Display for SendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Channel has no receivers")
+    }
+}
+
+impl<T> std::error::Error for SendError<T> {}
+
+/// Error returned by [`Receiver::recv`].
+#[derive(PartialEq, Clone, Debug, Copy)]
+pub enum RecvError {
+    /// Every sender has been dropped and every value has been received.
+    Closed,
+    /// The receiver fell behind and missed the given number of values.
+    /// The next call receives the oldest retained value.
+    Lagged(u64),
+}
+
+impl Display for RecvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Closed => write!(f, "Channel closed"),
+            Self::Lagged(count) => write!(f, "Receiver lagged by {count} values"),
+        }
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+/// Error returned by [`Receiver::try_recv`].
+#[derive(PartialEq, Clone, Debug, Copy)]
+pub enum TryRecvError {
+    /// No new value has been sent.
+    Empty,
+    /// Every sender has been dropped and every value has been received.
+    Closed,
+    /// The receiver fell behind and missed the given number of values.
+    Lagged(u64),
+}
+
+impl Display for TryRecvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Channel empty"),
+            Self::Closed => write!(f, "Channel closed"),
+            Self::Lagged(count) => write!(f, "Receiver lagged by {count} values"),
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
+
+struct Channel<T> {
+    state: Mutex<ChannelState<T>>,
+}
+
+struct ChannelState<T> {
+    /// Retained values, the front one having the sequence
+    /// number `next_sequence - values.len()`.
+    values: VecDeque<T>,
+    next_sequence: u64,
+    capacity: usize,
+    senders: usize,
+    receivers: usize,
+    wakers: Vec<Waker>,
+}
+
+impl<T> ChannelState<T> {
+    fn first_sequence(&self) -> u64 {
+        self.next_sequence - self.values.len() as u64
+    }
+}
+
+impl<T> Channel<T> {
+    fn lock(&self) -> MutexGuard<'_, ChannelState<T>> {
+        self.state.lock().unwrap()
+    }
+}
+
+/// Creates a broadcast channel that retains up to `capacity` values.
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero.
+pub fn channel<T: Clone>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity != 0, "rialight::concurrent::sync::broadcast::channel() must be called with non-zero capacity");
+    let channel = Arc::new(Channel {
+        state: Mutex::new(ChannelState {
+            values: VecDeque::new(),
+            next_sequence: 0,
+            capacity,
+            senders: 1,
+            receivers: 1,
+            wakers: vec![],
+        }),
+    });
+    (Sender { channel: Arc::clone(&channel) }, Receiver { channel, next_sequence: 0 })
+}
+
+/// Sending half of a broadcast channel.
+pub struct Sender<T> {
+    channel: Arc<Channel<T>>,
+}
+
+impl<T: Clone> Sender<T> {
+    /// Sends a value to every receiver, returning the number
+    /// of receivers. Fails if there are no receivers.
+    pub fn send(&self, value: T) -> Result<usize, SendError<T>> {
+        let mut state = self.channel.lock();
+        if state.receivers == 0 {
+            return Err(SendError(value));
+        }
+        if state.values.len() == state.capacity {
+            state.values.pop_front();
+        }
+        state.values.push_back(value);
+        state.next_sequence += 1;
+        wake_all(&mut state.wakers);
+        Ok(state.receivers)
+    }
+
+    /// Creates a receiver that receives the values sent
+    /// after this call.
+    pub fn subscribe(&self) -> Receiver<T> {
+        let mut state = self.channel.lock();
+        state.receivers += 1;
+        Receiver { channel: Arc::clone(&self.channel), next_sequence: state.next_sequence }
+    }
+
+    /// Returns the number of receivers.
+    pub fn receiver_count(&self) -> usize {
+        self.channel.lock().receivers
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.channel.lock().senders += 1;
+        Self { channel: Arc::clone(&self.channel) }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.channel.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            wake_all(&mut state.wakers);
+        }
+    }
+}
+
+impl<T> Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sender").finish_non_exhaustive()
+    }
+}
+
+/// Receiving half of a broadcast channel. Cloning the receiver
+/// creates a receiver at the same position.
+pub struct Receiver<T> {
+    channel: Arc<Channel<T>>,
+    next_sequence: u64,
+}
+
+impl<T: Clone> Receiver<T> {
+    /// Receives the next value, waiting until one is sent.
+    pub async fn recv(&mut self) -> Result<T, RecvError> {
+        std::future::poll_fn(|cx| {
+            match self.try_recv() {
+                Ok(value) => Poll::Ready(Ok(value)),
+                Err(TryRecvError::Closed) => Poll::Ready(Err(RecvError::Closed)),
+                Err(TryRecvError::Lagged(count)) => Poll::Ready(Err(RecvError::Lagged(count))),
+                Err(TryRecvError::Empty) => {
+                    let mut state = self.channel.lock();
+                    if state.next_sequence != self.next_sequence || state.senders == 0 {
+                        cx.waker().wake_by_ref();
+                    } else {
+                        register_waker(&mut state.wakers, cx.waker());
+                    }
+                    Poll::Pending
+                },
+            }
+        }).await
+    }
+
+    /// Receives the next value if one has been sent.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let state = self.channel.lock();
+        let first_sequence = state.first_sequence();
+        if self.next_sequence < first_sequence {
+            let count = first_sequence - self.next_sequence;
+            self.next_sequence = first_sequence;
+            return Err(TryRecvError::Lagged(count));
+        }
+        if self.next_sequence < state.next_sequence {
+            let value = state.values[(self.next_sequence - first_sequence) as usize].clone();
+            self.next_sequence += 1;
+            return Ok(value);
+        }
+        if state.senders == 0 {
+            Err(TryRecvError::Closed)
+        } else {
+            Err(TryRecvError::Empty)
+        }
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        self.channel.lock().receivers += 1;
+        Self { channel: Arc::clone(&self.channel), next_sequence: self.next_sequence }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.channel.lock();
+        state.receivers -= 1;
+        if state.receivers == 0 {
+            state.values.clear();
+        }
+    }
+}
+
+impl<T> Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn broadcast() {
+        let (sender, mut receiver_1) = channel(2);
+        assert_eq!(sender.send(1), Ok(1));
+        let mut receiver_2 = sender.subscribe();
+        for i in 2..=4 {
+            assert_eq!(sender.send(i), Ok(2));
+        }
+        assert_eq!(receiver_1.try_recv(), Err(TryRecvError::Lagged(2)));
+        assert_eq!(receiver_2.try_recv(), Err(TryRecvError::Lagged(1)));
+        block_on(async {
+            assert_eq!(receiver_1.recv().await, Ok(3));
+            assert_eq!(receiver_2.recv().await, Ok(3));
+            drop(sender);
+            assert_eq!(receiver_1.recv().await, Ok(4));
+            assert_eq!(receiver_1.recv().await, Err(RecvError::Closed));
+        });
+
+        let (sender, receiver) = channel(1);
+        drop(receiver);
+        assert_eq!(sender.send(1), Err(SendError(1)));
+    }
+}
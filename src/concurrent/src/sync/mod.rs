@@ -0,0 +1,65 @@
+/*!
+Asynchronous channels and synchronization primitives.
+
+The types in this module do not depend on a specific runtime: they
+work on the Tokio runtime of the default export as well as on the
+single-threaded runtime of the browser export.
+
+# Channels
+
+- [`mpsc`]: multi-producer, single-consumer channels, bounded or unbounded.
+- [`oneshot`]: sends a single value between tasks.
+- [`broadcast`]: multi-producer, multi-consumer channels where every
+  receiver sees every value.
+- [`watch`]: single-producer, multi-consumer channels where receivers
+  observe the latest value.
+
+```
+use rialight_concurrent::sync::mpsc;
+
+async fn example_fn() {
+    let (sender, mut receiver) = mpsc::channel(16);
+    sender.send("hello").await.unwrap();
+    assert_eq!(receiver.recv().await, Some("hello"));
+}
+```
+
+# Synchronization primitives
+
+- [`Mutex`] and [`RwLock`], whose guards can be held across `.await` points.
+- [`Semaphore`], which limits concurrent access to a resource.
+- [`Notify`], which wakes tasks waiting for an event.
+*/
+
+use std::task::Waker;
+
+pub mod mpsc;
+pub mod oneshot;
+pub mod broadcast;
+pub mod watch;
+
+mod semaphore;
+pub use semaphore::{Semaphore, SemaphorePermit, AcquireError, TryAcquireError};
+
+mod mutex;
+pub use mutex::{Mutex, MutexGuard, TryLockError};
+
+mod rw_lock;
+pub use rw_lock::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+mod notify;
+pub use notify::{Notify, Notified};
+
+/// Registers a waker unless an equivalent one is already registered.
+fn register_waker(wakers: &mut Vec<Waker>, waker: &Waker) {
+    if !wakers.iter().any(|registered| registered.will_wake(waker)) {
+        wakers.push(waker.clone());
+    }
+}
+
+/// Wakes and removes every registered waker.
+fn wake_all(wakers: &mut Vec<Waker>) {
+    for waker in std::mem::take(wakers) {
+        waker.wake();
+    }
+}
@@ -0,0 +1,402 @@
+/*!
+Multi-producer, single-consumer channels.
+
+A bounded channel, created by [`channel`], makes senders wait while
+its buffer is full. An unbounded channel, created by [`unbounded_channel`],
+buffers any number of values.
+
+The [`Receiver`] is also a [`Stream`] of the received values.
+*/
+
+use std::{
+    collections::VecDeque,
+    fmt::{Debug, Display},
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll, Waker},
+};
+use futures::Stream;
+use super::{register_waker, wake_all};
+
+/// Error returned when sending to a closed channel.
+/// Contains the value that was not sent.
+#[derive(PartialEq, Clone, Copy)]
+pub struct SendError<T>(pub T);
+
+impl<T> Debug for SendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SendError(..)")
+    }
+}
+
+impl<T> Display for SendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Channel closed")
+    }
+}
+
+impl<T> std::error::Error for SendError<T> {}
+
+/// Error returned by [`Sender::try_send`].
+/// Contains the value that was not sent.
+#[derive(PartialEq, Clone, Copy)]
+pub enum TrySendError<T> {
+    /// The channel buffer is full.
+    Full(T),
+    /// The receiver has been dropped or closed.
+    Closed(T),
+}
+
+impl<T> TrySendError<T> {
+    /// Returns the value that was not sent.
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Full(value) => value,
+            Self::Closed(value) => value,
+        }
+    }
+}
+
+impl<T> Debug for TrySendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Full(_) => write!(f, "Full(..)"),
+            Self::Closed(_) => write!(f, "Closed(..)"),
+        }
+    }
+}
+
+impl<T> Display for TrySendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Full(_) => write!(f, "Channel full"),
+            Self::Closed(_) => write!(f, "Channel closed"),
+        }
+    }
+}
+
+impl<T> std::error::Error for TrySendError<T> {}
+
+/// Error returned by [`Receiver::try_recv`].
+#[derive(PartialEq, Clone, Debug, Copy)]
+pub enum TryRecvError {
+    /// The channel is empty.
+    Empty,
+    /// The channel is empty and every sender has been dropped,
+    /// or the receiver has been closed.
+    Closed,
+}
+
+impl Display for TryRecvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Channel empty"),
+            Self::Closed => write!(f, "Channel closed"),
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
+
+struct Channel<T> {
+    state: Mutex<ChannelState<T>>,
+}
+
+struct ChannelState<T> {
+    queue: VecDeque<T>,
+    /// `None` for unbounded channels.
+    capacity: Option<usize>,
+    senders: usize,
+    closed: bool,
+    receiver_wakers: Vec<Waker>,
+    sender_wakers: Vec<Waker>,
+}
+
+impl<T> Channel<T> {
+    fn new(capacity: Option<usize>) -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(ChannelState {
+                queue: VecDeque::new(),
+                capacity,
+                senders: 1,
+                closed: false,
+                receiver_wakers: vec![],
+                sender_wakers: vec![],
+            }),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ChannelState<T>> {
+        self.state.lock().unwrap()
+    }
+
+    fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        let mut state = self.lock();
+        if state.closed {
+            return Err(TrySendError::Closed(value));
+        }
+        if state.capacity.is_some_and(|capacity| state.queue.len() >= capacity) {
+            return Err(TrySendError::Full(value));
+        }
+        state.queue.push_back(value);
+        wake_all(&mut state.receiver_wakers);
+        Ok(())
+    }
+
+    fn add_sender(&self) {
+        self.lock().senders += 1;
+    }
+
+    fn drop_sender(&self) {
+        let mut state = self.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            wake_all(&mut state.receiver_wakers);
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+}
+
+/// Creates a bounded channel that buffers up to `capacity` values.
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero.
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity != 0, "rialight::concurrent::sync::mpsc::channel() must be called with non-zero capacity");
+    let channel = Channel::new(Some(capacity));
+    (Sender { channel: Arc::clone(&channel) }, Receiver { channel })
+}
+
+/// Creates an unbounded channel.
+pub fn unbounded_channel<T>() -> (UnboundedSender<T>, Receiver<T>) {
+    let channel = Channel::new(None);
+    (UnboundedSender { channel: Arc::clone(&channel) }, Receiver { channel })
+}
+
+/// Sending half of a bounded channel. Cloning the sender
+/// creates another producer for the same channel.
+pub struct Sender<T> {
+    channel: Arc<Channel<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Sends a value, waiting while the channel buffer is full.
+    /// Fails if the receiver has been dropped or closed.
+    pub async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let mut value = Some(value);
+        std::future::poll_fn(|cx| {
+            match self.channel.try_send(value.take().unwrap()) {
+                Ok(()) => Poll::Ready(Ok(())),
+                Err(TrySendError::Closed(value)) => Poll::Ready(Err(SendError(value))),
+                Err(TrySendError::Full(returned)) => {
+                    let mut state = self.channel.lock();
+                    register_waker(&mut state.sender_wakers, cx.waker());
+                    // the buffer may have been drained before the lock was acquired
+                    if state.closed || state.capacity.is_some_and(|capacity| state.queue.len() < capacity) {
+                        cx.waker().wake_by_ref();
+                    }
+                    value = Some(returned);
+                    Poll::Pending
+                },
+            }
+        }).await
+    }
+
+    /// Sends a value if the channel buffer is not full.
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        self.channel.try_send(value)
+    }
+
+    /// Indicates whether the receiver has been dropped or closed.
+    pub fn is_closed(&self) -> bool {
+        self.channel.is_closed()
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.channel.add_sender();
+        Self { channel: Arc::clone(&self.channel) }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.channel.drop_sender();
+    }
+}
+
+impl<T> Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sender").finish_non_exhaustive()
+    }
+}
+
+/// Sending half of an unbounded channel. Cloning the sender
+/// creates another producer for the same channel.
+pub struct UnboundedSender<T> {
+    channel: Arc<Channel<T>>,
+}
+
+impl<T> UnboundedSender<T> {
+    /// Sends a value without waiting. Fails if the receiver
+    /// has been dropped or closed.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        self.channel.try_send(value).map_err(|error| SendError(error.into_inner()))
+    }
+
+    /// Indicates whether the receiver has been dropped or closed.
+    pub fn is_closed(&self) -> bool {
+        self.channel.is_closed()
+    }
+}
+
+impl<T> Clone for UnboundedSender<T> {
+    fn clone(&self) -> Self {
+        self.channel.add_sender();
+        Self { channel: Arc::clone(&self.channel) }
+    }
+}
+
+impl<T> Drop for UnboundedSender<T> {
+    fn drop(&mut self) {
+        self.channel.drop_sender();
+    }
+}
+
+impl<T> Debug for UnboundedSender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UnboundedSender").finish_non_exhaustive()
+    }
+}
+
+/// Receiving half of a channel.
+pub struct Receiver<T> {
+    channel: Arc<Channel<T>>,
+}
+
+impl<T> Receiver<T> {
+    /// Receives the next value, waiting while the channel is empty.
+    /// Returns `None` once the channel is empty and every sender has
+    /// been dropped, or the receiver has been closed.
+    pub async fn recv(&mut self) -> Option<T> {
+        std::future::poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Polls to receive the next value.
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        match self.try_recv() {
+            Ok(value) => Poll::Ready(Some(value)),
+            Err(TryRecvError::Closed) => Poll::Ready(None),
+            Err(TryRecvError::Empty) => {
+                let mut state = self.channel.lock();
+                if !state.queue.is_empty() || state.senders == 0 {
+                    cx.waker().wake_by_ref();
+                } else {
+                    register_waker(&mut state.receiver_wakers, cx.waker());
+                }
+                Poll::Pending
+            },
+        }
+    }
+
+    /// Receives the next value if the channel is not empty.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let mut state = self.channel.lock();
+        if let Some(value) = state.queue.pop_front() {
+            wake_all(&mut state.sender_wakers);
+            return Ok(value);
+        }
+        if state.closed || state.senders == 0 {
+            Err(TryRecvError::Closed)
+        } else {
+            Err(TryRecvError::Empty)
+        }
+    }
+
+    /// Closes the channel, so that senders fail, while still
+    /// allowing the buffered values to be received.
+    pub fn close(&mut self) {
+        let mut state = self.channel.lock();
+        state.closed = true;
+        wake_all(&mut state.sender_wakers);
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.channel.lock();
+        state.closed = true;
+        state.queue.clear();
+        wake_all(&mut state.sender_wakers);
+    }
+}
+
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.poll_recv(cx)
+    }
+}
+
+impl<T> Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::{executor::block_on, join, StreamExt};
+
+    #[test]
+    fn bounded() {
+        let (sender, mut receiver) = channel(2);
+        block_on(async {
+            let producer = async {
+                for i in 0..5 {
+                    sender.send(i).await.unwrap();
+                }
+                drop(sender);
+            };
+            let consumer = async {
+                let mut received = vec![];
+                while let Some(i) = receiver.recv().await {
+                    received.push(i);
+                }
+                received
+            };
+            let ((), received) = join!(producer, consumer);
+            assert_eq!(received, [0, 1, 2, 3, 4]);
+        });
+
+        let (sender, mut receiver) = channel(1);
+        assert_eq!(sender.try_send(1), Ok(()));
+        assert_eq!(sender.try_send(2), Err(TrySendError::Full(2)));
+        receiver.close();
+        assert_eq!(sender.try_send(3), Err(TrySendError::Closed(3)));
+        assert_eq!(receiver.try_recv(), Ok(1));
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Closed));
+    }
+
+    #[test]
+    fn unbounded() {
+        let (sender, receiver) = unbounded_channel();
+        let other_sender = sender.clone();
+        sender.send(1).unwrap();
+        other_sender.send(2).unwrap();
+        drop((sender, other_sender));
+        assert_eq!(block_on(receiver.collect::<Vec<_>>()), [1, 2]);
+
+        let (sender, receiver) = unbounded_channel();
+        drop(receiver);
+        assert!(sender.is_closed());
+        assert_eq!(sender.send(1), Err(SendError(1)));
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    cell::UnsafeCell,
+    fmt::{Debug, Display},
+    ops::{Deref, DerefMut},
+};
+use super::{Semaphore, SemaphorePermit};
+
+/// Error returned by [`Mutex::try_lock`] when the lock is held.
+#[derive(PartialEq, Clone, Debug, Copy)]
+pub struct TryLockError;
+
+impl Display for TryLockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Lock is held")
+    }
+}
+
+impl std::error::Error for TryLockError {}
+
+/// An asynchronous mutual exclusion lock. Unlike `std::sync::Mutex`,
+/// waiting for the lock does not block the thread, and the guard
+/// can be held across `.await` points.
+///
+/// Tasks acquire the lock in the order they request it.
+///
+/// # Example
+///
+/// ```
+/// use rialight_concurrent::sync::Mutex;
+///
+/// async fn increment(counter: &Mutex<u64>) {
+///     *counter.lock().await += 1;
+/// }
+/// ```
+pub struct Mutex<T: ?Sized> {
+    semaphore: Semaphore,
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: the semaphore grants access to the value to one guard at a time.
+unsafe impl<T: ?Sized + Send> Send for Mutex<T> {}
+unsafe impl<T: ?Sized + Send> Sync for Mutex<T> {}
+
+impl<T> Mutex<T> {
+    /// Creates an unlocked mutex holding `value`.
+    pub fn new(value: T) -> Self {
+        Self { semaphore: Semaphore::new(1), value: UnsafeCell::new(value) }
+    }
+
+    /// Consumes the mutex, returning its value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: ?Sized> Mutex<T> {
+    /// Waits until the lock is acquired.
+    pub async fn lock(&self) -> MutexGuard<'_, T> {
+        let permit = self.semaphore.acquire().await.unwrap();
+        MutexGuard { lock: self, _permit: permit }
+    }
+
+    /// Acquires the lock if it is not held.
+    pub fn try_lock(&self) -> Result<MutexGuard<'_, T>, TryLockError> {
+        let permit = self.semaphore.try_acquire().map_err(|_| TryLockError)?;
+        Ok(MutexGuard { lock: self, _permit: permit })
+    }
+
+    /// Returns a mutable reference to the value. No locking
+    /// is needed, as the mutex is borrowed mutably.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+}
+
+impl<T: Default> Default for Mutex<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: ?Sized + Debug> Debug for Mutex<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("Mutex");
+        match self.try_lock() {
+            Ok(guard) => debug.field("value", &&*guard),
+            Err(_) => debug.field("value", &format_args!("<locked>")),
+        };
+        debug.finish()
+    }
+}
+
+/// Guard of a locked [`Mutex`], which releases the
+/// lock when dropped.
+#[must_use]
+pub struct MutexGuard<'a, T: ?Sized> {
+    lock: &'a Mutex<T>,
+    _permit: SemaphorePermit<'a>,
+}
+
+// SAFETY: the guard only gives shared access to the value through `&self`.
+unsafe impl<T: ?Sized + Sync> Sync for MutexGuard<'_, T> {}
+
+impl<T: ?Sized> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: the guard holds the only permit of the semaphore.
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: the guard holds the only permit of the semaphore.
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized + Debug> Debug for MutexGuard<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::{executor::block_on, future::join_all};
+    use std::task::Poll;
+
+    #[test]
+    fn lock() {
+        let counter = Mutex::new(0);
+        block_on(join_all((0..10).map(|_| async {
+            let mut guard = counter.lock().await;
+            let value = *guard;
+            // yield while holding the lock
+            let mut yielded = false;
+            std::future::poll_fn(|cx| {
+                if yielded {
+                    return Poll::Ready(());
+                }
+                yielded = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }).await;
+            *guard = value + 1;
+        })));
+        let guard = counter.try_lock().unwrap();
+        assert_eq!(*guard, 10);
+        assert_eq!(counter.try_lock().unwrap_err(), TryLockError);
+        drop(guard);
+        assert_eq!(counter.into_inner(), 10);
+    }
+}
@@ -0,0 +1,190 @@
+use std::{
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll, Waker},
+};
+
+/// Notifies tasks waiting for an event.
+///
+/// [`Notify::notify_one`] wakes one waiting task, or stores a single
+/// permit that completes the next wait immediately if no task is waiting.
+/// [`Notify::notify_waiters`] wakes every task currently waiting.
+///
+/// # Example
+///
+/// ```
+/// use rialight_concurrent::sync::Notify;
+///
+/// async fn consumer(notify: &Notify) {
+///     loop {
+///         notify.notified().await;
+///         // handle the event
+///     }
+/// }
+///
+/// fn producer(notify: &Notify) {
+///     notify.notify_one();
+/// }
+/// ```
+#[derive(Default)]
+pub struct Notify {
+    state: Mutex<NotifyState>,
+}
+
+#[derive(Default)]
+struct NotifyState {
+    permit: bool,
+    waiters: Vec<Waiter>,
+    next_waiter_id: u64,
+}
+
+struct Waiter {
+    id: u64,
+    waker: Waker,
+    notification: Option<Notification>,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Notification {
+    One,
+    All,
+}
+
+impl NotifyState {
+    /// Notifies the first waiter not yet notified, returning
+    /// whether there was one.
+    fn notify_first_waiter(&mut self) -> bool {
+        let Some(waiter) = self.waiters.iter_mut().find(|waiter| waiter.notification.is_none()) else {
+            return false;
+        };
+        waiter.notification = Some(Notification::One);
+        waiter.waker.wake_by_ref();
+        true
+    }
+}
+
+impl Notify {
+    /// Creates a `Notify` without a stored permit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Waits until notified.
+    pub fn notified(&self) -> Notified<'_> {
+        Notified { notify: self, waiter_id: None }
+    }
+
+    /// Wakes the task that has waited longest, or stores
+    /// a permit for the next wait if no task is waiting.
+    pub fn notify_one(&self) {
+        let mut state = self.state.lock().unwrap();
+        if !state.notify_first_waiter() {
+            state.permit = true;
+        }
+    }
+
+    /// Wakes every task currently waiting, without
+    /// storing a permit.
+    pub fn notify_waiters(&self) {
+        let mut state = self.state.lock().unwrap();
+        for waiter in state.waiters.iter_mut().filter(|waiter| waiter.notification.is_none()) {
+            waiter.notification = Some(Notification::All);
+            waiter.waker.wake_by_ref();
+        }
+    }
+}
+
+impl Debug for Notify {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Notify").finish_non_exhaustive()
+    }
+}
+
+/// Future returned by [`Notify::notified`].
+///
+/// The future starts waiting when first polled; a
+/// [`Notify::notify_waiters`] call made before that does not complete it.
+#[must_use = "futures do nothing unless polled"]
+pub struct Notified<'a> {
+    notify: &'a Notify,
+    waiter_id: Option<u64>,
+}
+
+impl Future for Notified<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.notify.state.lock().unwrap();
+        let Some(id) = self.waiter_id else {
+            if state.permit {
+                state.permit = false;
+                return Poll::Ready(());
+            }
+            let id = state.next_waiter_id;
+            state.next_waiter_id += 1;
+            state.waiters.push(Waiter { id, waker: cx.waker().clone(), notification: None });
+            self.waiter_id = Some(id);
+            return Poll::Pending;
+        };
+        let index = state.waiters.iter().position(|waiter| waiter.id == id).unwrap();
+        if state.waiters[index].notification.is_some() {
+            state.waiters.remove(index);
+            self.waiter_id = None;
+            return Poll::Ready(());
+        }
+        state.waiters[index].waker.clone_from(cx.waker());
+        Poll::Pending
+    }
+}
+
+impl Drop for Notified<'_> {
+    fn drop(&mut self) {
+        let Some(id) = self.waiter_id else {
+            return;
+        };
+        let mut state = self.notify.state.lock().unwrap();
+        let index = state.waiters.iter().position(|waiter| waiter.id == id).unwrap();
+        let waiter = state.waiters.remove(index);
+        // pass an unconsumed `notify_one` on to another waiter
+        if waiter.notification == Some(Notification::One) && !state.notify_first_waiter() {
+            state.permit = true;
+        }
+    }
+}
+
+impl Debug for Notified<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Notified").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::{executor::block_on, join, FutureExt};
+
+    #[test]
+    fn notify() {
+        let notify = Notify::new();
+        notify.notify_one();
+        notify.notify_one();
+        block_on(notify.notified());
+        assert!(notify.notified().now_or_never().is_none());
+
+        block_on(async {
+            let waiters = join!(notify.notified(), notify.notified(), async { notify.notify_waiters() });
+            assert_eq!(waiters, ((), (), ()));
+        });
+
+        let mut first = Box::pin(notify.notified());
+        let mut second = Box::pin(notify.notified());
+        assert!((&mut first).now_or_never().is_none());
+        assert!((&mut second).now_or_never().is_none());
+        notify.notify_one();
+        // the notification moves on to the second waiter
+        drop(first);
+        assert!(second.now_or_never().is_some());
+    }
+}
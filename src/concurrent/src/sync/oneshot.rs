@@ -0,0 +1,210 @@
+/*!
+Channels for sending a single value between tasks.
+
+The [`Receiver`] is a future that completes with the sent value.
+
+```
+use rialight_concurrent::sync::oneshot;
+
+async fn example_fn() {
+    let (sender, receiver) = oneshot::channel();
+    sender.send(10).unwrap();
+    assert_eq!(receiver.await, Ok(10));
+}
+```
+*/
+
+use std::{
+    fmt::{Debug, Display},
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+/// Error returned when the sender is dropped without sending a value.
+#[derive(PartialEq, Clone, Debug, Copy)]
+pub struct RecvError;
+
+impl Display for RecvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Channel closed")
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+/// Error returned by [`Receiver::try_recv`].
+#[derive(PartialEq, Clone, Debug, Copy)]
+pub enum TryRecvError {
+    /// No value has been sent yet.
+    Empty,
+    /// The sender has been dropped without sending a value,
+    /// or the value has already been received.
+    Closed,
+}
+
+impl Display for TryRecvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Channel empty"),
+            Self::Closed => write!(f, "Channel closed"),
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
+
+struct Channel<T> {
+    state: Mutex<ChannelState<T>>,
+}
+
+struct ChannelState<T> {
+    value: Option<T>,
+    sender_alive: bool,
+    closed: bool,
+    receiver_waker: Option<Waker>,
+}
+
+/// Creates a one-shot channel.
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let channel = Arc::new(Channel {
+        state: Mutex::new(ChannelState {
+            value: None,
+            sender_alive: true,
+            closed: false,
+            receiver_waker: None,
+        }),
+    });
+    (Sender { channel: Arc::clone(&channel) }, Receiver { channel })
+}
+
+/// Sending half of a one-shot channel.
+pub struct Sender<T> {
+    channel: Arc<Channel<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Sends the value, consuming the sender. If the receiver
+    /// has been dropped or closed, the value is returned back.
+    pub fn send(self, value: T) -> Result<(), T> {
+        let mut state = self.channel.state.lock().unwrap();
+        if state.closed {
+            return Err(value);
+        }
+        state.value = Some(value);
+        if let Some(waker) = state.receiver_waker.take() {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    /// Indicates whether the receiver has been dropped or closed.
+    pub fn is_closed(&self) -> bool {
+        self.channel.state.lock().unwrap().closed
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.channel.state.lock().unwrap();
+        state.sender_alive = false;
+        if let Some(waker) = state.receiver_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sender").finish_non_exhaustive()
+    }
+}
+
+/// Receiving half of a one-shot channel. Awaiting it completes
+/// with the sent value, or with [`RecvError`] if the sender is dropped
+/// without sending a value.
+pub struct Receiver<T> {
+    channel: Arc<Channel<T>>,
+}
+
+impl<T> Receiver<T> {
+    /// Receives the value if it has been sent.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let mut state = self.channel.state.lock().unwrap();
+        if let Some(value) = state.value.take() {
+            state.closed = true;
+            return Ok(value);
+        }
+        if state.closed || !state.sender_alive {
+            Err(TryRecvError::Closed)
+        } else {
+            Err(TryRecvError::Empty)
+        }
+    }
+
+    /// Closes the channel, so that sending fails. A value
+    /// sent before closing can still be received.
+    pub fn close(&mut self) {
+        self.channel.state.lock().unwrap().closed = true;
+    }
+}
+
+impl<T> Future for Receiver<T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.try_recv() {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(TryRecvError::Closed) => Poll::Ready(Err(RecvError)),
+            Err(TryRecvError::Empty) => {
+                let mut state = self.channel.state.lock().unwrap();
+                if state.value.is_some() || !state.sender_alive {
+                    cx.waker().wake_by_ref();
+                } else {
+                    state.receiver_waker = Some(cx.waker().clone());
+                }
+                Poll::Pending
+            },
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.channel.state.lock().unwrap();
+        state.closed = true;
+        state.value = None;
+    }
+}
+
+impl<T> Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::{executor::block_on, join};
+
+    #[test]
+    fn send_and_receive() {
+        let (sender, receiver) = channel();
+        block_on(async {
+            let (result, value) = join!(async { sender.send("value") }, receiver);
+            assert_eq!((result, value), (Ok(()), Ok("value")));
+        });
+
+        let (sender, receiver) = channel::<()>();
+        drop(sender);
+        assert_eq!(block_on(receiver), Err(RecvError));
+
+        let (sender, mut receiver) = channel();
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+        receiver.close();
+        assert!(sender.is_closed());
+        assert_eq!(sender.send(1), Err(1));
+    }
+}
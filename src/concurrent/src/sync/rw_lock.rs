@@ -0,0 +1,176 @@
+use std::{
+    cell::UnsafeCell,
+    fmt::Debug,
+    ops::{Deref, DerefMut},
+};
+use super::{Semaphore, SemaphorePermit, TryLockError};
+
+/// Maximum number of concurrent readers.
+const MAX_READERS: u32 = u32::MAX >> 3;
+
+/// An asynchronous reader-writer lock, allowing either many readers
+/// or one writer at a time. Its guards can be held across `.await` points.
+///
+/// Tasks acquire the lock in the order they request it, so
+/// readers do not starve a waiting writer.
+///
+/// # Example
+///
+/// ```
+/// use rialight_concurrent::sync::RwLock;
+///
+/// async fn example_fn(settings: &RwLock<Vec<String>>) {
+///     let length = settings.read().await.len();
+///     settings.write().await.push(format!("entry {length}"));
+/// }
+/// ```
+pub struct RwLock<T: ?Sized> {
+    semaphore: Semaphore,
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: the semaphore grants either shared access to the readers
+// or exclusive access to one writer.
+unsafe impl<T: ?Sized + Send> Send for RwLock<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for RwLock<T> {}
+
+impl<T> RwLock<T> {
+    /// Creates an unlocked lock holding `value`.
+    pub fn new(value: T) -> Self {
+        Self { semaphore: Semaphore::new(MAX_READERS as usize), value: UnsafeCell::new(value) }
+    }
+
+    /// Consumes the lock, returning its value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: ?Sized> RwLock<T> {
+    /// Waits until shared read access is acquired.
+    pub async fn read(&self) -> RwLockReadGuard<'_, T> {
+        let permit = self.semaphore.acquire().await.unwrap();
+        RwLockReadGuard { lock: self, _permit: permit }
+    }
+
+    /// Waits until exclusive write access is acquired.
+    pub async fn write(&self) -> RwLockWriteGuard<'_, T> {
+        let permit = self.semaphore.acquire_many(MAX_READERS).await.unwrap();
+        RwLockWriteGuard { lock: self, _permit: permit }
+    }
+
+    /// Acquires shared read access if no writer holds
+    /// or is waiting for the lock.
+    pub fn try_read(&self) -> Result<RwLockReadGuard<'_, T>, TryLockError> {
+        let permit = self.semaphore.try_acquire().map_err(|_| TryLockError)?;
+        Ok(RwLockReadGuard { lock: self, _permit: permit })
+    }
+
+    /// Acquires exclusive write access if the lock is not held.
+    pub fn try_write(&self) -> Result<RwLockWriteGuard<'_, T>, TryLockError> {
+        let permit = self.semaphore.try_acquire_many(MAX_READERS).map_err(|_| TryLockError)?;
+        Ok(RwLockWriteGuard { lock: self, _permit: permit })
+    }
+
+    /// Returns a mutable reference to the value. No locking
+    /// is needed, as the lock is borrowed mutably.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+}
+
+impl<T: Default> Default for RwLock<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: ?Sized + Debug> Debug for RwLock<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("RwLock");
+        match self.try_read() {
+            Ok(guard) => debug.field("value", &&*guard),
+            Err(_) => debug.field("value", &format_args!("<locked>")),
+        };
+        debug.finish()
+    }
+}
+
+/// Guard of shared read access to a [`RwLock`].
+#[must_use]
+pub struct RwLockReadGuard<'a, T: ?Sized> {
+    lock: &'a RwLock<T>,
+    _permit: SemaphorePermit<'a>,
+}
+
+// SAFETY: the guard only gives shared access to the value.
+unsafe impl<T: ?Sized + Sync> Send for RwLockReadGuard<'_, T> {}
+unsafe impl<T: ?Sized + Sync> Sync for RwLockReadGuard<'_, T> {}
+
+impl<T: ?Sized> Deref for RwLockReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: no writer holds the lock while a reader does.
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized + Debug> Debug for RwLockReadGuard<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+/// Guard of exclusive write access to a [`RwLock`].
+#[must_use]
+pub struct RwLockWriteGuard<'a, T: ?Sized> {
+    lock: &'a RwLock<T>,
+    _permit: SemaphorePermit<'a>,
+}
+
+// SAFETY: the guard only gives shared access to the value through `&self`.
+unsafe impl<T: ?Sized + Sync> Sync for RwLockWriteGuard<'_, T> {}
+
+impl<T: ?Sized> Deref for RwLockWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: the writer holds every permit of the semaphore.
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for RwLockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: the writer holds every permit of the semaphore.
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized + Debug> Debug for RwLockWriteGuard<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn readers_and_writers() {
+        let lock = RwLock::new(1);
+        let reader_1 = lock.try_read().unwrap();
+        let reader_2 = block_on(lock.read());
+        assert_eq!(*reader_1 + *reader_2, 2);
+        assert!(lock.try_write().is_err());
+        drop((reader_1, reader_2));
+        let mut writer = lock.try_write().unwrap();
+        *writer += 1;
+        assert!(lock.try_read().is_err());
+        drop(writer);
+        assert_eq!(*block_on(lock.read()), 2);
+    }
+}
@@ -0,0 +1,302 @@
+use std::{
+    collections::VecDeque,
+    fmt::{Debug, Display},
+    future::Future,
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll, Waker},
+};
+
+/// Error returned when acquiring permits from a closed semaphore.
+#[derive(PartialEq, Clone, Debug, Copy)]
+pub struct AcquireError;
+
+impl Display for AcquireError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Semaphore closed")
+    }
+}
+
+impl std::error::Error for AcquireError {}
+
+/// Error returned by [`Semaphore::try_acquire`].
+#[derive(PartialEq, Clone, Debug, Copy)]
+pub enum TryAcquireError {
+    /// Not enough permits are available.
+    NoPermits,
+    /// The semaphore has been closed.
+    Closed,
+}
+
+impl Display for TryAcquireError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoPermits => write!(f, "No permits available"),
+            Self::Closed => write!(f, "Semaphore closed"),
+        }
+    }
+}
+
+impl std::error::Error for TryAcquireError {}
+
+/// An asynchronous counting semaphore, which limits the number of
+/// tasks accessing a resource at once.
+///
+/// Permits are granted in the order they are requested, so a task
+/// requesting many permits is not starved by tasks requesting few.
+///
+/// # Example
+///
+/// ```
+/// use rialight_concurrent::sync::Semaphore;
+///
+/// async fn download(semaphore: &Semaphore, url: &str) {
+///     // at most as many downloads as the semaphore has permits
+///     let _permit = semaphore.acquire().await.unwrap();
+///     // download `url`
+/// }
+/// ```
+pub struct Semaphore {
+    state: Mutex<SemaphoreState>,
+}
+
+struct SemaphoreState {
+    permits: usize,
+    closed: bool,
+    /// Waiting acquisitions, identified by a sequential number.
+    waiters: VecDeque<(u64, Waker)>,
+    next_waiter_id: u64,
+}
+
+impl SemaphoreState {
+    fn wake_first_waiter(&self) {
+        if let Some((_, waker)) = self.waiters.front() {
+            waker.wake_by_ref();
+        }
+    }
+}
+
+impl Semaphore {
+    /// The maximum number of permits a semaphore can hold.
+    pub const MAX_PERMITS: usize = usize::MAX >> 3;
+
+    /// Creates a semaphore with the given number of permits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `permits` exceeds [`Semaphore::MAX_PERMITS`].
+    pub fn new(permits: usize) -> Self {
+        assert!(permits <= Self::MAX_PERMITS, "rialight::concurrent::sync::Semaphore must be given at most MAX_PERMITS permits");
+        Self {
+            state: Mutex::new(SemaphoreState {
+                permits,
+                closed: false,
+                waiters: VecDeque::new(),
+                next_waiter_id: 0,
+            }),
+        }
+    }
+
+    /// Returns the number of permits currently available.
+    pub fn available_permits(&self) -> usize {
+        self.state.lock().unwrap().permits
+    }
+
+    /// Adds permits to the semaphore.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the total exceeds [`Semaphore::MAX_PERMITS`].
+    pub fn add_permits(&self, permits: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.permits += permits;
+        assert!(state.permits <= Self::MAX_PERMITS, "rialight::concurrent::sync::Semaphore must hold at most MAX_PERMITS permits");
+        state.wake_first_waiter();
+    }
+
+    /// Closes the semaphore, failing every pending
+    /// and future acquisition.
+    pub fn close(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        for (_, waker) in std::mem::take(&mut state.waiters) {
+            waker.wake();
+        }
+    }
+
+    /// Indicates whether the semaphore has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.state.lock().unwrap().closed
+    }
+
+    /// Waits until a permit is available and acquires it.
+    pub async fn acquire(&self) -> Result<SemaphorePermit<'_>, AcquireError> {
+        self.acquire_many(1).await
+    }
+
+    /// Waits until `permits` permits are available and acquires them.
+    pub async fn acquire_many(&self, permits: u32) -> Result<SemaphorePermit<'_>, AcquireError> {
+        Acquire { semaphore: self, permits, waiter_id: None }.await
+    }
+
+    /// Acquires a permit if one is available.
+    pub fn try_acquire(&self) -> Result<SemaphorePermit<'_>, TryAcquireError> {
+        self.try_acquire_many(1)
+    }
+
+    /// Acquires `permits` permits if they are available and
+    /// no other task is waiting for permits.
+    pub fn try_acquire_many(&self, permits: u32) -> Result<SemaphorePermit<'_>, TryAcquireError> {
+        let mut state = self.state.lock().unwrap();
+        if state.closed {
+            return Err(TryAcquireError::Closed);
+        }
+        if !state.waiters.is_empty() || state.permits < permits as usize {
+            return Err(TryAcquireError::NoPermits);
+        }
+        state.permits -= permits as usize;
+        Ok(SemaphorePermit { semaphore: self, permits })
+    }
+
+    fn release(&self, permits: u32) {
+        self.add_permits(permits as usize);
+    }
+}
+
+impl Debug for Semaphore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Semaphore").field("permits", &self.available_permits()).finish_non_exhaustive()
+    }
+}
+
+/// Permits acquired from a [`Semaphore`], which are
+/// released when the permit is dropped.
+#[must_use]
+pub struct SemaphorePermit<'a> {
+    semaphore: &'a Semaphore,
+    permits: u32,
+}
+
+impl SemaphorePermit<'_> {
+    /// Returns the number of permits held.
+    pub fn num_permits(&self) -> u32 {
+        self.permits
+    }
+
+    /// Drops the permit without releasing its permits
+    /// back to the semaphore.
+    pub fn forget(mut self) {
+        self.permits = 0;
+    }
+}
+
+impl Drop for SemaphorePermit<'_> {
+    fn drop(&mut self) {
+        if self.permits != 0 {
+            self.semaphore.release(self.permits);
+        }
+    }
+}
+
+impl Debug for SemaphorePermit<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SemaphorePermit").field("permits", &self.permits).finish_non_exhaustive()
+    }
+}
+
+struct Acquire<'a> {
+    semaphore: &'a Semaphore,
+    permits: u32,
+    waiter_id: Option<u64>,
+}
+
+impl<'a> Future for Acquire<'a> {
+    type Output = Result<SemaphorePermit<'a>, AcquireError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let semaphore = self.semaphore;
+        let mut state = semaphore.state.lock().unwrap();
+        if state.closed {
+            self.waiter_id = None;
+            return Poll::Ready(Err(AcquireError));
+        }
+        let first = match state.waiters.front() {
+            Some((id, _)) => Some(*id) == self.waiter_id,
+            None => true,
+        };
+        if first && state.permits >= self.permits as usize {
+            state.permits -= self.permits as usize;
+            if self.waiter_id.take().is_some() {
+                state.waiters.pop_front();
+                if state.permits != 0 {
+                    state.wake_first_waiter();
+                }
+            }
+            return Poll::Ready(Ok(SemaphorePermit { semaphore, permits: self.permits }));
+        }
+        match self.waiter_id {
+            Some(id) => {
+                let waiter = state.waiters.iter_mut().find(|(waiter_id, _)| *waiter_id == id).unwrap();
+                waiter.1.clone_from(cx.waker());
+            },
+            None => {
+                let id = state.next_waiter_id;
+                state.next_waiter_id += 1;
+                state.waiters.push_back((id, cx.waker().clone()));
+                self.waiter_id = Some(id);
+            },
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for Acquire<'_> {
+    fn drop(&mut self) {
+        let Some(id) = self.waiter_id else {
+            return;
+        };
+        let mut state = self.semaphore.state.lock().unwrap();
+        let Some(index) = state.waiters.iter().position(|(waiter_id, _)| *waiter_id == id) else {
+            return;
+        };
+        state.waiters.remove(index);
+        if index == 0 {
+            state.wake_first_waiter();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::{executor::block_on, join};
+    use std::cell::RefCell;
+
+    #[test]
+    fn fairness() {
+        let semaphore = Semaphore::new(2);
+        let permit = semaphore.try_acquire().unwrap();
+        block_on(async {
+            let order = RefCell::new(vec![]);
+            let many = async {
+                let _permits = semaphore.acquire_many(2).await.unwrap();
+                order.borrow_mut().push("many");
+            };
+            let one = async {
+                // yield so that `many` requests permits first
+                futures::pending!();
+                let _permit = semaphore.acquire().await.unwrap();
+                order.borrow_mut().push("one");
+            };
+            let release = async {
+                assert_eq!(semaphore.try_acquire_many(1).unwrap_err(), TryAcquireError::NoPermits);
+                drop(permit);
+            };
+            join!(many, one, release);
+            assert_eq!(*order.borrow(), ["many", "one"]);
+        });
+        assert_eq!(semaphore.available_permits(), 2);
+        semaphore.close();
+        assert_eq!(block_on(semaphore.acquire()).unwrap_err(), AcquireError);
+    }
+}
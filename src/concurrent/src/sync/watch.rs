@@ -0,0 +1,271 @@
+/*!
+Single-producer, multi-consumer channels that retain only the latest
+value, such as for sharing configuration or progress.
+
+Receivers are notified when the value changes, and intermediate
+values may be skipped.
+
+```
+use rialight_concurrent::sync::watch;
+
+async fn example_fn() {
+    let (sender, mut receiver) = watch::channel("loading");
+    sender.send("ready").unwrap();
+    receiver.changed().await.unwrap();
+    assert_eq!(*receiver.borrow_and_update(), "ready");
+}
+```
+*/
+
+use std::{
+    fmt::{Debug, Display},
+    ops::Deref,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Poll, Waker},
+};
+use super::{register_waker, wake_all};
+
+/// Error returned when sending to a channel without receivers.
+/// Contains the value that was not sent.
+#[derive(PartialEq, Clone, Copy)]
+pub struct SendError<T>(pub T);
+
+impl<T> Debug for SendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SendError(..)")
+    }
+}
+
+impl<T> Display for SendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Channel has no receivers")
+    }
+}
+
+impl<T> std::error::Error for SendError<T> {}
+
+/// Error returned by [`Receiver::changed`] when the sender has been dropped.
+#[derive(PartialEq, Clone, Debug, Copy)]
+pub struct RecvError;
+
+impl Display for RecvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Channel closed")
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+struct Channel<T> {
+    state: Mutex<ChannelState<T>>,
+}
+
+struct ChannelState<T> {
+    value: T,
+    version: u64,
+    sender_alive: bool,
+    receivers: usize,
+    wakers: Vec<Waker>,
+}
+
+impl<T> Channel<T> {
+    fn lock(&self) -> MutexGuard<'_, ChannelState<T>> {
+        self.state.lock().unwrap()
+    }
+}
+
+/// Creates a watch channel holding `initial` value.
+pub fn channel<T>(initial: T) -> (Sender<T>, Receiver<T>) {
+    let channel = Arc::new(Channel {
+        state: Mutex::new(ChannelState {
+            value: initial,
+            version: 0,
+            sender_alive: true,
+            receivers: 1,
+            wakers: vec![],
+        }),
+    });
+    (Sender { channel: Arc::clone(&channel) }, Receiver { channel, seen_version: 0 })
+}
+
+/// Reference to the value of a watch channel. The channel
+/// is locked while the reference is held, so it should
+/// not be held across `.await` points.
+pub struct Ref<'a, T> {
+    state: MutexGuard<'a, ChannelState<T>>,
+}
+
+impl<T> Deref for Ref<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.state.value
+    }
+}
+
+impl<T: Debug> Debug for Ref<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.state.value.fmt(f)
+    }
+}
+
+/// Sending half of a watch channel.
+pub struct Sender<T> {
+    channel: Arc<Channel<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Replaces the value and notifies the receivers.
+    /// Fails if there are no receivers.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        if self.channel.lock().receivers == 0 {
+            return Err(SendError(value));
+        }
+        self.send_replace(value);
+        Ok(())
+    }
+
+    /// Replaces the value and notifies the receivers, even
+    /// if there are none, returning the previous value.
+    pub fn send_replace(&self, value: T) -> T {
+        self.send_modify_with(|current| std::mem::replace(current, value))
+    }
+
+    /// Modifies the value in place and notifies the receivers.
+    pub fn send_modify<F: FnOnce(&mut T)>(&self, modify: F) {
+        self.send_modify_with(modify)
+    }
+
+    fn send_modify_with<R>(&self, modify: impl FnOnce(&mut T) -> R) -> R {
+        let mut state = self.channel.lock();
+        let result = modify(&mut state.value);
+        state.version += 1;
+        wake_all(&mut state.wakers);
+        result
+    }
+
+    /// Returns a reference to the current value.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        Ref { state: self.channel.lock() }
+    }
+
+    /// Creates a receiver that sees the current value as seen.
+    pub fn subscribe(&self) -> Receiver<T> {
+        let mut state = self.channel.lock();
+        state.receivers += 1;
+        Receiver { channel: Arc::clone(&self.channel), seen_version: state.version }
+    }
+
+    /// Returns the number of receivers.
+    pub fn receiver_count(&self) -> usize {
+        self.channel.lock().receivers
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.channel.lock();
+        state.sender_alive = false;
+        wake_all(&mut state.wakers);
+    }
+}
+
+impl<T> Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sender").finish_non_exhaustive()
+    }
+}
+
+/// Receiving half of a watch channel. Cloning the receiver creates
+/// a receiver that has seen the same version of the value.
+pub struct Receiver<T> {
+    channel: Arc<Channel<T>>,
+    seen_version: u64,
+}
+
+impl<T> Receiver<T> {
+    /// Returns a reference to the current value without
+    /// marking it as seen.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        Ref { state: self.channel.lock() }
+    }
+
+    /// Returns a reference to the current value and
+    /// marks it as seen.
+    pub fn borrow_and_update(&mut self) -> Ref<'_, T> {
+        let state = self.channel.lock();
+        self.seen_version = state.version;
+        Ref { state }
+    }
+
+    /// Indicates whether the value has changed since it was last seen.
+    pub fn has_changed(&self) -> bool {
+        self.channel.lock().version != self.seen_version
+    }
+
+    /// Waits until the value changes since it was last seen, then marks
+    /// it as seen. Fails if the sender is dropped without a change.
+    pub async fn changed(&mut self) -> Result<(), RecvError> {
+        std::future::poll_fn(|cx| {
+            let mut state = self.channel.lock();
+            if state.version != self.seen_version {
+                self.seen_version = state.version;
+                return Poll::Ready(Ok(()));
+            }
+            if !state.sender_alive {
+                return Poll::Ready(Err(RecvError));
+            }
+            register_waker(&mut state.wakers, cx.waker());
+            Poll::Pending
+        }).await
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        self.channel.lock().receivers += 1;
+        Self { channel: Arc::clone(&self.channel), seen_version: self.seen_version }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.channel.lock().receivers -= 1;
+    }
+}
+
+impl<T> Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::{executor::block_on, join};
+
+    #[test]
+    fn watch() {
+        let (sender, mut receiver) = channel(0);
+        assert!(!receiver.has_changed());
+        sender.send(1).unwrap();
+        sender.send_modify(|value| *value += 1);
+        assert!(receiver.has_changed());
+        assert_eq!(*receiver.borrow_and_update(), 2);
+        assert!(!receiver.has_changed());
+
+        block_on(async {
+            let producer = async {
+                sender.send(3).unwrap();
+                drop(sender);
+            };
+            let consumer = async {
+                assert_eq!(receiver.changed().await, Ok(()));
+                assert_eq!(*receiver.borrow(), 3);
+                assert_eq!(receiver.changed().await, Err(RecvError));
+            };
+            join!(consumer, producer);
+        });
+    }
+}
@@ -0,0 +1,400 @@
+use std::{
+    any::Any,
+    fmt::{Debug, Display},
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{mpsc as std_mpsc, Arc, Mutex, RwLock},
+};
+use rialight_util::{
+    bytes::Bytes,
+    serialization::{generic_deserialization::DeserializeOwned, json, Serialize},
+    timing::CancellationToken,
+};
+use crate::sync::{mpsc, oneshot};
+
+mod pool;
+pub use pool::{WorkerPool, JobContext, JobHandle};
+
+#[cfg(feature = "scripting")]
+mod script;
+#[cfg(feature = "scripting")]
+pub use script::{ScriptError, ScriptLimits};
+
+/// Error returned when a worker or job does not complete successfully.
+#[derive(PartialEq, Clone, Debug)]
+pub enum WorkerError {
+    /// The job was cancelled, or the worker was terminated.
+    Cancelled,
+    /// The worker or job panicked with the given message.
+    Panicked(String),
+    /// No job is registered with the given name.
+    UnknownJob(String),
+    /// The job failed with the given message.
+    Failed(String),
+}
+
+impl Display for WorkerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cancelled => write!(f, "Job cancelled"),
+            Self::Panicked(message) => write!(f, "Worker panicked: {message}"),
+            Self::UnknownJob(name) => write!(f, "Unknown job: {name}"),
+            Self::Failed(message) => write!(f, "Job failed: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for WorkerError {}
+
+impl From<MessageError> for WorkerError {
+    fn from(error: MessageError) -> Self {
+        Self::Failed(error.to_string())
+    }
+}
+
+/// Error returned when exchanging a message with a worker.
+#[derive(PartialEq, Clone, Debug)]
+pub enum MessageError {
+    /// The other side of the worker has closed the channel.
+    Closed,
+    /// The message could not be serialized or deserialized.
+    Serialization(String),
+}
+
+impl Display for MessageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Closed => write!(f, "Worker channel closed"),
+            Self::Serialization(message) => write!(f, "Message serialization failed: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for MessageError {}
+
+/// Serializes a message as JSON bytes.
+fn encode_message<T: Serialize + ?Sized>(message: &T) -> Result<Bytes, MessageError> {
+    json::serialize_as_byte_vec(message)
+        .map(Bytes::from)
+        .map_err(|error| MessageError::Serialization(error.to_string()))
+}
+
+/// Deserializes a message from JSON bytes.
+fn decode_message<T: DeserializeOwned>(message: &[u8]) -> Result<T, MessageError> {
+    json::deserialize_from_slice(message).map_err(|error| MessageError::Serialization(error.to_string()))
+}
+
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        return (*message).to_owned();
+    }
+    panic.downcast::<String>().map_or_else(|_| "unknown panic".to_owned(), |message| *message)
+}
+
+/// Spawns a background thread, with the default stack size unless
+/// `stack_size` is given.
+fn spawn_thread<F: FnOnce() + Send + 'static>(name: String, stack_size: Option<usize>, function: F) {
+    #[cfg(feature = "rialight_browser_export")]
+    {
+        drop((name, stack_size, function));
+        panic!("rialight::concurrent workers are not yet supported in the browser");
+    }
+    #[cfg(not(feature = "rialight_browser_export"))]
+    {
+        let mut builder = std::thread::Builder::new().name(name);
+        if let Some(stack_size) = stack_size {
+            builder = builder.stack_size(stack_size);
+        }
+        builder.spawn(function).expect("failed to spawn worker thread");
+    }
+}
+
+/// A worker running a function on a background thread, exchanging
+/// byte messages with the thread that spawned it.
+///
+/// Messages are [`Bytes`]; the `_serialized` methods exchange
+/// serializable values encoded as JSON instead.
+///
+/// The worker function receives a [`WorkerScope`], from which it
+/// receives messages by blocking its thread. The spawning side
+/// receives messages asynchronously, so it does not block
+/// the Rialight runtime.
+///
+/// # Example
+///
+/// ```
+/// use rialight_concurrent::Worker;
+///
+/// async fn example_fn() {
+///     let mut worker = Worker::spawn(|scope| {
+///         while let Some(message) = scope.recv() {
+///             let text: String = rialight_util::serialization::json::deserialize_from_slice(&message).unwrap();
+///             scope.post_serialized(&text.to_uppercase()).unwrap();
+///         }
+///     });
+///     worker.post_serialized("hello").unwrap();
+///     assert_eq!(worker.recv_deserialized::<String>().await.unwrap(), "HELLO");
+///     worker.join().await.unwrap();
+/// }
+/// ```
+pub struct Worker {
+    sender: Mutex<Option<std_mpsc::Sender<Bytes>>>,
+    receiver: mpsc::Receiver<Bytes>,
+    token: CancellationToken,
+    finished: oneshot::Receiver<Result<(), WorkerError>>,
+}
+
+impl Worker {
+    /// Spawns a worker running `function` on a background thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics on the browser export, which does not support workers yet.
+    pub fn spawn<F>(function: F) -> Self
+    where
+        F: FnOnce(WorkerScope) + Send + 'static,
+    {
+        Self::spawn_fallible(None, move |scope| {
+            function(scope);
+            Ok(())
+        })
+    }
+
+    fn spawn_fallible<F>(stack_size: Option<usize>, function: F) -> Self
+    where
+        F: FnOnce(WorkerScope) -> Result<(), WorkerError> + Send + 'static,
+    {
+        let (sender, inbox) = std_mpsc::channel();
+        let (outbox, receiver) = mpsc::unbounded_channel();
+        let (finished_sender, finished) = oneshot::channel();
+        let token = CancellationToken::new();
+        let scope = WorkerScope { inbox, outbox, token: token.clone() };
+        spawn_thread("rialight-worker".to_owned(), stack_size, move || {
+            let result = catch_unwind(AssertUnwindSafe(|| function(scope)))
+                .unwrap_or_else(|panic| Err(WorkerError::Panicked(panic_message(panic))));
+            let _ = finished_sender.send(result);
+        });
+        Self { sender: Mutex::new(Some(sender)), receiver, token, finished }
+    }
+
+    /// Posts a message to the worker.
+    pub fn post_message(&self, message: Bytes) -> Result<(), MessageError> {
+        let sender = self.sender.lock().unwrap();
+        let sender = sender.as_ref().ok_or(MessageError::Closed)?;
+        sender.send(message).map_err(|_| MessageError::Closed)
+    }
+
+    /// Serializes a value and posts it to the worker.
+    pub fn post_serialized<T: Serialize + ?Sized>(&self, message: &T) -> Result<(), MessageError> {
+        self.post_message(encode_message(message)?)
+    }
+
+    /// Receives the next message posted by the worker. Returns `None`
+    /// once the worker has finished and every message has been received.
+    pub async fn recv(&mut self) -> Option<Bytes> {
+        self.receiver.recv().await
+    }
+
+    /// Receives the next message posted by the worker and deserializes it.
+    pub async fn recv_deserialized<T: DeserializeOwned>(&mut self) -> Result<T, MessageError> {
+        decode_message(&self.recv().await.ok_or(MessageError::Closed)?)
+    }
+
+    /// Closes the channel of messages to the worker, so that
+    /// [`WorkerScope::recv`] returns `None` once the posted
+    /// messages have been received.
+    pub fn close(&self) {
+        self.sender.lock().unwrap().take();
+    }
+
+    /// Requests the worker to terminate: closes the channel of messages
+    /// to the worker and cancels its [`WorkerScope::cancellation_token`].
+    ///
+    /// The worker function is not interrupted; it should check
+    /// [`WorkerScope::is_terminated`] during long computations.
+    pub fn terminate(&self) {
+        self.close();
+        self.token.cancel();
+    }
+
+    /// Closes the channel of messages to the worker and waits
+    /// until the worker function returns.
+    pub async fn join(self) -> Result<(), WorkerError> {
+        self.close();
+        self.finished.await.unwrap_or(Err(WorkerError::Cancelled))
+    }
+}
+
+impl Debug for Worker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Worker").finish_non_exhaustive()
+    }
+}
+
+/// The worker side of a [`Worker`].
+pub struct WorkerScope {
+    inbox: std_mpsc::Receiver<Bytes>,
+    outbox: mpsc::UnboundedSender<Bytes>,
+    token: CancellationToken,
+}
+
+impl WorkerScope {
+    /// Receives the next message, blocking the worker thread until
+    /// one is posted. Returns `None` once the worker has been closed,
+    /// joined or terminated and every message has been received.
+    pub fn recv(&self) -> Option<Bytes> {
+        self.inbox.recv().ok()
+    }
+
+    /// Receives the next message if one has been posted.
+    pub fn try_recv(&self) -> Option<Bytes> {
+        self.inbox.try_recv().ok()
+    }
+
+    /// Receives the next message and deserializes it.
+    pub fn recv_deserialized<T: DeserializeOwned>(&self) -> Result<T, MessageError> {
+        decode_message(&self.recv().ok_or(MessageError::Closed)?)
+    }
+
+    /// Posts a message to the spawning side.
+    pub fn post_message(&self, message: Bytes) -> Result<(), MessageError> {
+        self.outbox.send(message).map_err(|_| MessageError::Closed)
+    }
+
+    /// Serializes a value and posts it to the spawning side.
+    pub fn post_serialized<T: Serialize + ?Sized>(&self, message: &T) -> Result<(), MessageError> {
+        self.post_message(encode_message(message)?)
+    }
+
+    /// Indicates whether the worker has been terminated.
+    pub fn is_terminated(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Returns the token cancelled when the worker is terminated.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+}
+
+impl Debug for WorkerScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkerScope").finish_non_exhaustive()
+    }
+}
+
+/// A fixed-length byte buffer shared between threads without copying,
+/// similar to the JavaScript `SharedArrayBuffer`. Cloning the buffer
+/// returns a handle to the same bytes.
+///
+/// # Example
+///
+/// ```
+/// use rialight_concurrent::{SharedBuffer, Worker};
+///
+/// async fn example_fn() {
+///     let buffer = SharedBuffer::new(4);
+///     let worker = Worker::spawn({
+///         let buffer = buffer.clone();
+///         move |_| buffer.write(|bytes| bytes.fill(1))
+///     });
+///     worker.join().await.unwrap();
+///     assert_eq!(buffer.to_bytes(), [1, 1, 1, 1][..]);
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct SharedBuffer {
+    bytes: Arc<RwLock<Box<[u8]>>>,
+}
+
+impl SharedBuffer {
+    /// Creates a buffer of `length` zero bytes.
+    pub fn new(length: usize) -> Self {
+        Self::from(vec![0; length])
+    }
+
+    /// Returns the length of the buffer.
+    pub fn len(&self) -> usize {
+        self.bytes.read().unwrap().len()
+    }
+
+    /// Indicates whether the buffer has zero length.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Calls `function` with shared access to the bytes.
+    pub fn read<R>(&self, function: impl FnOnce(&[u8]) -> R) -> R {
+        function(&self.bytes.read().unwrap())
+    }
+
+    /// Calls `function` with exclusive access to the bytes.
+    pub fn write<R>(&self, function: impl FnOnce(&mut [u8]) -> R) -> R {
+        function(&mut self.bytes.write().unwrap())
+    }
+
+    /// Copies the bytes into a [`Bytes`] value.
+    pub fn to_bytes(&self) -> Bytes {
+        self.read(Bytes::copy_from_slice)
+    }
+}
+
+impl From<Vec<u8>> for SharedBuffer {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self { bytes: Arc::new(RwLock::new(bytes.into_boxed_slice())) }
+    }
+}
+
+impl From<&[u8]> for SharedBuffer {
+    fn from(bytes: &[u8]) -> Self {
+        Self::from(bytes.to_vec())
+    }
+}
+
+impl Debug for SharedBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedBuffer").field("length", &self.len()).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn worker_messages() {
+        let mut worker = Worker::spawn(|scope| {
+            let mut sum = 0;
+            while let Ok(value) = scope.recv_deserialized::<u32>() {
+                sum += value;
+                scope.post_serialized(&sum).unwrap();
+            }
+        });
+        block_on(async {
+            for value in 1..=3 {
+                worker.post_serialized(&value).unwrap();
+            }
+            let mut sums = vec![];
+            for _ in 0..3 {
+                sums.push(worker.recv_deserialized::<u32>().await.unwrap());
+            }
+            assert_eq!(sums, [1, 3, 6]);
+            worker.join().await.unwrap();
+        });
+
+        let worker = Worker::spawn(|_| panic!("worker failure"));
+        assert_eq!(block_on(worker.join()), Err(WorkerError::Panicked("worker failure".into())));
+    }
+
+    #[test]
+    fn shared_buffer() {
+        let buffer = SharedBuffer::from(&b"abcd"[..]);
+        let worker = Worker::spawn({
+            let buffer = buffer.clone();
+            move |_| buffer.write(|bytes| bytes.make_ascii_uppercase())
+        });
+        block_on(worker.join()).unwrap();
+        assert_eq!(buffer.to_bytes(), &b"ABCD"[..]);
+        assert_eq!(buffer.len(), 4);
+    }
+}
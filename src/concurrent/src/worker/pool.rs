@@ -0,0 +1,283 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    future::Future,
+    panic::{catch_unwind, AssertUnwindSafe},
+    pin::Pin,
+    sync::{mpsc as std_mpsc, Arc, Mutex, RwLock},
+    task::{Context, Poll},
+};
+use rialight_util::{
+    bytes::Bytes,
+    serialization::{generic_deserialization::DeserializeOwned, Serialize},
+    timing::CancellationToken,
+};
+use crate::sync::{oneshot, watch};
+use super::{decode_message, encode_message, panic_message, spawn_thread, WorkerError};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+type RegisteredJob = Arc<dyn Fn(Bytes, &JobContext) -> Result<Bytes, WorkerError> + Send + Sync>;
+
+/// A pool of background threads running jobs.
+///
+/// Jobs are either closures, given to [`WorkerPool::run`], or functions
+/// registered by name with [`WorkerPool::register`] and called with byte
+/// messages through [`WorkerPool::call`]. Registered jobs only exchange
+/// bytes, so code written with them is portable to runtimes that cannot
+/// transfer closures to workers, such as Web Workers in the browser.
+///
+/// Each job receives a [`JobContext`] for reporting progress and checking
+/// cancellation, and is observed through a [`JobHandle`].
+///
+/// Dropping the pool lets the queued jobs finish, after which
+/// its threads exit.
+///
+/// # Example
+///
+/// ```
+/// use rialight_concurrent::{WorkerPool, WorkerError};
+///
+/// async fn example_fn() {
+///     let pool = WorkerPool::new(4);
+///     pool.register("sum", |input, context| {
+///         context.check_cancelled()?;
+///         Ok(vec![input.iter().map(|&byte| byte as u32).sum::<u32>() as u8].into())
+///     });
+///     let output = pool.call("sum", vec![1, 2, 3].into()).await.unwrap();
+///     assert_eq!(output[0], 6);
+/// }
+/// ```
+pub struct WorkerPool {
+    sender: std_mpsc::Sender<Job>,
+    thread_count: usize,
+    jobs: RwLock<HashMap<String, RegisteredJob>>,
+}
+
+impl WorkerPool {
+    /// Creates a pool of `thread_count` threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `thread_count` is zero, or on the browser export,
+    /// which does not support workers yet.
+    pub fn new(thread_count: usize) -> Self {
+        assert!(thread_count != 0, "rialight::concurrent::WorkerPool must be given non-zero thread count");
+        let (sender, receiver) = std_mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for i in 0..thread_count {
+            let receiver = Arc::clone(&receiver);
+            spawn_thread(format!("rialight-worker-pool-{i}"), None, move || {
+                loop {
+                    let job = receiver.lock().unwrap().recv();
+                    match job {
+                        Ok(job) => job(),
+                        Err(_) => break,
+                    }
+                }
+            });
+        }
+        Self { sender, thread_count, jobs: RwLock::new(HashMap::new()) }
+    }
+
+    /// Returns the number of threads of the pool.
+    pub fn thread_count(&self) -> usize {
+        self.thread_count
+    }
+
+    /// Registers a job function by name, replacing any
+    /// job function previously registered with that name.
+    pub fn register<F>(&self, name: impl Into<String>, job: F)
+    where
+        F: Fn(Bytes, &JobContext) -> Result<Bytes, WorkerError> + Send + Sync + 'static,
+    {
+        self.jobs.write().unwrap().insert(name.into(), Arc::new(job));
+    }
+
+    /// Runs a closure on the pool.
+    pub fn run<T, F>(&self, job: F) -> JobHandle<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&JobContext) -> Result<T, WorkerError> + Send + 'static,
+    {
+        let token = CancellationToken::new();
+        let (progress_sender, progress) = watch::channel(0.0);
+        let (result_sender, result) = oneshot::channel();
+        let context = JobContext { token: token.clone(), progress: progress_sender };
+        let job: Job = Box::new(move || {
+            let result = if context.is_cancelled() {
+                Err(WorkerError::Cancelled)
+            } else {
+                catch_unwind(AssertUnwindSafe(|| job(&context)))
+                    .unwrap_or_else(|panic| Err(WorkerError::Panicked(panic_message(panic))))
+            };
+            let _ = result_sender.send(result);
+        });
+        // the threads only exit once the sender is dropped
+        let _ = self.sender.send(job);
+        JobHandle { token, progress, result }
+    }
+
+    /// Calls a registered job function with a byte message.
+    pub fn call(&self, name: &str, input: Bytes) -> JobHandle<Bytes> {
+        self.call_with(name, move |job, context| job(input, context))
+    }
+
+    /// Calls a registered job function with a serializable value,
+    /// deserializing its output. Values are encoded as JSON.
+    pub fn call_serialized<I, O>(&self, name: &str, input: &I) -> JobHandle<O>
+    where
+        I: Serialize + ?Sized,
+        O: DeserializeOwned + Send + 'static,
+    {
+        let input = encode_message(input);
+        self.call_with(name, move |job, context| {
+            let output = job(input?, context)?;
+            Ok(decode_message(&output)?)
+        })
+    }
+
+    fn call_with<T, F>(&self, name: &str, call: F) -> JobHandle<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(RegisteredJob, &JobContext) -> Result<T, WorkerError> + Send + 'static,
+    {
+        let job = self.jobs.read().unwrap().get(name).cloned();
+        let name = name.to_owned();
+        self.run(move |context| {
+            let job = job.ok_or(WorkerError::UnknownJob(name))?;
+            call(job, context)
+        })
+    }
+}
+
+impl Default for WorkerPool {
+    /// Creates a pool with as many threads as the available parallelism.
+    fn default() -> Self {
+        Self::new(std::thread::available_parallelism().map_or(1, |count| count.get()))
+    }
+}
+
+impl Debug for WorkerPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkerPool").field("thread_count", &self.thread_count).finish_non_exhaustive()
+    }
+}
+
+/// Context of a job running on a [`WorkerPool`].
+pub struct JobContext {
+    token: CancellationToken,
+    progress: watch::Sender<f64>,
+}
+
+impl JobContext {
+    /// Reports the progress of the job, from `0.0` to `1.0`.
+    pub fn report_progress(&self, progress: f64) {
+        self.progress.send_replace(progress.clamp(0.0, 1.0));
+    }
+
+    /// Indicates whether the job has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Returns [`WorkerError::Cancelled`] if the job has been cancelled,
+    /// allowing long computations to stop with the `?` operator.
+    pub fn check_cancelled(&self) -> Result<(), WorkerError> {
+        if self.is_cancelled() { Err(WorkerError::Cancelled) } else { Ok(()) }
+    }
+
+    /// Returns the token cancelled when the job is cancelled.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+}
+
+impl Debug for JobContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JobContext").field("cancelled", &self.is_cancelled()).finish_non_exhaustive()
+    }
+}
+
+/// Handle to a job running on a [`WorkerPool`]. Awaiting it completes
+/// with the output of the job.
+pub struct JobHandle<T> {
+    token: CancellationToken,
+    progress: watch::Receiver<f64>,
+    result: oneshot::Receiver<Result<T, WorkerError>>,
+}
+
+impl<T> JobHandle<T> {
+    /// Cancels the job. A job that has not started completes with
+    /// [`WorkerError::Cancelled`] without running; a running job
+    /// observes the cancellation through its [`JobContext`].
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    /// Returns a receiver of the progress reported by the job.
+    pub fn progress(&self) -> watch::Receiver<f64> {
+        self.progress.clone()
+    }
+}
+
+impl<T> Future for JobHandle<T> {
+    type Output = Result<T, WorkerError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.result).poll(cx).map(|result| result.unwrap_or(Err(WorkerError::Cancelled)))
+    }
+}
+
+impl<T> Debug for JobHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JobHandle").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn jobs() {
+        let pool = WorkerPool::new(2);
+        let handle = pool.run(|context| {
+            for i in 1..=4 {
+                context.report_progress(i as f64 / 4.0);
+            }
+            Ok("done")
+        });
+        let mut progress = handle.progress();
+        assert_eq!(block_on(handle), Ok("done"));
+        assert_eq!(*progress.borrow_and_update(), 1.0);
+
+        pool.register("double", |input, _| Ok(input.iter().map(|byte| byte * 2).collect::<Vec<_>>().into()));
+        assert_eq!(block_on(pool.call("double", vec![1, 2].into())), Ok(Bytes::from(vec![2, 4])));
+        assert_eq!(block_on(pool.call("triple", Bytes::new())), Err(WorkerError::UnknownJob("triple".into())));
+
+        pool.register("length", |input, _| Ok(input.len().to_string().into()));
+        let length: Result<usize, _> = block_on(pool.call_serialized("length", "text"));
+        assert_eq!(length, Ok(6));
+
+        let handle = pool.run(|_| -> Result<(), _> { panic!("job failure") });
+        assert_eq!(block_on(handle), Err(WorkerError::Panicked("job failure".into())));
+    }
+
+    #[test]
+    fn cancellation() {
+        let pool = WorkerPool::new(1);
+        let (unblock, blocked) = std_mpsc::channel::<()>();
+        let blocking = pool.run(move |context| {
+            blocked.recv().unwrap();
+            context.check_cancelled()
+        });
+        let queued = pool.run(|_| Ok(()));
+        queued.cancel();
+        blocking.cancel();
+        unblock.send(()).unwrap();
+        assert_eq!(block_on(blocking), Err(WorkerError::Cancelled));
+        assert_eq!(block_on(queued), Err(WorkerError::Cancelled));
+    }
+}
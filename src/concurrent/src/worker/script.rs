@@ -0,0 +1,420 @@
+use std::{
+    fmt::Display,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+use rhai::{Array, CallFnOptions, Dynamic, Engine, EvalAltResult, FnPtr, ParseError, Scope, AST, INT};
+use rialight_util::{reg_exp::RegExp, serialization::json, timing::Duration};
+use super::{decode_message, encode_message, Worker, WorkerError, WorkerScope};
+
+/// Error returned when a worker script cannot be compiled.
+#[derive(PartialEq, Clone, Debug)]
+pub struct ScriptError {
+    message: String,
+    line: Option<usize>,
+    column: Option<usize>,
+}
+
+impl ScriptError {
+    /// Returns the description of the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the line of the error, counted from 1.
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// Returns the column of the error, counted from 1.
+    pub fn column(&self) -> Option<usize> {
+        self.column
+    }
+}
+
+impl Display for ScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "{} (line {line}, column {column})", self.message),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+impl From<ParseError> for ScriptError {
+    fn from(error: ParseError) -> Self {
+        Self { message: error.0.to_string(), line: error.1.line(), column: error.1.position() }
+    }
+}
+
+/// Sandboxing limits of a worker script, which prevent
+/// scripts such as user mods from hanging the application or
+/// exhausting its memory.
+///
+/// The operation and run time limits apply separately to the evaluation
+/// of the script and to each call of its `on_message` function. The run
+/// time includes the time spent in `sleep`.
+///
+/// The size limits apply to each value, including the elements and
+/// strings nested in arrays and maps. The memory limit applies to the
+/// values of all variables together, including map keys and the values
+/// captured by closures, which the size limits do not count. Memory use
+/// is estimated and checked periodically as variables are accessed, so
+/// a script may exceed the limit briefly.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptLimits {
+    /// Maximum number of operations, or zero for no limit. Default: 1,000,000.
+    pub max_operations: u64,
+    /// Maximum wall-clock run time, or zero for no limit. Default: 10 seconds.
+    pub max_run_time: Duration,
+    /// Maximum estimated size of the values of all variables, in bytes,
+    /// or zero for no limit. Default: 64 MiB.
+    pub max_memory: usize,
+    /// Maximum depth of nested function calls. Default: 64.
+    pub max_call_depth: usize,
+    /// Maximum depth of nested expressions. Default: 64.
+    pub max_expression_depth: usize,
+    /// Maximum length of a string, in bytes. Default: 1 MiB.
+    pub max_string_size: usize,
+    /// Maximum number of elements of an array, including the
+    /// elements of nested arrays. Default: 65,536.
+    pub max_array_size: usize,
+    /// Maximum number of properties of a map, including the
+    /// properties of nested maps. Default: 65,536.
+    pub max_map_size: usize,
+}
+
+impl Default for ScriptLimits {
+    fn default() -> Self {
+        Self {
+            max_operations: 1_000_000,
+            max_run_time: Duration::from_secs(10),
+            max_memory: 64 * 1024 * 1024,
+            max_call_depth: 64,
+            max_expression_depth: 64,
+            max_string_size: 1024 * 1024,
+            max_array_size: 65_536,
+            max_map_size: 65_536,
+        }
+    }
+}
+
+impl ScriptLimits {
+    fn engine(&self) -> Engine {
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(self.max_operations)
+            .set_max_call_levels(self.max_call_depth)
+            .set_max_expr_depths(self.max_expression_depth, self.max_expression_depth)
+            .set_max_string_size(self.max_string_size)
+            .set_max_array_size(self.max_array_size)
+            .set_max_map_size(self.max_map_size);
+        engine
+    }
+}
+
+impl Worker {
+    /// Creates a worker evaluating a [Rhai](https://rhai.rs/book) script
+    /// with the default [`ScriptLimits`]. Usually the script is given by
+    /// the [`worker!`](crate::worker) macro.
+    ///
+    /// The script is evaluated on a background thread. If it defines an
+    /// `on_message(message)` function, the function is then called for
+    /// each message posted to the worker, until the worker is closed.
+    /// Messages are exchanged as serialized values, so the spawning side
+    /// uses [`Worker::post_serialized`] and [`Worker::recv_deserialized`].
+    ///
+    /// Besides the Rhai standard library, scripts can call:
+    ///
+    /// - `post_message(value)`, which posts a value to the spawning side.
+    /// - `is_terminated()`.
+    /// - `json_parse(text)` and `json_stringify(value)`.
+    /// - `regex_is_match(pattern, text)`, `regex_find_all(pattern, text)`
+    ///   and `regex_replace_all(pattern, text, replacement)`.
+    /// - `elapsed()`, the milliseconds elapsed since the worker started,
+    ///   and `sleep(milliseconds)`.
+    ///
+    /// A script exceeding its limits fails with [`WorkerError::Failed`],
+    /// and a script running when the worker is terminated fails with
+    /// [`WorkerError::Cancelled`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rialight_concurrent::Worker;
+    ///
+    /// async fn example_fn() {
+    ///     let mut worker = Worker::new(r#"
+    ///         fn on_message(message) {
+    ///             post_message(message.x + message.y);
+    ///         }
+    ///     "#).unwrap();
+    ///     worker.post_serialized(&rialight_util::serialization::json::json!({ "x": 1, "y": 2 })).unwrap();
+    ///     assert_eq!(worker.recv_deserialized::<i64>().await.unwrap(), 3);
+    /// }
+    /// ```
+    pub fn new(source: &str) -> Result<Self, ScriptError> {
+        Self::with_limits(source, ScriptLimits::default())
+    }
+
+    /// Creates a worker evaluating a Rhai script with the given limits.
+    /// See [`Worker::new`] for details.
+    pub fn with_limits(source: &str, limits: ScriptLimits) -> Result<Self, ScriptError> {
+        let ast = limits.engine().compile(source)?;
+        Ok(Self::spawn_fallible(Some(SCRIPT_STACK_SIZE), move |scope| run_script(ast, limits, scope)))
+    }
+}
+
+/// Stack size of the threads running scripts, which evaluate nested
+/// function calls and expressions recursively.
+const SCRIPT_STACK_SIZE: usize = 16 * 1024 * 1024;
+
+/// Interval, in operations or variable accesses, between checks of
+/// the run time and memory limits.
+const CHECK_INTERVAL: u64 = 64;
+
+/// Maximum depth of nested values whose size is estimated, which also
+/// stops at closures that capture themselves.
+const MAX_SIZE_DEPTH: usize = 64;
+
+const TIME_LIMIT_EXCEEDED: &str = "script run time limit exceeded";
+const MEMORY_LIMIT_EXCEEDED: &str = "script memory limit exceeded";
+
+/// Deadline of the current run of a script, restarted before its
+/// evaluation and before each call of its `on_message` function.
+#[derive(Clone, Default)]
+struct Deadline(Arc<Mutex<Option<Instant>>>);
+
+impl Deadline {
+    fn restart(&self, max_run_time: Duration) {
+        *self.0.lock().unwrap() = (!max_run_time.is_zero()).then(|| Instant::now() + max_run_time);
+    }
+
+    /// Indicates whether the deadline is reached after waiting for `duration`.
+    fn exceeded_after(&self, duration: Duration) -> bool {
+        self.0.lock().unwrap().is_some_and(|deadline| {
+            Instant::now().checked_add(duration).is_none_or(|time| time > deadline)
+        })
+    }
+}
+
+fn run_script(ast: AST, limits: ScriptLimits, scope: WorkerScope) -> Result<(), WorkerError> {
+    let deadline = Deadline::default();
+    let mut engine = limits.engine();
+    register_checks(&mut engine, &limits, &scope, &deadline);
+    register_api(&mut engine, &scope, &deadline);
+    let mut script_scope = Scope::new();
+    deadline.restart(limits.max_run_time);
+    engine.run_ast_with_scope(&mut script_scope, &ast).map_err(script_failure)?;
+    if !ast.iter_functions().any(|function| function.name == "on_message" && function.params.len() == 1) {
+        return Ok(());
+    }
+    while let Some(message) = scope.recv() {
+        let message: Dynamic = decode_message(&message)?;
+        let options = CallFnOptions::new().eval_ast(false).rewind_scope(false);
+        deadline.restart(limits.max_run_time);
+        let _ = engine.call_fn_with_options::<Dynamic>(options, &mut script_scope, &ast, "on_message", (message,))
+            .map_err(script_failure)?;
+    }
+    Ok(())
+}
+
+fn script_failure(error: Box<EvalAltResult>) -> WorkerError {
+    match error.as_ref() {
+        EvalAltResult::ErrorTerminated(token, _) if token.is_unit() => WorkerError::Cancelled,
+        EvalAltResult::ErrorTerminated(token, _) => WorkerError::Failed(token.to_string()),
+        error => WorkerError::Failed(error.to_string()),
+    }
+}
+
+fn reg_exp(pattern: &str) -> Result<RegExp, Box<EvalAltResult>> {
+    RegExp::new(pattern).map_err(|error| error.to_string().into())
+}
+
+/// Estimated size of the variables of a script, measured as they
+/// are accessed.
+#[derive(Default)]
+struct MemoryUsage {
+    accesses: u64,
+    /// Size of the variables of each function call level.
+    levels: Vec<Option<usize>>,
+}
+
+/// Returns the estimated size of a value, in bytes, including its nested
+/// values, the keys of maps and the values captured by closures.
+fn value_size(value: &Dynamic, depth: usize) -> usize {
+    let mut size = std::mem::size_of::<Dynamic>();
+    if depth == 0 {
+        return size;
+    }
+    if let Ok(string) = value.as_immutable_string_ref() {
+        size += string.len();
+    } else if let Ok(blob) = value.as_blob_ref() {
+        size += blob.len();
+    } else if let Ok(array) = value.as_array_ref() {
+        size += array.iter().map(|value| value_size(value, depth - 1)).sum::<usize>();
+    } else if let Ok(map) = value.as_map_ref() {
+        size += map.iter().map(|(key, value)| key.len() + value_size(value, depth - 1)).sum::<usize>();
+    } else if let Some(function) = value.read_lock::<FnPtr>() {
+        size += function.iter_curry().map(|value| value_size(value, depth - 1)).sum::<usize>();
+    }
+    size
+}
+
+/// Registers the checks of termination and of the run time and memory limits.
+fn register_checks(engine: &mut Engine, limits: &ScriptLimits, scope: &WorkerScope, deadline: &Deadline) {
+    let (token, deadline) = (scope.cancellation_token(), deadline.clone());
+    engine.on_progress(move |operations| {
+        if token.is_cancelled() {
+            Some(Dynamic::UNIT)
+        } else if operations % CHECK_INTERVAL == 0 && deadline.exceeded_after(Duration::ZERO) {
+            Some(TIME_LIMIT_EXCEEDED.into())
+        } else {
+            None
+        }
+    });
+
+    if limits.max_memory != 0 {
+        let (max_memory, usage) = (limits.max_memory, Mutex::new(MemoryUsage::default()));
+        // each function call has its own scope, so the size of the variables
+        // of each call level is kept until the call returns
+        // (the variable resolver is stable, although Rhai marks it as volatile)
+        #[allow(deprecated)]
+        engine.on_var(move |_, _, context| {
+            let mut usage = usage.lock().unwrap();
+            let level = context.call_level();
+            usage.levels.resize(level + 1, None);
+            usage.accesses += 1;
+            if usage.levels[level].is_none() || usage.accesses % CHECK_INTERVAL == 0 {
+                usage.levels[level] = Some(context.scope().iter_raw().map(|(_, _, value)| value_size(value, MAX_SIZE_DEPTH)).sum());
+                if usage.levels.iter().flatten().sum::<usize>() > max_memory {
+                    return Err(MEMORY_LIMIT_EXCEEDED.into());
+                }
+            }
+            Ok(None)
+        });
+    }
+}
+
+fn register_api(engine: &mut Engine, scope: &WorkerScope, deadline: &Deadline) {
+    let outbox = scope.outbox.clone();
+    engine.register_fn("post_message", move |message: Dynamic| -> Result<(), Box<EvalAltResult>> {
+        let message = encode_message(&message).map_err(|error| error.to_string())?;
+        outbox.send(message).map_err(|_| "worker channel closed".into())
+    });
+    let token = scope.cancellation_token();
+    engine.register_fn("is_terminated", move || token.is_cancelled());
+
+    engine.register_fn("json_parse", |text: &str| -> Result<Dynamic, Box<EvalAltResult>> {
+        json::deserialize(text).map_err(|error| error.to_string().into())
+    });
+    engine.register_fn("json_stringify", |value: Dynamic| -> Result<String, Box<EvalAltResult>> {
+        json::serialize(&value).map_err(|error| error.to_string().into())
+    });
+
+    engine.register_fn("regex_is_match", |pattern: &str, text: &str| -> Result<bool, Box<EvalAltResult>> {
+        Ok(reg_exp(pattern)?.is_match(text))
+    });
+    engine.register_fn("regex_find_all", |pattern: &str, text: &str| -> Result<Array, Box<EvalAltResult>> {
+        Ok(reg_exp(pattern)?.find_iter(text).map(|found| found.as_str().into()).collect())
+    });
+    engine.register_fn("regex_replace_all", |pattern: &str, text: &str, replacement: &str| -> Result<String, Box<EvalAltResult>> {
+        Ok(reg_exp(pattern)?.replace_all(text, replacement).into_owned())
+    });
+
+    let start = Instant::now();
+    engine.register_fn("elapsed", move || start.elapsed().as_millis() as INT);
+    let (token, deadline) = (scope.cancellation_token(), deadline.clone());
+    engine.register_fn("sleep", move |milliseconds: INT| -> Result<(), Box<EvalAltResult>> {
+        let mut remaining = Duration::from_millis(milliseconds.max(0) as u64);
+        if deadline.exceeded_after(remaining) {
+            return Err(TIME_LIMIT_EXCEEDED.into());
+        }
+        // sleep in steps so that termination is observed
+        while !remaining.is_zero() && !token.is_cancelled() {
+            let step = remaining.min(Duration::from_millis(10));
+            std::thread::sleep(step);
+            remaining -= step;
+        }
+        Ok(())
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::worker;
+    use futures::executor::block_on;
+
+    #[test]
+    fn script_messages() {
+        let mut worker = worker!("test_worker.rhai").unwrap();
+        block_on(async {
+            worker.post_serialized("a1 b22 c333").unwrap();
+            assert_eq!(worker.recv_deserialized::<Vec<String>>().await.unwrap(), ["1", "22", "333"]);
+            worker.post_serialized(&[1, 2]).unwrap();
+            assert_eq!(worker.recv_deserialized::<String>().await.unwrap(), "[1,2]");
+            worker.join().await.unwrap();
+        });
+
+        assert_eq!(Worker::new("let x = ;").unwrap_err().line(), Some(1));
+    }
+
+    #[test]
+    fn sandboxing() {
+        let limits = ScriptLimits { max_operations: 1000, ..Default::default() };
+        let worker = Worker::with_limits("loop {}", limits).unwrap();
+        assert!(matches!(block_on(worker.join()), Err(WorkerError::Failed(_))));
+
+        let worker = Worker::with_limits("loop { sleep(1); }", ScriptLimits { max_operations: 0, ..Default::default() }).unwrap();
+        worker.terminate();
+        assert_eq!(block_on(worker.join()), Err(WorkerError::Cancelled));
+
+        let worker = Worker::new(r#"let s = "x"; loop { s += s; }"#).unwrap();
+        assert!(matches!(block_on(worker.join()), Err(WorkerError::Failed(_))));
+
+        // nested calls up to the call depth limit fit in the stack
+        let worker = Worker::new("fn nest(n) { if n > 0 { 1 + (1 + (1 + nest(n - 1))) } else { 0 } } nest(62);").unwrap();
+        assert_eq!(block_on(worker.join()), Ok(()));
+        let worker = Worker::new("fn nest(n) { nest(n + 1) } nest(0);").unwrap();
+        assert!(matches!(block_on(worker.join()), Err(WorkerError::Failed(_))));
+    }
+
+    fn failure(worker: Worker) -> String {
+        match block_on(worker.join()) {
+            Err(WorkerError::Failed(message)) => message,
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn run_time_limit() {
+        let worker = Worker::new("sleep(9223372036854775807);").unwrap();
+        assert!(failure(worker).contains(TIME_LIMIT_EXCEEDED));
+
+        let limits = ScriptLimits { max_operations: 0, max_run_time: Duration::from_millis(100), ..Default::default() };
+        let worker = Worker::with_limits("loop { sleep(1); }", limits.clone()).unwrap();
+        assert!(failure(worker).contains(TIME_LIMIT_EXCEEDED));
+        let worker = Worker::with_limits("loop {}", limits).unwrap();
+        assert!(failure(worker).contains(TIME_LIMIT_EXCEEDED));
+    }
+
+    #[test]
+    fn memory_limit() {
+        let limits = ScriptLimits { max_memory: 16 * 1024 * 1024, ..Default::default() };
+        let scripts = [
+            // map keys and the values captured by closures are not
+            // counted by the size limits, nor is the total of all variables
+            "let m = #{}; for i in 0..1000 { m[`${i}${s}`] = i; }",
+            "let a = []; for i in 0..1000 { let t = s + i; a.push(|| t); }",
+            "fn nest(s, n) { let a = [#{ s: s + n }]; if n > 0 { nest(s, n - 1) } } nest(s, 60);",
+        ];
+        for script in scripts {
+            let source = format!(r#"let s = "x"; while s.len() < 500000 {{ s += s; }} {script}"#);
+            let worker = Worker::with_limits(&source, limits.clone()).unwrap();
+            assert!(failure(worker).contains(MEMORY_LIMIT_EXCEEDED), "{script}");
+        }
+    }
+}
@@ -2,8 +2,5 @@
 The Rialight file system API.
 */
 
-use rialight_prelude::*;
-use rialight_util::file_paths::{Path, PlatformPathVariant};
-
 mod error;
 pub use error::FileError;
@@ -0,0 +1,29 @@
+/*!
+The Rialight internationalization API.
+
+# Locales
+
+A [`Locale`] identifies a language, optionally with a script and a region,
+and gives its text direction.
+
+# Number formatting
+
+[`NumberFormat`] formats and parses numbers according to the conventions
+of a locale, in decimal, percent, currency, compact and scientific styles.
+
+```
+use rialight_intl::{Locale, NumberFormat, NumberFormatOptions};
+
+let format = NumberFormat::new(&Locale::parse("hi-IN").unwrap(), NumberFormatOptions::default());
+assert_eq!(format.format(&12345678), "1,23,45,678");
+assert_eq!(format.parse("1,23,456.5").unwrap().to_string(), "123456.5");
+```
+*/
+
+mod locale;
+pub use locale::{Locale, LocaleError, TextDirection};
+
+mod number_data;
+
+mod number_format;
+pub use number_format::{NumberFormat, NumberFormatOptions, NumberStyle, FormattableNumber, NumberParseError};
@@ -0,0 +1,140 @@
+use std::{fmt::Display, str::FromStr};
+use rialight_util::serialization::{Deserialize, Deserializer, Serialize, Serializer, generic_deserialization};
+
+/// A locale, identified by a [BCP 47](https://www.rfc-editor.org/info/bcp47)
+/// language tag consisting of a language, an optional script and an optional
+/// region, such as `en`, `pt-BR` or `zh-Hant-TW`.
+///
+/// Subtags are case-normalized and may also be separated by `_`.
+/// Variants and extensions are not supported.
+///
+/// ```
+/// use rialight_intl::{Locale, TextDirection};
+///
+/// let locale: Locale = "ar_eg".parse().unwrap();
+/// assert_eq!(locale.to_string(), "ar-EG");
+/// assert_eq!(locale.text_direction(), TextDirection::RightToLeft);
+/// ```
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub struct Locale {
+    language: String,
+    script: Option<String>,
+    region: Option<String>,
+}
+
+impl Locale {
+    /// Parses a language tag.
+    pub fn parse(tag: &str) -> Result<Self, LocaleError> {
+        let error = || LocaleError { tag: tag.to_owned() };
+        let mut subtags = tag.split(['-', '_']).peekable();
+        let language = subtags.next().filter(|language| matches!(language.len(), 2..=3 | 5..=8) && language.bytes().all(|byte| byte.is_ascii_alphabetic())).ok_or_else(error)?;
+        let script = subtags.next_if(|script| script.len() == 4 && script.bytes().all(|byte| byte.is_ascii_alphabetic()));
+        let region = subtags.next_if(|region| {
+            (region.len() == 2 && region.bytes().all(|byte| byte.is_ascii_alphabetic()))
+            || (region.len() == 3 && region.bytes().all(|byte| byte.is_ascii_digit()))
+        });
+        if subtags.next().is_some() {
+            return Err(error());
+        }
+        Ok(Self {
+            language: language.to_ascii_lowercase(),
+            script: script.map(|script| script[..1].to_ascii_uppercase() + &script[1..].to_ascii_lowercase()),
+            region: region.map(str::to_ascii_uppercase),
+        })
+    }
+
+    /// Returns the language subtag, such as `en`.
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Returns the script subtag, such as `Hant`.
+    pub fn script(&self) -> Option<&str> {
+        self.script.as_deref()
+    }
+
+    /// Returns the region subtag, such as `US`.
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+
+    /// Returns the direction in which text of the locale is written.
+    pub fn text_direction(&self) -> TextDirection {
+        let right_to_left = match self.script() {
+            Some(script) => matches!(script, "Arab" | "Hebr" | "Thaa" | "Syrc" | "Nkoo" | "Adlm" | "Rohg" | "Mand" | "Samr"),
+            None => matches!(self.language(), "ar" | "he" | "fa" | "ur" | "ps" | "yi" | "dv" | "sd" | "ug" | "ckb" | "ku" | "syr"),
+        };
+        if right_to_left { TextDirection::RightToLeft } else { TextDirection::LeftToRight }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = LocaleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.language)?;
+        if let Some(script) = &self.script {
+            write!(f, "-{script}")?;
+        }
+        if let Some(region) = &self.region {
+            write!(f, "-{region}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Locale {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Locale {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tag = String::deserialize(deserializer)?;
+        Self::parse(&tag).map_err(generic_deserialization::Error::custom)
+    }
+}
+
+/// The direction in which text is written.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum TextDirection {
+    LeftToRight,
+    RightToLeft,
+}
+
+/// Error returned when parsing an invalid language tag.
+#[derive(PartialEq, Clone, Debug)]
+pub struct LocaleError {
+    tag: String,
+}
+
+impl Display for LocaleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid language tag \"{}\"", self.tag)
+    }
+}
+
+impl std::error::Error for LocaleError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let locale = Locale::parse("ZH-hant-tw").unwrap();
+        assert_eq!((locale.language(), locale.script(), locale.region()), ("zh", Some("Hant"), Some("TW")));
+        assert_eq!(Locale::parse("es-419").unwrap().to_string(), "es-419");
+        assert_eq!(Locale::parse("fa").unwrap().text_direction(), TextDirection::RightToLeft);
+        assert_eq!(Locale::parse("az-Latn").unwrap().text_direction(), TextDirection::LeftToRight);
+        assert!(Locale::parse("").is_err());
+        assert!(Locale::parse("en-US-x-private").is_err());
+        assert_eq!(Locale::parse("e1").unwrap_err().to_string(), "invalid language tag \"e1\"");
+    }
+}
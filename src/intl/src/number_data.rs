@@ -0,0 +1,213 @@
+//! Number formatting data of the supported locales, following the
+//! [Unicode CLDR](https://cldr.unicode.org).
+
+use crate::Locale;
+
+/// Number symbols and patterns of a locale.
+///
+/// In patterns, `#` stands for the formatted number and `¤`
+/// for the currency symbol.
+pub(crate) struct NumberSymbols {
+    pub decimal: &'static str,
+    pub group: &'static str,
+    pub primary_grouping: usize,
+    pub secondary_grouping: usize,
+    /// Minimum number of digits before the first group separator.
+    pub minimum_grouping_digits: usize,
+    pub percent_pattern: &'static str,
+    pub currency_pattern: &'static str,
+    /// Compact suffixes by power of ten, in ascending order.
+    pub compact: &'static [(u32, &'static str)],
+    /// Currency code and symbol used by the locale for its own currency.
+    pub local_currency: Option<(&'static str, &'static str)>,
+}
+
+const ROOT: NumberSymbols = NumberSymbols {
+    decimal: ".",
+    group: ",",
+    primary_grouping: 3,
+    secondary_grouping: 3,
+    minimum_grouping_digits: 1,
+    percent_pattern: "#%",
+    currency_pattern: "¤#",
+    compact: &[(3, "K"), (6, "M"), (9, "G"), (12, "T")],
+    local_currency: None,
+};
+
+const EN: NumberSymbols = NumberSymbols {
+    compact: &[(3, "K"), (6, "M"), (9, "B"), (12, "T")],
+    ..ROOT
+};
+
+const EN_IN: NumberSymbols = NumberSymbols {
+    secondary_grouping: 2,
+    compact: &[(3, "K"), (5, "L"), (7, "Cr")],
+    local_currency: Some(("INR", "₹")),
+    ..EN
+};
+
+const HI: NumberSymbols = NumberSymbols {
+    compact: &[(3, "\u{a0}हज़ार"), (5, "\u{a0}लाख"), (7, "\u{a0}क॰"), (9, "\u{a0}अ॰"), (11, "\u{a0}ख॰")],
+    ..EN_IN
+};
+
+const DE: NumberSymbols = NumberSymbols {
+    decimal: ",",
+    group: ".",
+    percent_pattern: "#\u{a0}%",
+    currency_pattern: "#\u{a0}¤",
+    compact: &[(6, "\u{a0}Mio."), (9, "\u{a0}Mrd."), (12, "\u{a0}Bio.")],
+    ..ROOT
+};
+
+const DE_CH: NumberSymbols = NumberSymbols {
+    decimal: ".",
+    group: "’",
+    currency_pattern: "¤\u{a0}#",
+    ..DE
+};
+
+const FR: NumberSymbols = NumberSymbols {
+    group: "\u{202f}",
+    percent_pattern: "#\u{202f}%",
+    compact: &[(3, "\u{a0}k"), (6, "\u{a0}M"), (9, "\u{a0}Md"), (12, "\u{a0}Bn")],
+    ..DE
+};
+
+const ES: NumberSymbols = NumberSymbols {
+    minimum_grouping_digits: 2,
+    compact: &[(3, "\u{a0}mil"), (6, "\u{a0}M"), (12, "\u{a0}B")],
+    ..DE
+};
+
+const IT: NumberSymbols = NumberSymbols {
+    percent_pattern: "#%",
+    compact: &[(6, "\u{a0}Mln"), (9, "\u{a0}Mrd"), (12, "\u{a0}Bln")],
+    ..DE
+};
+
+const PT: NumberSymbols = NumberSymbols {
+    percent_pattern: "#%",
+    currency_pattern: "¤\u{a0}#",
+    compact: &[(3, "\u{a0}mil"), (6, "\u{a0}mi"), (9, "\u{a0}bi"), (12, "\u{a0}tri")],
+    local_currency: Some(("BRL", "R$")),
+    ..DE
+};
+
+const PT_PT: NumberSymbols = NumberSymbols {
+    group: "\u{a0}",
+    minimum_grouping_digits: 2,
+    currency_pattern: "#\u{a0}¤",
+    compact: &[(3, "\u{a0}mil"), (6, "\u{a0}M"), (9, "\u{a0}mM"), (12, "\u{a0}Bi")],
+    local_currency: None,
+    ..PT
+};
+
+const NL: NumberSymbols = NumberSymbols {
+    percent_pattern: "#%",
+    currency_pattern: "¤\u{a0}#",
+    compact: &[(3, "K"), (6, "\u{a0}mln."), (9, "\u{a0}mld."), (12, "\u{a0}bln.")],
+    ..DE
+};
+
+const RU: NumberSymbols = NumberSymbols {
+    group: "\u{a0}",
+    compact: &[(3, "\u{a0}тыс."), (6, "\u{a0}млн"), (9, "\u{a0}млрд"), (12, "\u{a0}трлн")],
+    local_currency: Some(("RUB", "₽")),
+    ..DE
+};
+
+const PL: NumberSymbols = NumberSymbols {
+    group: "\u{a0}",
+    minimum_grouping_digits: 2,
+    percent_pattern: "#%",
+    compact: &[(3, "\u{a0}tys."), (6, "\u{a0}mln"), (9, "\u{a0}mld"), (12, "\u{a0}bln")],
+    local_currency: Some(("PLN", "zł")),
+    ..DE
+};
+
+const SV: NumberSymbols = NumberSymbols {
+    group: "\u{a0}",
+    compact: &[(3, "\u{a0}tn"), (6, "\u{a0}mn"), (9, "\u{a0}md"), (12, "\u{a0}bn")],
+    local_currency: Some(("SEK", "kr")),
+    ..DE
+};
+
+const TR: NumberSymbols = NumberSymbols {
+    percent_pattern: "%#",
+    currency_pattern: "¤#",
+    compact: &[(3, "\u{a0}B"), (6, "\u{a0}Mn"), (9, "\u{a0}Mr"), (12, "\u{a0}Tn")],
+    local_currency: Some(("TRY", "₺")),
+    ..DE
+};
+
+const JA: NumberSymbols = NumberSymbols {
+    compact: &[(4, "万"), (8, "億"), (12, "兆")],
+    local_currency: Some(("JPY", "￥")),
+    ..ROOT
+};
+
+const ZH: NumberSymbols = NumberSymbols {
+    compact: &[(4, "万"), (8, "亿"), (12, "万亿")],
+    local_currency: Some(("CNY", "¥")),
+    ..ROOT
+};
+
+const KO: NumberSymbols = NumberSymbols {
+    compact: &[(3, "천"), (4, "만"), (8, "억"), (12, "조")],
+    ..ROOT
+};
+
+/// Returns the number symbols of a locale, falling back to those
+/// of the CLDR root locale.
+pub(crate) fn number_symbols(locale: &Locale) -> &'static NumberSymbols {
+    match (locale.language(), locale.region()) {
+        ("en", Some("IN")) => &EN_IN,
+        ("en", _) => &EN,
+        ("hi", _) => &HI,
+        ("de", Some("CH" | "LI")) => &DE_CH,
+        ("de", _) => &DE,
+        ("fr", _) => &FR,
+        ("es", _) => &ES,
+        ("it", _) => &IT,
+        ("pt", Some("BR") | None) => &PT,
+        ("pt", _) => &PT_PT,
+        ("nl", _) => &NL,
+        ("ru", _) => &RU,
+        ("pl", _) => &PL,
+        ("sv", _) => &SV,
+        ("tr", _) => &TR,
+        ("ja", _) => &JA,
+        ("zh", _) => &ZH,
+        ("ko", _) => &KO,
+        _ => &ROOT,
+    }
+}
+
+/// Returns the symbol and the number of fraction digits of a currency.
+pub(crate) fn currency(symbols: &NumberSymbols, code: &str) -> (String, u32) {
+    let digits = match code {
+        "JPY" | "KRW" | "CLP" | "ISK" | "VND" => 0,
+        _ => 2,
+    };
+    if let Some((local_code, symbol)) = symbols.local_currency {
+        if local_code == code {
+            return (symbol.to_owned(), digits);
+        }
+    }
+    let symbol = match code {
+        "USD" => "$",
+        "EUR" => "€",
+        "GBP" => "£",
+        "JPY" => "¥",
+        "CNY" => "CN¥",
+        "KRW" => "₩",
+        "INR" => "₹",
+        "BRL" => "R$",
+        "CAD" => "CA$",
+        "AUD" => "A$",
+        "MXN" => "MX$",
+        code => code,
+    };
+    (symbol.to_owned(), digits)
+}
@@ -0,0 +1,427 @@
+use std::{fmt::Display, str::FromStr};
+use rialight_util::number::{BigDecimal, BigInt, NonNegBigInt, RoundingMode};
+use crate::{Locale, number_data::{self, NumberSymbols}};
+
+/// The style of a [`NumberFormat`].
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub enum NumberStyle {
+    /// Plain decimal notation, such as `1,234.5`.
+    #[default]
+    Decimal,
+    /// Percentage of a ratio, such as `12%` for `0.12`.
+    Percent,
+    /// Amount of the currency with the given ISO 4217 code, such as `$1,234.50` for `USD`.
+    Currency(String),
+    /// Compact notation, such as `1.2K` or `12万`.
+    Compact,
+    /// Scientific notation, such as `1.235E3`.
+    Scientific,
+}
+
+/// Options of a [`NumberFormat`].
+///
+/// The fraction digits default to between 0 and 3 for decimal and scientific
+/// notation, 0 for percentages and those of the currency for currency amounts.
+/// Compact notation shows 2 significant digits by default, or no fraction digits
+/// for a number with at least 2 integer digits.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct NumberFormatOptions {
+    /// Default: [`NumberStyle::Decimal`].
+    pub style: NumberStyle,
+    /// Minimum number of fraction digits, padded with zeros.
+    pub minimum_fraction_digits: Option<u32>,
+    /// Maximum number of fraction digits, rounding half away from zero.
+    pub maximum_fraction_digits: Option<u32>,
+    /// Whether to separate groups of integer digits. Default: `true`.
+    pub use_grouping: bool,
+}
+
+impl Default for NumberFormatOptions {
+    fn default() -> Self {
+        Self {
+            style: NumberStyle::default(),
+            minimum_fraction_digits: None,
+            maximum_fraction_digits: None,
+            use_grouping: true,
+        }
+    }
+}
+
+/// A number that a [`NumberFormat`] formats.
+pub trait FormattableNumber {
+    /// Returns the exact value of the number, or the number itself
+    /// if it is infinite or NaN.
+    fn to_big_decimal(&self) -> Result<BigDecimal, f64>;
+}
+
+macro_rules! formattable_integer {
+    ($($t:ty),*) => {
+        $(
+            impl FormattableNumber for $t {
+                fn to_big_decimal(&self) -> Result<BigDecimal, f64> {
+                    Ok(BigDecimal::from(*self))
+                }
+            }
+        )*
+    };
+}
+
+formattable_integer!(i8, u8, i16, u16, i32, u32, i64, u64, i128, u128, isize, usize);
+
+impl FormattableNumber for BigInt {
+    fn to_big_decimal(&self) -> Result<BigDecimal, f64> {
+        Ok(BigDecimal::from(self.clone()))
+    }
+}
+
+impl FormattableNumber for NonNegBigInt {
+    fn to_big_decimal(&self) -> Result<BigDecimal, f64> {
+        Ok(BigDecimal::from(BigInt::from(self.clone())))
+    }
+}
+
+impl FormattableNumber for BigDecimal {
+    fn to_big_decimal(&self) -> Result<BigDecimal, f64> {
+        Ok(self.clone())
+    }
+}
+
+impl FormattableNumber for f64 {
+    /// Returns the shortest decimal that rounds to the number.
+    fn to_big_decimal(&self) -> Result<BigDecimal, f64> {
+        BigDecimal::from_f64_lossy(*self).ok_or(*self)
+    }
+}
+
+impl FormattableNumber for f32 {
+    /// Returns the shortest decimal that rounds to the number.
+    fn to_big_decimal(&self) -> Result<BigDecimal, f64> {
+        if !self.is_finite() {
+            return Err(f64::from(*self));
+        }
+        Ok(BigDecimal::from_str(&format!("{self:e}")).unwrap())
+    }
+}
+
+/// Formats and parses numbers according to the conventions of a locale,
+/// such as `1,234,567.8` in `en`, `1.234.567,8` in `de` and `12,34,567.8` in `hi`.
+///
+/// Formatting data is built in for the `en`, `hi`, `de`, `fr`, `es`, `it`, `pt`,
+/// `nl`, `ru`, `pl`, `sv`, `tr`, `ja`, `zh` and `ko` languages; other locales
+/// use the conventions of the CLDR root locale. Digits are always Latin.
+///
+/// # Example
+///
+/// ```
+/// use rialight_intl::{Locale, NumberFormat, NumberFormatOptions, NumberStyle};
+///
+/// let de = Locale::parse("de-DE").unwrap();
+/// assert_eq!(NumberFormat::new(&de, NumberFormatOptions::default()).format(&1234567.8), "1.234.567,8");
+///
+/// let currency = NumberFormat::new(&de, NumberFormatOptions {
+///     style: NumberStyle::Currency("EUR".into()),
+///     ..Default::default()
+/// });
+/// assert_eq!(currency.format(&-1234.5), "-1.234,50\u{a0}€");
+/// assert_eq!(currency.parse("1.234,50 €").unwrap().to_string(), "1234.50");
+///
+/// let compact = NumberFormat::new(&Locale::parse("en").unwrap(), NumberFormatOptions {
+///     style: NumberStyle::Compact,
+///     ..Default::default()
+/// });
+/// assert_eq!(compact.format(&1234), "1.2K");
+/// assert_eq!(compact.format(&999_999), "1M");
+/// ```
+#[derive(Clone)]
+pub struct NumberFormat {
+    locale: Locale,
+    options: NumberFormatOptions,
+    symbols: &'static NumberSymbols,
+    currency_symbol: String,
+    minimum_fraction_digits: u32,
+    /// `None` for the default rounding of compact notation.
+    maximum_fraction_digits: Option<u32>,
+}
+
+impl NumberFormat {
+    /// Constructs a number format for a locale.
+    pub fn new(locale: &Locale, options: NumberFormatOptions) -> Self {
+        let symbols = number_data::number_symbols(locale);
+        let mut currency_symbol = String::new();
+        let (minimum, maximum) = match &options.style {
+            NumberStyle::Decimal | NumberStyle::Scientific => (0, Some(3)),
+            NumberStyle::Percent => (0, Some(0)),
+            NumberStyle::Currency(code) => {
+                let (symbol, digits) = number_data::currency(symbols, &code.to_ascii_uppercase());
+                currency_symbol = symbol;
+                (digits, Some(digits))
+            },
+            NumberStyle::Compact => (0, None),
+        };
+        let (minimum_fraction_digits, maximum_fraction_digits) = match (options.minimum_fraction_digits, options.maximum_fraction_digits) {
+            (Some(minimum), Some(maximum)) => (minimum, Some(maximum.max(minimum))),
+            (Some(minimum), None) => (minimum, maximum.map(|maximum| maximum.max(minimum))),
+            (None, Some(maximum)) => (minimum.min(maximum), Some(maximum)),
+            (None, None) => (minimum, maximum),
+        };
+        Self { locale: locale.clone(), options, symbols, currency_symbol, minimum_fraction_digits, maximum_fraction_digits }
+    }
+
+    pub fn locale(&self) -> &Locale {
+        &self.locale
+    }
+
+    pub fn options(&self) -> &NumberFormatOptions {
+        &self.options
+    }
+
+    /// Formats a number.
+    pub fn format<N>(&self, number: &N) -> String
+        where N: ?Sized + FormattableNumber
+    {
+        let value = match number.to_big_decimal() {
+            Ok(value) => value,
+            Err(value) if value.is_nan() => return self.apply_pattern("NaN", false),
+            Err(value) => return self.apply_pattern("∞", value.is_sign_negative()),
+        };
+        let (rounded, suffix) = match &self.options.style {
+            NumberStyle::Percent => (self.round(&shift(&value, -2)), String::new()),
+            NumberStyle::Compact => self.round_compact(&value),
+            NumberStyle::Scientific => {
+                let mut exponent = magnitude(&value);
+                let mut mantissa = self.round(&shift(&value, exponent));
+                // rounding may carry into a new digit, such as 9.9996 to 10.000
+                if magnitude(&mantissa) > 0 {
+                    exponent += 1;
+                    mantissa = self.round(&shift(&value, exponent));
+                }
+                (mantissa, format!("E{exponent}"))
+            },
+            _ => (self.round(&value), String::new()),
+        };
+        let text = self.digits(&rounded) + &suffix;
+        self.apply_pattern(&text, rounded.is_negative())
+    }
+
+    /// Rounds to the fraction digits of the format.
+    fn round(&self, value: &BigDecimal) -> BigDecimal {
+        let rounded = match self.maximum_fraction_digits {
+            Some(maximum) => value.round(maximum.into(), RoundingMode::HalfUp),
+            None => value.clone(),
+        };
+        let rounded = rounded.normalized();
+        let minimum = i64::from(self.minimum_fraction_digits);
+        if rounded.scale() < minimum { rounded.round(minimum, RoundingMode::HalfUp) } else { rounded }
+    }
+
+    /// Divides by the power of ten of a compact suffix and rounds,
+    /// returning the suffix.
+    fn round_compact(&self, value: &BigDecimal) -> (BigDecimal, String) {
+        let entry_for = |magnitude: i64| self.symbols.compact.iter().rev().find(|(exponent, _)| i64::from(*exponent) <= magnitude).copied();
+        let round = |entry: Option<(u32, &str)>| {
+            let exponent = entry.map_or(0, |(exponent, _)| i64::from(exponent));
+            let scaled = shift(value, exponent);
+            let rounded = match self.maximum_fraction_digits {
+                Some(_) => self.round(&scaled),
+                None if magnitude(&scaled) >= 1 => self.round(&scaled.round(0, RoundingMode::HalfUp)),
+                None => self.round(&scaled.round_to_precision(2, RoundingMode::HalfUp)),
+            };
+            (rounded, exponent)
+        };
+        let mut entry = entry_for(magnitude(value));
+        let (mut rounded, exponent) = round(entry);
+        // rounding may carry into the next suffix, such as 999,999 to 1,000K
+        let carried = entry_for(magnitude(&rounded) + exponent);
+        if carried != entry {
+            entry = carried;
+            rounded = round(entry).0;
+        }
+        (rounded, entry.map_or("", |(_, suffix)| suffix).to_owned())
+    }
+
+    /// Returns the localized digits of the absolute value of a rounded number.
+    fn digits(&self, value: &BigDecimal) -> String {
+        let string = value.abs().to_string();
+        match string.split_once('.') {
+            Some((integer, fraction)) => self.group(integer) + self.symbols.decimal + fraction,
+            None => self.group(&string),
+        }
+    }
+
+    fn group(&self, integer: &str) -> String {
+        let symbols = self.symbols;
+        // compact notation does not group four-digit numbers
+        let minimum_grouping_digits = match self.options.style {
+            NumberStyle::Compact => symbols.minimum_grouping_digits.max(2),
+            _ => symbols.minimum_grouping_digits,
+        };
+        if !self.options.use_grouping || integer.len() < symbols.primary_grouping + minimum_grouping_digits {
+            return integer.to_owned();
+        }
+        let (mut head, tail) = integer.split_at(integer.len() - symbols.primary_grouping);
+        let mut groups = vec![tail];
+        while head.len() > symbols.secondary_grouping {
+            let (rest, group) = head.split_at(head.len() - symbols.secondary_grouping);
+            groups.push(group);
+            head = rest;
+        }
+        groups.push(head);
+        groups.reverse();
+        groups.join(symbols.group)
+    }
+
+    fn apply_pattern(&self, text: &str, negative: bool) -> String {
+        let formatted = match &self.options.style {
+            NumberStyle::Percent => self.symbols.percent_pattern.replace('#', text),
+            NumberStyle::Currency(_) => self.symbols.currency_pattern.replace('¤', &self.currency_symbol).replace('#', text),
+            _ => text.to_owned(),
+        };
+        if negative { format!("-{formatted}") } else { formatted }
+    }
+
+    /// Parses a number written according to the format, such as from a text input.
+    ///
+    /// Group separators are optional, and a currency symbol, percent sign
+    /// or compact suffix is recognized according to the style. Exponents
+    /// beyond ±10,000 are rejected.
+    pub fn parse(&self, text: &str) -> Result<BigDecimal, NumberParseError> {
+        let error = || NumberParseError { text: text.to_owned() };
+        let mut rest = text.trim();
+        // the sign may precede a currency symbol, such as in `-$1.00`
+        let negative = rest.starts_with(['-', '\u{2212}']);
+        if negative {
+            rest = rest[rest.chars().next().unwrap().len_utf8()..].trim_start();
+        }
+        let mut exponent = 0;
+        match &self.options.style {
+            NumberStyle::Percent => {
+                rest = strip_affix(rest, "%").unwrap_or(rest);
+                exponent = -2;
+            },
+            NumberStyle::Currency(code) => {
+                rest = strip_affix(rest, &self.currency_symbol).or_else(|| strip_affix(rest, code)).unwrap_or(rest);
+            },
+            NumberStyle::Compact => {
+                let mut entries = self.symbols.compact.to_vec();
+                entries.sort_by_key(|(_, suffix)| std::cmp::Reverse(suffix.len()));
+                if let Some((entry_exponent, stripped)) = entries.iter().find_map(|(entry_exponent, suffix)| Some((entry_exponent, rest.strip_suffix(suffix.trim_start_matches('\u{a0}'))?))) {
+                    exponent = i64::from(*entry_exponent);
+                    rest = stripped;
+                }
+            },
+            _ => {},
+        }
+        let rest = rest.trim();
+        let group_is_space = self.symbols.group.chars().all(char::is_whitespace);
+        let mut normalized = String::from(if negative { "-" } else { "" });
+        let mut fraction = false;
+        for (index, ch) in rest.char_indices() {
+            let symbol = &rest[index..index + ch.len_utf8()];
+            match ch {
+                '0'..='9' => normalized.push(ch),
+                '-' | '\u{2212}' | '+' if index == 0 => normalized.push(if ch == '+' { '+' } else { '-' }),
+                'E' | 'e' if self.options.style == NumberStyle::Scientific => {
+                    normalized.push('E');
+                    fraction = true;
+                },
+                '-' if normalized.ends_with('E') => normalized.push('-'),
+                _ if symbol == self.symbols.decimal && !fraction => {
+                    normalized.push('.');
+                    fraction = true;
+                },
+                _ if !fraction && (symbol == self.symbols.group || (group_is_space && ch.is_whitespace()) || (self.symbols.group == "’" && ch == '\'')) => {},
+                _ => return Err(error()),
+            }
+        }
+        let value = BigDecimal::from_str(&normalized).map_err(|_| error())?;
+        Ok(shift(&value, -exponent))
+    }
+}
+
+/// Divides by `10^exponent`.
+fn shift(value: &BigDecimal, exponent: i64) -> BigDecimal {
+    BigDecimal::new(value.unscaled().clone(), value.scale() + exponent)
+}
+
+/// Returns the power of ten of the most significant digit, or 0 for zero.
+fn magnitude(value: &BigDecimal) -> i64 {
+    if value.is_zero() { 0 } else { value.precision() as i64 - 1 - value.scale() }
+}
+
+/// Strips a prefix or suffix, along with the whitespace separating it.
+fn strip_affix<'a>(text: &'a str, affix: &str) -> Option<&'a str> {
+    text.strip_prefix(affix).or_else(|| text.strip_suffix(affix)).map(str::trim)
+}
+
+/// Error returned when parsing a number that does not match a [`NumberFormat`].
+#[derive(PartialEq, Clone, Debug)]
+pub struct NumberParseError {
+    text: String,
+}
+
+impl Display for NumberParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid number \"{}\"", self.text)
+    }
+}
+
+impl std::error::Error for NumberParseError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn format(locale: &str, style: NumberStyle) -> NumberFormat {
+        NumberFormat::new(&Locale::parse(locale).unwrap(), NumberFormatOptions { style, ..Default::default() })
+    }
+
+    #[test]
+    fn formatting() {
+        assert_eq!(format("en", NumberStyle::Decimal).format(&-1234567.8915), "-1,234,567.892");
+        assert_eq!(format("fr", NumberStyle::Decimal).format(&1234.5f32), "1\u{202f}234,5");
+        assert_eq!(format("de-CH", NumberStyle::Decimal).format(&BigInt::from(10).pow(7)), "10’000’000");
+        assert_eq!(format("es", NumberStyle::Decimal).format(&1234), "1234");
+        assert_eq!(format("es", NumberStyle::Decimal).format(&12345), "12.345");
+        assert_eq!(format("en", NumberStyle::Decimal).format(&-0.0001), "0");
+        assert_eq!(format("en", NumberStyle::Decimal).format(&f64::NEG_INFINITY), "-∞");
+
+        assert_eq!(format("de", NumberStyle::Percent).format(&0.256), "26\u{a0}%");
+        assert_eq!(format("tr", NumberStyle::Percent).format(&-0.5), "-%50");
+        assert_eq!(format("ja", NumberStyle::Currency("JPY".into())).format(&1234.5), "￥1,235");
+        assert_eq!(format("en", NumberStyle::Currency("eur".into())).format(&-3), "-€3.00");
+        assert_eq!(format("pt-BR", NumberStyle::Currency("BRL".into())).format(&1234.5), "R$\u{a0}1.234,50");
+
+        assert_eq!(format("en", NumberStyle::Compact).format(&-12_345), "-12K");
+        assert_eq!(format("en", NumberStyle::Compact).format(&1.25), "1.3");
+        assert_eq!(format("ja", NumberStyle::Compact).format(&123_456_789), "1.2億");
+        assert_eq!(format("ja", NumberStyle::Compact).format(&1234), "1234");
+        assert_eq!(format("es", NumberStyle::Compact).format(&5_300_000_000u64), "5300\u{a0}M");
+        assert_eq!(format("en", NumberStyle::Scientific).format(&99_996), "1E5");
+        assert_eq!(format("de", NumberStyle::Scientific).format(&0.00012345), "1,235E-4");
+
+        let fixed = NumberFormat::new(&Locale::parse("en").unwrap(), NumberFormatOptions {
+            minimum_fraction_digits: Some(2),
+            use_grouping: false,
+            ..Default::default()
+        });
+        assert_eq!(fixed.format(&1234), "1234.00");
+        assert_eq!(fixed.format(&1.23456), "1.235");
+    }
+
+    #[test]
+    fn parsing() {
+        let parse = |locale, style, text| format(locale, style).parse(text).map(|value| value.to_string());
+        assert_eq!(parse("de", NumberStyle::Decimal, "-1.234.567,5"), Ok("-1234567.5".into()));
+        assert_eq!(parse("fr", NumberStyle::Decimal, "1 234,5"), Ok("1234.5".into()));
+        assert_eq!(parse("de-CH", NumberStyle::Decimal, "1'234.5"), Ok("1234.5".into()));
+        assert_eq!(parse("en", NumberStyle::Percent, "12.5%"), Ok("0.125".into()));
+        assert_eq!(parse("en", NumberStyle::Currency("USD".into()), "-$1,000.25"), Ok("-1000.25".into()));
+        assert_eq!(parse("zh", NumberStyle::Compact, "1.5万亿"), Ok("1500000000000".into()));
+        assert_eq!(parse("en", NumberStyle::Scientific, "1.5E-3"), Ok("0.0015".into()));
+        assert!(parse("en", NumberStyle::Scientific, "1E-5000000000").is_err());
+        assert!(parse("en", NumberStyle::Scientific, "1E10001").is_err());
+        assert!(parse("en", NumberStyle::Decimal, "1.234,5").is_err());
+        assert!(parse("en", NumberStyle::Decimal, "").is_err());
+        assert_eq!(format("en", NumberStyle::Decimal).parse("x").unwrap_err().to_string(), "invalid number \"x\"");
+    }
+}
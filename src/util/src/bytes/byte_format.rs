@@ -0,0 +1,92 @@
+use std::fmt::Display;
+
+/// The byte order of multi-byte numbers read by a [`ByteReader`](super::ByteReader)
+/// or written by a [`ByteWriter`](super::ByteWriter).
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+pub enum Endianness {
+    /// Least significant byte first, the order of most platforms.
+    #[default]
+    Little,
+    /// Most significant byte first, also known as network byte order.
+    Big,
+}
+
+/// How the length of a byte sequence or string is written before it.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+pub enum LengthPrefix {
+    U8,
+    U16,
+    U32,
+    /// An unsigned LEB128 varint.
+    #[default]
+    Varint,
+}
+
+impl LengthPrefix {
+    /// Returns the maximum length that the prefix can represent.
+    pub fn max_length(self) -> u64 {
+        match self {
+            Self::U8 => u8::MAX.into(),
+            Self::U16 => u16::MAX.into(),
+            Self::U32 => u32::MAX.into(),
+            Self::Varint => u64::MAX,
+        }
+    }
+}
+
+/// Error returned by [`ByteReader`](super::ByteReader) and
+/// [`ByteWriter`](super::ByteWriter) operations.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ByteError {
+    /// Fewer bytes remain than requested.
+    UnexpectedEnd { position: usize, requested: usize },
+    /// A varint does not fit in the requested integer type.
+    VarintOverflow { position: usize },
+    /// A string is not valid UTF-8.
+    InvalidUtf8 { position: usize },
+    /// A length does not fit in its length prefix.
+    LengthOverflow { length: usize, prefix: LengthPrefix },
+    /// Writing would exceed the length limit of a writer.
+    LimitExceeded { limit: usize },
+    /// A position is past the end of the data.
+    PositionOutOfBounds { position: usize },
+    /// An alignment of zero bytes was requested.
+    ZeroAlignment,
+}
+
+impl Display for ByteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedEnd { position, requested } => write!(f, "unexpected end of data reading {requested} bytes at position {position}"),
+            Self::VarintOverflow { position } => write!(f, "varint overflow at position {position}"),
+            Self::InvalidUtf8 { position } => write!(f, "invalid UTF-8 at position {position}"),
+            Self::LengthOverflow { length, prefix } => write!(f, "length {length} does not fit in a {prefix:?} length prefix"),
+            Self::LimitExceeded { limit } => write!(f, "exceeded the limit of {limit} bytes"),
+            Self::PositionOutOfBounds { position } => write!(f, "position {position} is out of bounds"),
+            Self::ZeroAlignment => write!(f, "alignment must not be zero"),
+        }
+    }
+}
+
+impl std::error::Error for ByteError {}
+
+/// A position saved by [`ByteReader::save_position`](super::ByteReader::save_position)
+/// or [`ByteWriter::save_position`](super::ByteWriter::save_position), including
+/// the bit offset within the current byte.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct SavedPosition {
+    pub(super) byte: usize,
+    pub(super) bit: u32,
+}
+
+impl SavedPosition {
+    /// Returns the byte position.
+    pub fn byte(&self) -> usize {
+        self.byte
+    }
+
+    /// Returns the number of bits used of the byte at the byte position.
+    pub fn bit(&self) -> u32 {
+        self.bit
+    }
+}
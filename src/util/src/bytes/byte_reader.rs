@@ -0,0 +1,308 @@
+use super::{Buffer, ByteError, Bytes, Endianness, LengthPrefix, SavedPosition};
+
+type Result<T> = std::result::Result<T, ByteError>;
+
+/// A checked cursor for reading binary data, such as save files
+/// and network messages, which returns errors instead of panicking
+/// when data ends early.
+///
+/// The reader takes the remaining bytes of any [`Buffer`], such as `Bytes`,
+/// `BytesMut` or a chain of buffers. Contiguous buffers are taken without
+/// copying, and byte sequences are read as slices of the same memory.
+///
+/// Besides numbers in either byte order, the reader supports LEB128 varints,
+/// zig-zag encoded signed varints, length-prefixed strings and bit fields.
+/// Bits are read from the least significant bit of each byte. Reading a byte-level
+/// value after a bit field skips the remaining bits of the current byte.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::bytes::{Buffer, ByteReader, Bytes, Endianness, LengthPrefix};
+///
+/// let header = Bytes::from_static(b"\x00\x02");
+/// let body = Bytes::from_static(b"\x05hello\xAC\x02\x0D");
+/// let mut reader = ByteReader::new(header.chain(body)).with_endianness(Endianness::Big);
+///
+/// assert_eq!(reader.read_u16().unwrap(), 2);
+/// assert_eq!(reader.read_prefixed_string(LengthPrefix::U8).unwrap(), "hello");
+/// assert_eq!(reader.read_varint_u64().unwrap(), 300);
+///
+/// let position = reader.save_position();
+/// assert_eq!(reader.read_bits(3).unwrap(), 0b101);
+/// assert!(reader.read_bit().unwrap());
+/// reader.restore_position(position);
+/// assert_eq!(reader.read_u8().unwrap(), 0x0D);
+/// assert!(reader.read_u8().is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct ByteReader {
+    bytes: Bytes,
+    position: usize,
+    bit: u32,
+    endianness: Endianness,
+}
+
+macro_rules! read_numbers {
+    ($($name:ident -> $t:ty),*) => {
+        $(
+            #[doc = concat!("Reads a `", stringify!($t), "` in the byte order of the reader.")]
+            pub fn $name(&mut self) -> Result<$t> {
+                let bytes = self.read_array::<{ std::mem::size_of::<$t>() }>()?;
+                Ok(match self.endianness {
+                    Endianness::Little => <$t>::from_le_bytes(bytes),
+                    Endianness::Big => <$t>::from_be_bytes(bytes),
+                })
+            }
+        )*
+    };
+}
+
+impl ByteReader {
+    /// Constructs a reader of the remaining bytes of a buffer, in little-endian order.
+    pub fn new(mut buffer: impl Buffer) -> Self {
+        let bytes = buffer.copy_to_bytes(buffer.remaining());
+        Self { bytes, position: 0, bit: 0, endianness: Endianness::default() }
+    }
+
+    /// Sets the byte order of multi-byte numbers.
+    pub fn with_endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = endianness;
+        self
+    }
+
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    pub fn set_endianness(&mut self, endianness: Endianness) {
+        self.endianness = endianness;
+    }
+
+    /// Returns all bytes of the reader, including those already read.
+    pub fn get_ref(&self) -> &Bytes {
+        &self.bytes
+    }
+
+    /// Returns the total number of bytes.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Returns the position of the next byte to read. During a bit field,
+    /// this is the position of the partially read byte.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Moves to a byte position, which may be the end of the data.
+    pub fn set_position(&mut self, position: usize) -> Result<()> {
+        if position > self.bytes.len() {
+            return Err(ByteError::PositionOutOfBounds { position });
+        }
+        self.position = position;
+        self.bit = 0;
+        Ok(())
+    }
+
+    /// Returns the number of bytes remaining after the current one,
+    /// not counting a partially read byte.
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.aligned_position()
+    }
+
+    /// Indicates whether all bytes have been read.
+    pub fn is_at_end(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Saves the current position, including the bit offset.
+    pub fn save_position(&self) -> SavedPosition {
+        SavedPosition { byte: self.position, bit: self.bit }
+    }
+
+    /// Restores a position saved by [`ByteReader::save_position`].
+    pub fn restore_position(&mut self, position: SavedPosition) {
+        self.position = position.byte.min(self.bytes.len());
+        self.bit = if self.position < self.bytes.len() { position.bit } else { 0 };
+    }
+
+    fn aligned_position(&self) -> usize {
+        self.position + usize::from(self.bit != 0)
+    }
+
+    /// Skips the remaining bits of a partially read byte.
+    pub fn align_to_byte(&mut self) {
+        self.position = self.aligned_position();
+        self.bit = 0;
+    }
+
+    /// Skips bytes until the position is a multiple of `alignment`,
+    /// failing if `alignment` is zero.
+    pub fn align(&mut self, alignment: usize) -> Result<()> {
+        if alignment == 0 {
+            return Err(ByteError::ZeroAlignment);
+        }
+        self.align_to_byte();
+        let padding = (alignment - self.position % alignment) % alignment;
+        self.skip(padding)
+    }
+
+    /// Skips bytes.
+    pub fn skip(&mut self, length: usize) -> Result<()> {
+        self.read_slice(length).map(|_| ())
+    }
+
+    fn read_slice(&mut self, length: usize) -> Result<std::ops::Range<usize>> {
+        self.align_to_byte();
+        if length > self.remaining() {
+            return Err(ByteError::UnexpectedEnd { position: self.position, requested: length });
+        }
+        let start = self.position;
+        self.position += length;
+        Ok(start..self.position)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let range = self.read_slice(N)?;
+        Ok(self.bytes[range].try_into().unwrap())
+    }
+
+    /// Reads a sequence of bytes, sharing the memory of the reader.
+    pub fn read_bytes(&mut self, length: usize) -> Result<Bytes> {
+        let range = self.read_slice(length)?;
+        Ok(self.bytes.slice(range))
+    }
+
+    /// Reads bytes filling `destination`.
+    pub fn read_into(&mut self, destination: &mut [u8]) -> Result<()> {
+        let range = self.read_slice(destination.len())?;
+        destination.copy_from_slice(&self.bytes[range]);
+        Ok(())
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    pub fn read_i8(&mut self) -> Result<i8> {
+        Ok(self.read_u8()? as i8)
+    }
+
+    /// Reads a byte as a boolean, which is `true` for any non-zero value.
+    pub fn read_bool(&mut self) -> Result<bool> {
+        Ok(self.read_u8()? != 0)
+    }
+
+    read_numbers!(
+        read_u16 -> u16, read_i16 -> i16,
+        read_u32 -> u32, read_i32 -> i32,
+        read_u64 -> u64, read_i64 -> i64,
+        read_u128 -> u128, read_i128 -> i128,
+        read_f32 -> f32, read_f64 -> f64
+    );
+
+    /// Reads an unsigned LEB128 varint.
+    pub fn read_varint_u64(&mut self) -> Result<u64> {
+        self.align_to_byte();
+        let start = self.position;
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_u8().map_err(|_| ByteError::UnexpectedEnd { position: start, requested: self.position - start + 1 })?;
+            // the tenth byte holds only the most significant bit
+            if shift == 63 && byte > 1 {
+                return Err(ByteError::VarintOverflow { position: start });
+            }
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(ByteError::VarintOverflow { position: start })
+    }
+
+    /// Reads an unsigned LEB128 varint that fits in a `u32`.
+    pub fn read_varint_u32(&mut self) -> Result<u32> {
+        let position = self.aligned_position();
+        u32::try_from(self.read_varint_u64()?).map_err(|_| ByteError::VarintOverflow { position })
+    }
+
+    /// Reads a zig-zag encoded LEB128 varint.
+    pub fn read_varint_i64(&mut self) -> Result<i64> {
+        let value = self.read_varint_u64()?;
+        Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+    }
+
+    /// Reads a zig-zag encoded LEB128 varint that fits in an `i32`.
+    pub fn read_varint_i32(&mut self) -> Result<i32> {
+        let position = self.aligned_position();
+        i32::try_from(self.read_varint_i64()?).map_err(|_| ByteError::VarintOverflow { position })
+    }
+
+    fn read_length(&mut self, prefix: LengthPrefix) -> Result<usize> {
+        let position = self.aligned_position();
+        let length = match prefix {
+            LengthPrefix::U8 => self.read_u8()?.into(),
+            LengthPrefix::U16 => self.read_u16()?.into(),
+            LengthPrefix::U32 => self.read_u32()?.into(),
+            LengthPrefix::Varint => self.read_varint_u64()?,
+        };
+        usize::try_from(length).map_err(|_| ByteError::UnexpectedEnd { position, requested: usize::MAX })
+    }
+
+    /// Reads a sequence of bytes preceded by its length.
+    pub fn read_prefixed_bytes(&mut self, prefix: LengthPrefix) -> Result<Bytes> {
+        let length = self.read_length(prefix)?;
+        self.read_bytes(length)
+    }
+
+    /// Reads a UTF-8 string preceded by its length in bytes.
+    pub fn read_prefixed_string(&mut self, prefix: LengthPrefix) -> Result<String> {
+        let length = self.read_length(prefix)?;
+        let position = self.position;
+        let range = self.read_slice(length)?;
+        String::from_utf8(self.bytes[range].to_vec()).map_err(|_| ByteError::InvalidUtf8 { position })
+    }
+
+    /// Reads a bit.
+    pub fn read_bit(&mut self) -> Result<bool> {
+        Ok(self.read_bits(1)? != 0)
+    }
+
+    /// Reads a field of up to 64 bits, starting from the least significant bit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is greater than 64.
+    pub fn read_bits(&mut self, count: u32) -> Result<u64> {
+        assert!(count <= 64, "cannot read more than 64 bits");
+        let available = (self.bytes.len() - self.position) as u64 * 8 - u64::from(self.bit);
+        if u64::from(count) > available {
+            return Err(ByteError::UnexpectedEnd { position: self.position, requested: (self.bit + count).div_ceil(8) as usize });
+        }
+        let mut value = 0u64;
+        let mut read = 0;
+        while read < count {
+            let length = (8 - self.bit).min(count - read);
+            let bits = (self.bytes[self.position] >> self.bit) & (0xffu16 >> (8 - length)) as u8;
+            value |= u64::from(bits) << read;
+            read += length;
+            self.bit += length;
+            if self.bit == 8 {
+                self.bit = 0;
+                self.position += 1;
+            }
+        }
+        Ok(value)
+    }
+}
+
+impl From<Bytes> for ByteReader {
+    fn from(bytes: Bytes) -> Self {
+        Self::new(bytes)
+    }
+}
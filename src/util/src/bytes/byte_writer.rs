@@ -0,0 +1,359 @@
+use super::{ByteError, Bytes, BytesMut, Endianness, LengthPrefix, SavedPosition};
+
+type Result<T> = std::result::Result<T, ByteError>;
+
+/// A checked cursor for writing binary data, such as save files
+/// and network messages, into a `BytesMut`.
+///
+/// The writer is the counterpart of [`ByteReader`](super::ByteReader).
+/// Writing at a position before the end overwrites existing bytes, which
+/// allows saving a position and restoring it later to fill in a value,
+/// such as the length of a message. An optional limit on the length of the
+/// data, such as the maximum size of a packet, makes writes past it fail.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::bytes::{ByteReader, ByteWriter, LengthPrefix};
+///
+/// let mut writer = ByteWriter::new().with_limit(512);
+/// let length_position = writer.save_position();
+/// writer.write_u16(0).unwrap();
+/// writer.write_prefixed_string(LengthPrefix::Varint, "héllo").unwrap();
+/// writer.write_varint_i64(-3).unwrap();
+/// writer.write_bits(0b101, 3).unwrap();
+/// writer.write_bit(true).unwrap();
+///
+/// let end = writer.save_position();
+/// writer.restore_position(length_position);
+/// writer.write_u16(end.byte() as u16).unwrap();
+/// writer.restore_position(end);
+///
+/// let mut reader = ByteReader::new(writer.into_bytes());
+/// assert_eq!(reader.read_u16().unwrap(), 10);
+/// assert_eq!(reader.read_prefixed_string(LengthPrefix::Varint).unwrap(), "héllo");
+/// assert_eq!(reader.read_varint_i64().unwrap(), -3);
+/// assert_eq!(reader.read_bits(4).unwrap(), 0b1101);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ByteWriter {
+    bytes: BytesMut,
+    position: usize,
+    bit: u32,
+    limit: Option<usize>,
+    endianness: Endianness,
+}
+
+macro_rules! write_numbers {
+    ($($name:ident($t:ty)),*) => {
+        $(
+            #[doc = concat!("Writes a `", stringify!($t), "` in the byte order of the writer.")]
+            pub fn $name(&mut self, value: $t) -> Result<()> {
+                match self.endianness {
+                    Endianness::Little => self.write_bytes(&value.to_le_bytes()),
+                    Endianness::Big => self.write_bytes(&value.to_be_bytes()),
+                }
+            }
+        )*
+    };
+}
+
+impl ByteWriter {
+    /// Constructs an empty writer, in little-endian order.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a writer that appends to the bytes of a buffer.
+    pub fn from_buffer(bytes: BytesMut) -> Self {
+        Self { position: bytes.len(), bytes, ..Self::default() }
+    }
+
+    /// Sets the byte order of multi-byte numbers.
+    pub fn with_endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = endianness;
+        self
+    }
+
+    /// Sets the maximum length of the data.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    pub fn set_endianness(&mut self, endianness: Endianness) {
+        self.endianness = endianness;
+    }
+
+    /// Returns the bytes written.
+    pub fn get_ref(&self) -> &BytesMut {
+        &self.bytes
+    }
+
+    /// Returns the total number of bytes written.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Returns the position of the next byte to write. During a bit field,
+    /// this is the position of the partially written byte.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Moves to a byte position, which may be the end of the data.
+    pub fn set_position(&mut self, position: usize) -> Result<()> {
+        if position > self.bytes.len() {
+            return Err(ByteError::PositionOutOfBounds { position });
+        }
+        self.position = position;
+        self.bit = 0;
+        Ok(())
+    }
+
+    /// Saves the current position, including the bit offset.
+    pub fn save_position(&self) -> SavedPosition {
+        SavedPosition { byte: self.position, bit: self.bit }
+    }
+
+    /// Restores a position saved by [`ByteWriter::save_position`].
+    pub fn restore_position(&mut self, position: SavedPosition) {
+        self.position = position.byte.min(self.bytes.len());
+        self.bit = if self.position < self.bytes.len() { position.bit } else { 0 };
+    }
+
+    /// Leaves the remaining bits of a partially written byte as zeros.
+    pub fn align_to_byte(&mut self) {
+        if self.bit != 0 {
+            self.position += 1;
+            self.bit = 0;
+        }
+    }
+
+    /// Writes zeros until the position is a multiple of `alignment`,
+    /// failing if `alignment` is zero.
+    pub fn align(&mut self, alignment: usize) -> Result<()> {
+        if alignment == 0 {
+            return Err(ByteError::ZeroAlignment);
+        }
+        self.align_to_byte();
+        let padding = (alignment - self.position % alignment) % alignment;
+        self.write_bytes(&vec![0; padding])
+    }
+
+    fn check_limit(&self, end: usize) -> Result<()> {
+        match self.limit {
+            Some(limit) if end > limit => Err(ByteError::LimitExceeded { limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Writes a sequence of bytes.
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.align_to_byte();
+        let end = self.position + bytes.len();
+        self.check_limit(end)?;
+        let overwritten = bytes.len().min(self.bytes.len() - self.position);
+        self.bytes[self.position..self.position + overwritten].copy_from_slice(&bytes[..overwritten]);
+        self.bytes.extend_from_slice(&bytes[overwritten..]);
+        self.position = end;
+        Ok(())
+    }
+
+    pub fn write_u8(&mut self, value: u8) -> Result<()> {
+        self.write_bytes(&[value])
+    }
+
+    pub fn write_i8(&mut self, value: i8) -> Result<()> {
+        self.write_u8(value as u8)
+    }
+
+    /// Writes a boolean as a byte, `1` or `0`.
+    pub fn write_bool(&mut self, value: bool) -> Result<()> {
+        self.write_u8(value.into())
+    }
+
+    write_numbers!(
+        write_u16(u16), write_i16(i16),
+        write_u32(u32), write_i32(i32),
+        write_u64(u64), write_i64(i64),
+        write_u128(u128), write_i128(i128),
+        write_f32(f32), write_f64(f64)
+    );
+
+    /// Writes an unsigned LEB128 varint.
+    pub fn write_varint_u64(&mut self, mut value: u64) -> Result<()> {
+        let mut bytes = Vec::with_capacity(10);
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                bytes.push(byte);
+                break;
+            }
+            bytes.push(byte | 0x80);
+        }
+        self.write_bytes(&bytes)
+    }
+
+    /// Writes an unsigned LEB128 varint.
+    pub fn write_varint_u32(&mut self, value: u32) -> Result<()> {
+        self.write_varint_u64(value.into())
+    }
+
+    /// Writes a zig-zag encoded LEB128 varint, which is short for
+    /// numbers close to zero, either positive or negative.
+    pub fn write_varint_i64(&mut self, value: i64) -> Result<()> {
+        self.write_varint_u64(((value << 1) ^ (value >> 63)) as u64)
+    }
+
+    /// Writes a zig-zag encoded LEB128 varint.
+    pub fn write_varint_i32(&mut self, value: i32) -> Result<()> {
+        self.write_varint_i64(value.into())
+    }
+
+    fn write_length(&mut self, length: usize, prefix: LengthPrefix) -> Result<()> {
+        if length as u64 > prefix.max_length() {
+            return Err(ByteError::LengthOverflow { length, prefix });
+        }
+        match prefix {
+            LengthPrefix::U8 => self.write_u8(length as u8),
+            LengthPrefix::U16 => self.write_u16(length as u16),
+            LengthPrefix::U32 => self.write_u32(length as u32),
+            LengthPrefix::Varint => self.write_varint_u64(length as u64),
+        }
+    }
+
+    /// Writes a sequence of bytes preceded by its length.
+    pub fn write_prefixed_bytes(&mut self, prefix: LengthPrefix, bytes: &[u8]) -> Result<()> {
+        let start = self.save_position();
+        let length = self.bytes.len();
+        self.write_length(bytes.len(), prefix)?;
+        self.write_bytes(bytes).inspect_err(|_| self.undo(start, length))
+    }
+
+    /// Writes a UTF-8 string preceded by its length in bytes.
+    pub fn write_prefixed_string(&mut self, prefix: LengthPrefix, string: &str) -> Result<()> {
+        self.write_prefixed_bytes(prefix, string.as_bytes())
+    }
+
+    /// Removes the length prefix of a sequence that could not be written.
+    fn undo(&mut self, position: SavedPosition, length: usize) {
+        self.bytes.truncate(length.max(position.byte));
+        self.restore_position(position);
+    }
+
+    /// Writes a bit.
+    pub fn write_bit(&mut self, value: bool) -> Result<()> {
+        self.write_bits(value.into(), 1)
+    }
+
+    /// Writes the `count` least significant bits of `value`, starting from
+    /// the least significant bit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is greater than 64.
+    pub fn write_bits(&mut self, mut value: u64, count: u32) -> Result<()> {
+        assert!(count <= 64, "cannot write more than 64 bits");
+        self.check_limit(self.position + (self.bit + count).div_ceil(8) as usize)?;
+        let mut written = 0;
+        while written < count {
+            if self.position == self.bytes.len() {
+                self.bytes.extend_from_slice(&[0]);
+            }
+            let length = (8 - self.bit).min(count - written);
+            let mask = ((0xffu16 >> (8 - length)) as u8) << self.bit;
+            let bits = ((value as u8) << self.bit) & mask;
+            self.bytes[self.position] = (self.bytes[self.position] & !mask) | bits;
+            value = value.checked_shr(length).unwrap_or(0);
+            written += length;
+            self.bit += length;
+            if self.bit == 8 {
+                self.bit = 0;
+                self.position += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the bytes written.
+    pub fn into_bytes(self) -> Bytes {
+        self.bytes.freeze()
+    }
+
+    /// Returns the underlying buffer.
+    pub fn into_inner(self) -> BytesMut {
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bytes::{Buffer, ByteReader};
+
+    #[test]
+    fn round_trip() {
+        let mut writer = ByteWriter::new().with_endianness(Endianness::Big);
+        writer.write_i32(-2).unwrap();
+        writer.write_f64(1.5).unwrap();
+        writer.write_varint_u64(u64::MAX).unwrap();
+        writer.write_varint_i32(i32::MIN).unwrap();
+        writer.write_bits(0x1ff, 9).unwrap();
+        writer.write_bits(u64::MAX, 64).unwrap();
+        writer.align(4).unwrap();
+        assert_eq!(writer.align(0), Err(ByteError::ZeroAlignment));
+        writer.write_prefixed_bytes(LengthPrefix::U8, &[1, 2]).unwrap();
+        assert_eq!(writer.write_prefixed_bytes(LengthPrefix::U8, &[0; 256]), Err(ByteError::LengthOverflow { length: 256, prefix: LengthPrefix::U8 }));
+        let bytes = writer.into_bytes();
+        assert_eq!(&bytes[..4], [0xff, 0xff, 0xff, 0xfe]);
+        assert_eq!(bytes.len() % 4, 3);
+
+        let (head, tail) = (bytes.slice(..5), bytes.slice(5..));
+        let mut reader = ByteReader::new(head.chain(tail)).with_endianness(Endianness::Big);
+        assert_eq!(reader.read_i32().unwrap(), -2);
+        assert_eq!(reader.read_f64().unwrap(), 1.5);
+        assert_eq!(reader.read_varint_u64().unwrap(), u64::MAX);
+        assert_eq!(reader.read_varint_i32().unwrap(), i32::MIN);
+        assert_eq!(reader.read_bits(9).unwrap(), 0x1ff);
+        assert_eq!(reader.read_bits(64).unwrap(), u64::MAX);
+        reader.align(4).unwrap();
+        assert_eq!(reader.align(0), Err(ByteError::ZeroAlignment));
+        assert_eq!(reader.read_prefixed_bytes(LengthPrefix::U8).unwrap(), [1, 2][..]);
+        assert!(reader.is_at_end());
+        assert_eq!(reader.read_bit(), Err(ByteError::UnexpectedEnd { position: bytes.len(), requested: 1 }));
+
+        let mut reader = ByteReader::from(Bytes::from_static(b"\xff\xff\xff\xff\x10\x02\xc3\x28"));
+        assert_eq!(reader.read_varint_u32(), Err(ByteError::VarintOverflow { position: 0 }));
+        reader.set_position(6).unwrap();
+        assert_eq!(reader.read_prefixed_string(LengthPrefix::U8), Err(ByteError::UnexpectedEnd { position: 7, requested: 195 }));
+        reader.set_position(5).unwrap();
+        assert_eq!(reader.read_prefixed_string(LengthPrefix::U8), Err(ByteError::InvalidUtf8 { position: 6 }));
+        assert!(reader.set_position(9).is_err());
+
+        let mut reader = ByteReader::from(Bytes::from_static(b"\xff\xff"));
+        reader.read_bits(12).unwrap();
+        let saved = reader.save_position();
+        let mut short = ByteReader::from(Bytes::from_static(b"\xff"));
+        short.restore_position(saved);
+        assert_eq!(short.position(), 1);
+        assert_eq!(short.read_bits(4), Err(ByteError::UnexpectedEnd { position: 1, requested: 1 }));
+
+        let mut writer = ByteWriter::new().with_limit(2);
+        writer.write_u8(1).unwrap();
+        assert_eq!(writer.write_prefixed_string(LengthPrefix::U8, "ab"), Err(ByteError::LimitExceeded { limit: 2 }));
+        assert_eq!(writer.len(), 1);
+        assert_eq!(writer.write_u16(0), Err(ByteError::LimitExceeded { limit: 2 }));
+        writer.write_bits(0, 8).unwrap();
+        assert!(writer.write_bit(true).is_err());
+    }
+}
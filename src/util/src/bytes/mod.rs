@@ -64,9 +64,25 @@
 //! argument to `Read::read` and `Write::write`. `Read` and `Write` may then
 //! perform a syscall, which has the potential of failing. Operations on `Buffer`
 //! and `BufferMut` are infallible.
+//!
+//! # `ByteReader`, `ByteWriter`
+//!
+//! [`ByteReader`] and [`ByteWriter`] are checked cursors for binary formats, such
+//! as save files and network protocols. They return a [`ByteError`] instead of
+//! panicking when data ends early, and support both byte orders, LEB128 varints,
+//! length-prefixed strings, bit fields, alignment and saving positions.
 
 pub use bytes::{Bytes, BytesMut, Buf as Buffer, BufMut as BufferMut};
 
+mod byte_format;
+pub use byte_format::{ByteError, Endianness, LengthPrefix, SavedPosition};
+
+mod byte_reader;
+pub use byte_reader::ByteReader;
+
+mod byte_writer;
+pub use byte_writer::ByteWriter;
+
 /// Utilities for working with buffers.
 ///
 /// A buffer is any structure that contains a sequence of bytes. The bytes may
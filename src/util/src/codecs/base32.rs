@@ -0,0 +1,55 @@
+use super::{decode_bits, encode_bits, BinaryEncoding, CodecError};
+
+const STANDARD_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const HEX_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHIJKLMNOPQRSTUV";
+
+/// The Base32 encoding of RFC 4648, with the standard or the extended hex alphabet.
+///
+/// Decoding is case-insensitive.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Base32 {
+    hex: bool,
+    padding: bool,
+}
+
+impl Base32 {
+    /// The standard alphabet, with padding.
+    pub const STANDARD: Self = Self { hex: false, padding: true };
+    /// The standard alphabet without padding.
+    pub const STANDARD_NO_PAD: Self = Self { hex: false, padding: false };
+    /// The extended hex alphabet, which preserves sort order, with padding.
+    pub const HEX: Self = Self { hex: true, padding: true };
+    /// The extended hex alphabet without padding.
+    pub const HEX_NO_PAD: Self = Self { hex: true, padding: false };
+
+    /// Indicates whether the extended hex alphabet is used.
+    pub fn is_hex(&self) -> bool {
+        self.hex
+    }
+
+    /// Indicates whether encoded text is padded with `=`.
+    pub fn is_padded(&self) -> bool {
+        self.padding
+    }
+
+    fn alphabet(&self) -> &'static [u8; 32] {
+        if self.hex { HEX_ALPHABET } else { STANDARD_ALPHABET }
+    }
+}
+
+impl BinaryEncoding for Base32 {
+    const GROUP_BYTES: usize = 5;
+    const GROUP_CHARS: usize = 8;
+
+    fn encode_group(&self, group: &[u8], output: &mut String) {
+        encode_bits(group, 5, Self::GROUP_CHARS, self.alphabet(), self.padding, output);
+    }
+
+    fn decode_group(&self, group: &[u8], position: usize, output: &mut Vec<u8>) -> Result<bool, CodecError> {
+        let alphabet = self.alphabet();
+        decode_bits(group, position, 5, Self::GROUP_CHARS, |ch| {
+            let ch = ch.to_ascii_uppercase();
+            alphabet.iter().position(|c| *c == ch).map(|i| i as u8)
+        }, output)
+    }
+}
@@ -0,0 +1,91 @@
+use super::{decode_bits, encode_bits, BinaryEncoding, CodecError};
+
+const STANDARD_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// The Base64 encoding of RFC 4648, with the standard or the URL-safe alphabet.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Base64 {
+    url_safe: bool,
+    padding: bool,
+}
+
+impl Base64 {
+    /// The standard alphabet, with `+` and `/`, and padding.
+    pub const STANDARD: Self = Self { url_safe: false, padding: true };
+    /// The standard alphabet without padding.
+    pub const STANDARD_NO_PAD: Self = Self { url_safe: false, padding: false };
+    /// The URL-safe alphabet, with `-` and `_`, and padding.
+    pub const URL_SAFE: Self = Self { url_safe: true, padding: true };
+    /// The URL-safe alphabet without padding.
+    pub const URL_SAFE_NO_PAD: Self = Self { url_safe: true, padding: false };
+
+    /// Indicates whether the URL-safe alphabet is used.
+    pub fn is_url_safe(&self) -> bool {
+        self.url_safe
+    }
+
+    /// Indicates whether encoded text is padded with `=`.
+    pub fn is_padded(&self) -> bool {
+        self.padding
+    }
+
+    fn alphabet(&self) -> &'static [u8; 64] {
+        if self.url_safe { URL_SAFE_ALPHABET } else { STANDARD_ALPHABET }
+    }
+}
+
+impl BinaryEncoding for Base64 {
+    const GROUP_BYTES: usize = 3;
+    const GROUP_CHARS: usize = 4;
+
+    fn encode_group(&self, group: &[u8], output: &mut String) {
+        encode_bits(group, 6, Self::GROUP_CHARS, self.alphabet(), self.padding, output);
+    }
+
+    fn decode_group(&self, group: &[u8], position: usize, output: &mut Vec<u8>) -> Result<bool, CodecError> {
+        let alphabet = self.alphabet();
+        decode_bits(group, position, 6, Self::GROUP_CHARS, |ch| alphabet.iter().position(|c| *c == ch).map(|i| i as u8), output)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bytes::{Buffer, Bytes};
+
+    #[test]
+    fn encoding() {
+        assert_eq!(Base64::STANDARD.encode(b""), "");
+        assert_eq!(Base64::STANDARD.encode(b"f"), "Zg==");
+        assert_eq!(Base64::STANDARD.encode(b"fo"), "Zm8=");
+        assert_eq!(Base64::STANDARD.encode(b"foobar"), "Zm9vYmFy");
+        assert_eq!(Base64::STANDARD_NO_PAD.encode(b"fo"), "Zm8");
+        assert_eq!(Base64::URL_SAFE.encode(b"\xff\xfe"), "__4=");
+
+        let mut encoder = Base64::STANDARD.encoder();
+        let text = encoder.update(Bytes::from_static(b"fo").chain(&b"ob"[..])) + &encoder.finish();
+        assert_eq!(text, "Zm9vYg==");
+    }
+
+    #[test]
+    fn decoding() {
+        assert_eq!(Base64::STANDARD.decode("Zm8=").unwrap(), b"fo");
+        assert_eq!(Base64::STANDARD.decode("Zm8").unwrap(), b"fo");
+        assert_eq!(Base64::URL_SAFE_NO_PAD.decode("__4").unwrap(), b"\xff\xfe");
+        assert_eq!(Base64::STANDARD.decode("Zm9v!mFy"), Err(CodecError::InvalidCharacter { position: 4, character: '!' }));
+        assert_eq!(Base64::STANDARD.decode("Zm9vY"), Err(CodecError::InvalidLength { length: 5 }));
+        assert_eq!(Base64::STANDARD.decode("Zm8=Zm8="), Err(CodecError::InvalidPadding { position: 4 }));
+        assert_eq!(Base64::STANDARD.decode("Zg="), Err(CodecError::InvalidPadding { position: 2 }));
+        assert_eq!(Base64::STANDARD.decode("Z=g="), Err(CodecError::InvalidCharacter { position: 2, character: 'g' }));
+        assert_eq!(Base64::STANDARD.decode("QQ==").unwrap(), b"A");
+        assert_eq!(Base64::STANDARD.decode("QR=="), Err(CodecError::InvalidCharacter { position: 1, character: 'R' }));
+        assert_eq!(Base64::STANDARD_NO_PAD.decode("Zm9"), Err(CodecError::InvalidCharacter { position: 2, character: '9' }));
+
+        let mut decoder = Base64::STANDARD.decoder();
+        let mut bytes = decoder.update(&b"Zm9vY"[..]).unwrap().to_vec();
+        bytes.extend_from_slice(&decoder.update(&b"mFy"[..]).unwrap());
+        bytes.extend_from_slice(&decoder.finish().unwrap());
+        assert_eq!(bytes, b"foobar");
+    }
+}
@@ -0,0 +1,39 @@
+use super::{decode_bits, encode_bits, BinaryEncoding, CodecError};
+
+/// The hexadecimal encoding, also known as Base16, with lowercase or
+/// uppercase digits.
+///
+/// Decoding is case-insensitive.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Hex {
+    uppercase: bool,
+}
+
+impl Hex {
+    /// Lowercase digits.
+    pub const LOWER: Self = Self { uppercase: false };
+    /// Uppercase digits.
+    pub const UPPER: Self = Self { uppercase: true };
+
+    /// Indicates whether encoded text uses uppercase digits.
+    pub fn is_uppercase(&self) -> bool {
+        self.uppercase
+    }
+}
+
+impl BinaryEncoding for Hex {
+    const GROUP_BYTES: usize = 1;
+    const GROUP_CHARS: usize = 2;
+
+    fn encode_group(&self, group: &[u8], output: &mut String) {
+        let alphabet = if self.uppercase { b"0123456789ABCDEF" } else { b"0123456789abcdef" };
+        encode_bits(group, 4, Self::GROUP_CHARS, alphabet, false, output);
+    }
+
+    fn decode_group(&self, group: &[u8], position: usize, output: &mut Vec<u8>) -> Result<bool, CodecError> {
+        if let Some(index) = group.iter().position(|ch| *ch == b'=') {
+            return Err(CodecError::InvalidCharacter { position: position + index, character: '=' });
+        }
+        decode_bits(group, position, 4, Self::GROUP_CHARS, |ch| (ch as char).to_digit(16).map(|digit| digit as u8), output)
+    }
+}
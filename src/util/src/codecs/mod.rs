@@ -0,0 +1,275 @@
+/*!
+Encode and decode binary data as text, and text in legacy encodings.
+
+# Binary-to-text encodings
+
+[`Base64`], [`Base32`] and [`Hex`] implement the [`BinaryEncoding`] trait,
+which encodes bytes as text and decodes them back. Decoding accepts input
+with or without padding, although padding, if present, must be complete,
+and rejects a last character whose bits beyond the encoded bytes are not zero.
+
+```
+use rialight_util::codecs::{Base32, Base64, BinaryEncoding, Hex};
+
+assert_eq!(Base64::STANDARD.encode(b"\xfb\xffhi"), "+/9oaQ==");
+assert_eq!(Base64::URL_SAFE_NO_PAD.encode(b"\xfb\xffhi"), "-_9oaQ");
+assert_eq!(Base64::URL_SAFE_NO_PAD.decode("-_9oaQ").unwrap(), b"\xfb\xffhi");
+assert_eq!(Base32::STANDARD.encode(b"hi"), "NBUQ====");
+assert_eq!(Hex::LOWER.decode("cafe").unwrap(), [0xca, 0xfe]);
+```
+
+Streaming encoders and decoders process data in chunks given as any
+[`Buffer`](crate::bytes::Buffer), such as `Bytes` or a chain of buffers:
+
+```
+use rialight_util::{bytes::Bytes, codecs::{Base64, BinaryEncoding}};
+
+let mut encoder = Base64::STANDARD.encoder();
+let mut text = encoder.update(Bytes::from_static(b"hel"));
+text += &encoder.update(&b"lo"[..]);
+text += &encoder.finish();
+assert_eq!(text, "aGVsbG8=");
+
+let mut decoder = Base64::STANDARD.decoder();
+let mut bytes = decoder.update(&b"aGVs"[..]).unwrap().to_vec();
+bytes.extend_from_slice(&decoder.update(&b"bG8="[..]).unwrap());
+bytes.extend_from_slice(&decoder.finish().unwrap());
+assert_eq!(bytes, b"hello");
+```
+
+# Text encodings
+
+[`TextEncoding`] converts between strings and bytes in UTF-8, UTF-16,
+Latin-1 and Windows-1252, as well as in double-byte legacy encodings,
+such as Shift_JIS, given their mapping table. [`TextDecoder`] decodes
+text in chunks.
+
+```
+use rialight_util::codecs::TextEncoding;
+
+let bytes = TextEncoding::Utf16Le.encode("hé").unwrap();
+assert_eq!(bytes, [0x68, 0x00, 0xe9, 0x00]);
+assert_eq!(TextEncoding::Utf16Le.decode(&bytes).unwrap(), "hé");
+assert_eq!(TextEncoding::Windows1252.decode(b"\x93ok\x94").unwrap(), "\u{201c}ok\u{201d}");
+assert_eq!(TextEncoding::Latin1.encode_lossy("a€"), b"a?");
+```
+*/
+
+use std::fmt::Display;
+use crate::bytes::{Buffer, Bytes};
+
+mod base64;
+pub use base64::Base64;
+
+mod base32;
+pub use base32::Base32;
+
+mod hex;
+pub use hex::Hex;
+
+mod text;
+pub use text::{TextEncoding, TextDecoder, IndexTable};
+
+/// A binary-to-text encoding, which encodes groups of bytes as groups
+/// of ASCII characters.
+///
+/// The `encode_group` and `decode_group` methods define the encoding;
+/// the other methods are provided.
+pub trait BinaryEncoding: Copy {
+    /// Number of bytes in a group.
+    const GROUP_BYTES: usize;
+    /// Number of characters in an encoded group.
+    const GROUP_CHARS: usize;
+
+    /// Encodes a group of `1..=GROUP_BYTES` bytes, which is shorter
+    /// than `GROUP_BYTES` only at the end of the data.
+    fn encode_group(&self, group: &[u8], output: &mut String);
+
+    /// Decodes a group of `1..=GROUP_CHARS` characters, which is shorter
+    /// than `GROUP_CHARS` only at the end of the data. `position` is the
+    /// offset of the group in the input. Returns whether the group
+    /// ends with padding.
+    fn decode_group(&self, group: &[u8], position: usize, output: &mut Vec<u8>) -> Result<bool, CodecError>;
+
+    /// Encodes bytes as text.
+    fn encode(&self, bytes: &[u8]) -> String {
+        let mut encoder = self.encoder();
+        encoder.update(bytes) + &encoder.finish()
+    }
+
+    /// Decodes text into bytes.
+    fn decode(&self, text: impl AsRef<[u8]>) -> Result<Vec<u8>, CodecError> {
+        let mut decoder = self.decoder();
+        let mut bytes = decoder.update(text.as_ref())?.to_vec();
+        bytes.extend_from_slice(&decoder.finish()?);
+        Ok(bytes)
+    }
+
+    /// Returns a streaming encoder.
+    fn encoder(&self) -> Encoder<Self> {
+        Encoder { encoding: *self, pending: Vec::with_capacity(Self::GROUP_BYTES) }
+    }
+
+    /// Returns a streaming decoder.
+    fn decoder(&self) -> Decoder<Self> {
+        Decoder { encoding: *self, pending: Vec::with_capacity(Self::GROUP_CHARS), position: 0, padded: false }
+    }
+}
+
+/// A streaming encoder of a [`BinaryEncoding`], which encodes complete
+/// groups of bytes as they are given.
+#[derive(Clone, Debug)]
+pub struct Encoder<E> {
+    encoding: E,
+    pending: Vec<u8>,
+}
+
+impl<E: BinaryEncoding> Encoder<E> {
+    /// Encodes the complete groups of bytes given so far.
+    pub fn update(&mut self, mut input: impl Buffer) -> String {
+        let mut output = String::new();
+        while input.has_remaining() {
+            let length = (E::GROUP_BYTES - self.pending.len()).min(input.chunk().len());
+            self.pending.extend_from_slice(&input.chunk()[..length]);
+            input.advance(length);
+            if self.pending.len() == E::GROUP_BYTES {
+                self.encoding.encode_group(&self.pending, &mut output);
+                self.pending.clear();
+            }
+        }
+        output
+    }
+
+    /// Encodes the remaining bytes, with padding if the encoding uses it.
+    pub fn finish(self) -> String {
+        let mut output = String::new();
+        if !self.pending.is_empty() {
+            self.encoding.encode_group(&self.pending, &mut output);
+        }
+        output
+    }
+}
+
+/// A streaming decoder of a [`BinaryEncoding`], which decodes complete
+/// groups of characters as they are given.
+#[derive(Clone, Debug)]
+pub struct Decoder<E> {
+    encoding: E,
+    pending: Vec<u8>,
+    position: usize,
+    padded: bool,
+}
+
+impl<E: BinaryEncoding> Decoder<E> {
+    /// Decodes the complete groups of characters given so far.
+    pub fn update(&mut self, mut input: impl Buffer) -> Result<Bytes, CodecError> {
+        let mut output = Vec::new();
+        while input.has_remaining() {
+            if self.padded {
+                return Err(CodecError::InvalidPadding { position: self.position });
+            }
+            let length = (E::GROUP_CHARS - self.pending.len()).min(input.chunk().len());
+            self.pending.extend_from_slice(&input.chunk()[..length]);
+            input.advance(length);
+            if self.pending.len() == E::GROUP_CHARS {
+                self.padded = self.encoding.decode_group(&self.pending, self.position, &mut output)?;
+                self.position += E::GROUP_CHARS;
+                self.pending.clear();
+            }
+        }
+        Ok(output.into())
+    }
+
+    /// Decodes the remaining characters, failing if they do not form
+    /// a valid group.
+    pub fn finish(self) -> Result<Bytes, CodecError> {
+        let mut output = Vec::new();
+        if !self.pending.is_empty() {
+            self.encoding.decode_group(&self.pending, self.position, &mut output)?;
+        }
+        Ok(output.into())
+    }
+}
+
+/// Encodes a group of bytes as characters of `bits` bits each, taken from `alphabet`.
+fn encode_bits(group: &[u8], bits: usize, group_chars: usize, alphabet: &[u8], padding: bool, output: &mut String) {
+    let group_bytes = group_chars * bits / 8;
+    let value = group.iter().enumerate().fold(0u64, |value, (i, byte)| value | u64::from(*byte) << (8 * (group_bytes - 1 - i)));
+    let chars = (group.len() * 8).div_ceil(bits);
+    for i in 0..chars {
+        let index = (value >> (group_bytes * 8 - bits * (i + 1))) & ((1 << bits) - 1);
+        output.push(alphabet[index as usize] as char);
+    }
+    if padding {
+        output.extend(std::iter::repeat_n('=', group_chars - chars));
+    }
+}
+
+/// Decodes a group of characters of `bits` bits each, given the value of a character.
+fn decode_bits(group: &[u8], position: usize, bits: usize, group_chars: usize, value_of: impl Fn(u8) -> Option<u8>, output: &mut Vec<u8>) -> Result<bool, CodecError> {
+    let data_length = group.iter().position(|ch| *ch == b'=').unwrap_or(group.len());
+    if let Some(index) = group[data_length..].iter().position(|ch| *ch != b'=') {
+        let position = position + data_length + index;
+        return Err(CodecError::InvalidCharacter { position, character: group[data_length + index] as char });
+    }
+    let padded = data_length < group.len();
+    let bytes = data_length * bits / 8;
+    if bytes == 0 || (bytes * 8).div_ceil(bits) != data_length {
+        return Err(if padded {
+            CodecError::InvalidPadding { position: position + data_length }
+        } else {
+            CodecError::InvalidLength { length: position + group.len() }
+        });
+    }
+    if padded && group.len() != group_chars {
+        return Err(CodecError::InvalidPadding { position: position + data_length });
+    }
+    let group_bytes = group_chars * bits / 8;
+    // bits of the last character beyond the decoded bytes must be zero,
+    // so that each byte sequence has a single encoding
+    let unused_bits = data_length * bits - bytes * 8;
+    let mut value = 0u64;
+    for (i, ch) in group[..data_length].iter().enumerate() {
+        let invalid = CodecError::InvalidCharacter { position: position + i, character: *ch as char };
+        let digit = value_of(*ch).ok_or(invalid)?;
+        if i == data_length - 1 && digit & ((1 << unused_bits) - 1) != 0 {
+            return Err(invalid);
+        }
+        value |= u64::from(digit) << (group_bytes * 8 - bits * (i + 1));
+    }
+    output.extend((0..bytes).map(|i| (value >> (8 * (group_bytes - 1 - i))) as u8));
+    Ok(padded)
+}
+
+/// Error returned by encoding and decoding operations.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum CodecError {
+    /// A character is not part of the alphabet of a binary-to-text encoding.
+    InvalidCharacter { position: usize, character: char },
+    /// Encoded data ends with an incomplete group.
+    InvalidLength { length: usize },
+    /// Padding is incomplete or followed by data.
+    InvalidPadding { position: usize },
+    /// Bytes do not form a valid sequence in a text encoding.
+    InvalidSequence { position: usize },
+    /// A character cannot be represented in a text encoding.
+    UnmappableCharacter { position: usize, character: char },
+    /// A line of an index table, or an entry given to
+    /// [`IndexTable::from_entries`], is malformed or out of range.
+    InvalidIndex { line: usize },
+}
+
+impl Display for CodecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidCharacter { position, character } => write!(f, "invalid character {character:?} at position {position}"),
+            Self::InvalidLength { length } => write!(f, "invalid encoded length {length}"),
+            Self::InvalidPadding { position } => write!(f, "invalid padding at position {position}"),
+            Self::InvalidSequence { position } => write!(f, "invalid byte sequence at position {position}"),
+            Self::UnmappableCharacter { position, character } => write!(f, "character {character:?} at position {position} cannot be encoded"),
+            Self::InvalidIndex { line } => write!(f, "invalid index table entry at line {line}"),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
@@ -0,0 +1,475 @@
+use std::{collections::HashMap, sync::Arc};
+use super::CodecError;
+use crate::bytes::Buffer;
+
+/// Code points of the bytes 0x80 to 0x9F in Windows-1252. The bytes that
+/// Windows-1252 leaves undefined map to the C1 control characters, as in
+/// the WHATWG Encoding Standard.
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+/// Shift_JIS pointers of the end-user-defined characters, which decode
+/// to the Private Use Area.
+const SHIFT_JIS_EUDC: std::ops::RangeInclusive<u32> = 8836..=10715;
+
+/// Number of Shift_JIS pointers, from 60 lead bytes of 188 trail bytes each.
+const SHIFT_JIS_POINTERS: u32 = 60 * 188;
+
+/// Number of EUC-KR pointers, from 126 lead bytes of 190 trail bytes each,
+/// which is the most of the supported double-byte encodings.
+const EUC_KR_POINTERS: u32 = 126 * 190;
+
+/// Shift_JIS pointers of NEC-selected IBM extensions, which are never
+/// used for encoding because the IBM extensions duplicate them.
+const SHIFT_JIS_NEC_IBM: std::ops::RangeInclusive<u32> = 8272..=8835;
+
+/// A text encoding, used to convert between strings and bytes.
+///
+/// Double-byte legacy encodings take the mapping table between pointers
+/// and code points as an [`IndexTable`], such as the `index-jis0208.txt`
+/// and `index-euc-kr.txt` files of the WHATWG Encoding Standard, and decode
+/// bytes as that standard does.
+///
+/// Strict decoding fails on malformed bytes, whereas lossy decoding replaces
+/// them with U+FFFD. A byte order mark is not removed by decoding; use
+/// [`TextEncoding::detect_bom`] to detect and skip it.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use rialight_util::codecs::{IndexTable, TextEncoding};
+///
+/// let table = IndexTable::parse("# part of index-jis0208.txt\n283\t0x3042\t# あ\n").unwrap();
+/// let shift_jis = TextEncoding::ShiftJis(Arc::new(table));
+/// assert_eq!(shift_jis.encode("ｱあ").unwrap(), [0xb1, 0x82, 0xa0]);
+/// assert_eq!(shift_jis.decode(&[0x82, 0xa0, 0x41]).unwrap(), "あA");
+/// assert_eq!(shift_jis.decode_lossy(&[0x82, 0xa1]), "\u{FFFD}");
+/// ```
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum TextEncoding {
+    Utf8,
+    /// UTF-16 in little-endian byte order.
+    Utf16Le,
+    /// UTF-16 in big-endian byte order.
+    Utf16Be,
+    /// ISO-8859-1, which maps each byte to the code point of the same value.
+    Latin1,
+    Windows1252,
+    /// Shift_JIS, given the JIS X 0208 index.
+    ShiftJis(Arc<IndexTable>),
+    /// EUC-KR, given the EUC-KR index.
+    EucKr(Arc<IndexTable>),
+}
+
+/// Result of decoding a character from the start of a byte sequence.
+enum Step {
+    Char(char, usize),
+    /// The bytes are the incomplete start of a sequence.
+    Incomplete,
+    /// The given number of bytes are malformed.
+    Invalid(usize),
+}
+
+impl TextEncoding {
+    /// Returns the name of the encoding.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Utf8 => "UTF-8",
+            Self::Utf16Le => "UTF-16LE",
+            Self::Utf16Be => "UTF-16BE",
+            Self::Latin1 => "ISO-8859-1",
+            Self::Windows1252 => "windows-1252",
+            Self::ShiftJis(_) => "Shift_JIS",
+            Self::EucKr(_) => "EUC-KR",
+        }
+    }
+
+    /// Detects a byte order mark at the start of bytes, returning
+    /// its encoding and length.
+    pub fn detect_bom(bytes: &[u8]) -> Option<(Self, usize)> {
+        if bytes.starts_with(b"\xEF\xBB\xBF") {
+            Some((Self::Utf8, 3))
+        } else if bytes.starts_with(b"\xFF\xFE") {
+            Some((Self::Utf16Le, 2))
+        } else if bytes.starts_with(b"\xFE\xFF") {
+            Some((Self::Utf16Be, 2))
+        } else {
+            None
+        }
+    }
+
+    /// Decodes bytes, failing on malformed bytes.
+    pub fn decode(&self, bytes: &[u8]) -> Result<String, CodecError> {
+        let mut decoder = self.decoder();
+        Ok(decoder.update(bytes)? + &decoder.finish()?)
+    }
+
+    /// Decodes bytes, replacing malformed bytes with U+FFFD.
+    pub fn decode_lossy(&self, bytes: &[u8]) -> String {
+        let mut decoder = self.decoder().lossy();
+        decoder.update(bytes).unwrap() + &decoder.finish().unwrap()
+    }
+
+    /// Returns a streaming decoder.
+    pub fn decoder(&self) -> TextDecoder {
+        TextDecoder { encoding: self.clone(), pending: vec![], position: 0, lossy: false }
+    }
+
+    /// Encodes a string, failing on characters that the encoding cannot represent.
+    pub fn encode(&self, text: &str) -> Result<Vec<u8>, CodecError> {
+        let mut output = Vec::with_capacity(text.len());
+        for (position, character) in text.char_indices() {
+            if !self.encode_char(character, &mut output) {
+                return Err(CodecError::UnmappableCharacter { position, character });
+            }
+        }
+        Ok(output)
+    }
+
+    /// Encodes a string, replacing characters that the encoding cannot
+    /// represent with `?`.
+    pub fn encode_lossy(&self, text: &str) -> Vec<u8> {
+        let mut output = Vec::with_capacity(text.len());
+        for character in text.chars() {
+            if !self.encode_char(character, &mut output) {
+                output.push(b'?');
+            }
+        }
+        output
+    }
+
+    fn encode_char(&self, ch: char, output: &mut Vec<u8>) -> bool {
+        let code = u32::from(ch);
+        match self {
+            Self::Utf8 => output.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes()),
+            Self::Utf16Le => output.extend(ch.encode_utf16(&mut [0; 2]).iter().flat_map(|unit| unit.to_le_bytes())),
+            Self::Utf16Be => output.extend(ch.encode_utf16(&mut [0; 2]).iter().flat_map(|unit| unit.to_be_bytes())),
+            Self::Latin1 if code <= 0xFF => output.push(code as u8),
+            Self::Windows1252 if code < 0x80 || (0xA0..=0xFF).contains(&code) => output.push(code as u8),
+            Self::Windows1252 => match WINDOWS_1252_HIGH.iter().position(|c| *c == ch) {
+                Some(index) => output.push(0x80 + index as u8),
+                None => return false,
+            },
+            Self::ShiftJis(_) if code <= 0x80 => output.push(code as u8),
+            Self::ShiftJis(_) if ch == '\u{A5}' => output.push(0x5C),
+            Self::ShiftJis(_) if ch == '\u{203E}' => output.push(0x7E),
+            Self::ShiftJis(_) if (0xFF61..=0xFF9F).contains(&code) => output.push((code - 0xFF61 + 0xA1) as u8),
+            Self::ShiftJis(table) => {
+                let ch = if ch == '\u{2212}' { '\u{FF0D}' } else { ch };
+                let pointer = match table.pointer(ch) {
+                    Some(pointer) if SHIFT_JIS_NEC_IBM.contains(&pointer) => table.pointer_after(ch, *SHIFT_JIS_NEC_IBM.end()),
+                    pointer => pointer,
+                };
+                let Some(pointer) = pointer.filter(|pointer| *pointer < SHIFT_JIS_POINTERS) else {
+                    return false;
+                };
+                let (lead, trail) = (pointer / 188, pointer % 188);
+                let lead_offset = if lead < 0x1F { 0x81 } else { 0xC1 };
+                let trail_offset = if trail < 0x3F { 0x40 } else { 0x41 };
+                output.extend([(lead + lead_offset) as u8, (trail + trail_offset) as u8]);
+            },
+            Self::EucKr(_) if code < 0x80 => output.push(code as u8),
+            Self::EucKr(table) => {
+                let Some(pointer) = table.pointer(ch) else {
+                    return false;
+                };
+                output.extend([(pointer / 190 + 0x81) as u8, (pointer % 190 + 0x41) as u8]);
+            },
+            Self::Latin1 => return false,
+        }
+        true
+    }
+
+    fn decode_char(&self, bytes: &[u8]) -> Step {
+        let byte = bytes[0];
+        match self {
+            Self::Utf8 => match std::str::from_utf8(&bytes[..bytes.len().min(4)]) {
+                Ok(text) => Step::Char(text.chars().next().unwrap(), text.chars().next().unwrap().len_utf8()),
+                Err(error) if error.valid_up_to() > 0 => {
+                    let ch = std::str::from_utf8(&bytes[..error.valid_up_to()]).unwrap().chars().next().unwrap();
+                    Step::Char(ch, ch.len_utf8())
+                },
+                Err(error) => error.error_len().map_or(Step::Incomplete, Step::Invalid),
+            },
+            Self::Utf16Le | Self::Utf16Be => {
+                let unit = |index: usize| -> Option<u16> {
+                    let pair = [*bytes.get(index)?, *bytes.get(index + 1)?];
+                    Some(if *self == Self::Utf16Le { u16::from_le_bytes(pair) } else { u16::from_be_bytes(pair) })
+                };
+                let Some(first) = unit(0) else {
+                    return Step::Incomplete;
+                };
+                match first {
+                    0xD800..=0xDBFF => match unit(2) {
+                        None => Step::Incomplete,
+                        Some(second @ 0xDC00..=0xDFFF) => {
+                            let code = 0x10000 + ((u32::from(first) - 0xD800) << 10) + (u32::from(second) - 0xDC00);
+                            Step::Char(char::from_u32(code).unwrap(), 4)
+                        },
+                        _ => Step::Invalid(2),
+                    },
+                    0xDC00..=0xDFFF => Step::Invalid(2),
+                    _ => Step::Char(char::from_u32(first.into()).unwrap(), 2),
+                }
+            },
+            Self::Latin1 => Step::Char(byte.into(), 1),
+            Self::Windows1252 if (0x80..=0x9F).contains(&byte) => Step::Char(WINDOWS_1252_HIGH[usize::from(byte - 0x80)], 1),
+            Self::Windows1252 => Step::Char(byte.into(), 1),
+            Self::ShiftJis(_) if byte <= 0x80 => Step::Char(byte.into(), 1),
+            Self::ShiftJis(_) if (0xA1..=0xDF).contains(&byte) => Step::Char(char::from_u32(0xFF61 + u32::from(byte) - 0xA1).unwrap(), 1),
+            Self::ShiftJis(table) if matches!(byte, 0x81..=0x9F | 0xE0..=0xFC) => {
+                let Some(&trail) = bytes.get(1) else {
+                    return Step::Incomplete;
+                };
+                let lead_offset = if byte < 0xA0 { 0x81 } else { 0xC1 };
+                let trail_offset = if trail < 0x7F { 0x40 } else { 0x41 };
+                let code_point = matches!(trail, 0x40..=0x7E | 0x80..=0xFC).then(|| {
+                    let pointer = u32::from(byte - lead_offset) * 188 + u32::from(trail - trail_offset);
+                    if SHIFT_JIS_EUDC.contains(&pointer) {
+                        char::from_u32(0xE000 + pointer - SHIFT_JIS_EUDC.start())
+                    } else {
+                        table.code_point(pointer)
+                    }
+                }).flatten();
+                double_byte_step(code_point, trail)
+            },
+            Self::EucKr(_) if byte < 0x80 => Step::Char(byte.into(), 1),
+            Self::EucKr(table) if (0x81..=0xFE).contains(&byte) => {
+                let Some(&trail) = bytes.get(1) else {
+                    return Step::Incomplete;
+                };
+                let code_point = (0x41..=0xFE).contains(&trail)
+                    .then(|| table.code_point(u32::from(byte - 0x81) * 190 + u32::from(trail - 0x41)))
+                    .flatten();
+                double_byte_step(code_point, trail)
+            },
+            Self::ShiftJis(_) | Self::EucKr(_) => Step::Invalid(1),
+        }
+    }
+}
+
+/// Returns the step for a double-byte sequence. When the sequence is
+/// malformed, an ASCII trail byte is decoded on its own.
+fn double_byte_step(code_point: Option<char>, trail: u8) -> Step {
+    match code_point {
+        Some(ch) => Step::Char(ch, 2),
+        None if trail.is_ascii() => Step::Invalid(1),
+        None => Step::Invalid(2),
+    }
+}
+
+/// A streaming decoder of a [`TextEncoding`], which keeps incomplete
+/// sequences at the end of a chunk until the next chunk.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::codecs::TextEncoding;
+///
+/// let mut decoder = TextEncoding::Utf8.decoder();
+/// assert_eq!(decoder.update(&b"caf\xC3"[..]).unwrap(), "caf");
+/// assert_eq!(decoder.update(&b"\xA9!"[..]).unwrap(), "é!");
+/// assert_eq!(decoder.finish().unwrap(), "");
+/// ```
+#[derive(Clone, Debug)]
+pub struct TextDecoder {
+    encoding: TextEncoding,
+    pending: Vec<u8>,
+    position: usize,
+    lossy: bool,
+}
+
+impl TextDecoder {
+    /// Replaces malformed bytes with U+FFFD instead of failing.
+    pub fn lossy(mut self) -> Self {
+        self.lossy = true;
+        self
+    }
+
+    pub fn encoding(&self) -> &TextEncoding {
+        &self.encoding
+    }
+
+    /// Decodes the complete characters given so far.
+    pub fn update(&mut self, mut input: impl Buffer) -> Result<String, CodecError> {
+        while input.has_remaining() {
+            let length = input.chunk().len();
+            self.pending.extend_from_slice(input.chunk());
+            input.advance(length);
+        }
+        self.decode_pending(false)
+    }
+
+    /// Decodes the remaining bytes, which are malformed if they
+    /// end with an incomplete sequence.
+    pub fn finish(mut self) -> Result<String, CodecError> {
+        self.decode_pending(true)
+    }
+
+    fn decode_pending(&mut self, last: bool) -> Result<String, CodecError> {
+        let mut output = String::new();
+        let mut offset = 0;
+        while offset < self.pending.len() {
+            let bytes = &self.pending[offset..];
+            let length = match self.encoding.decode_char(bytes) {
+                Step::Char(ch, length) => {
+                    output.push(ch);
+                    offset += length;
+                    continue;
+                },
+                Step::Incomplete if !last => break,
+                Step::Incomplete => bytes.len(),
+                Step::Invalid(length) => length,
+            };
+            if !self.lossy {
+                return Err(CodecError::InvalidSequence { position: self.position + offset });
+            }
+            output.push('\u{FFFD}');
+            offset += length;
+        }
+        self.pending.drain(..offset);
+        self.position += offset;
+        Ok(output)
+    }
+}
+
+/// A mapping table between pointers and code points of a double-byte
+/// legacy encoding.
+///
+/// When several pointers map to the same code point, encoding uses
+/// the lowest pointer. Pointers are below 23,940, the size of the EUC-KR
+/// index, and pointers beyond the range of Shift_JIS are not used by it.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct IndexTable {
+    code_points: Vec<Option<char>>,
+    pointers: HashMap<char, u32>,
+}
+
+impl IndexTable {
+    /// Parses an index in the format of the WHATWG Encoding Standard, where
+    /// each line contains a decimal pointer and a hexadecimal code point
+    /// separated by whitespace, and `#` starts a comment.
+    pub fn parse(text: &str) -> Result<Self, CodecError> {
+        let mut table = Self::default();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap();
+            let mut fields = line.split_whitespace();
+            let Some(pointer) = fields.next() else {
+                continue;
+            };
+            let error = CodecError::InvalidIndex { line: index + 1 };
+            let pointer = pointer.parse::<u32>().map_err(|_| error)?;
+            let code_point = fields.next()
+                .and_then(|field| field.strip_prefix("0x"))
+                .and_then(|field| u32::from_str_radix(field, 16).ok())
+                .and_then(char::from_u32)
+                .ok_or(error)?;
+            table.insert(pointer, code_point).ok_or(error)?;
+        }
+        Ok(table)
+    }
+
+    /// Constructs a table from pairs of pointer and code point, failing
+    /// with the position of the first pair, counted from 1, whose pointer
+    /// is out of range.
+    pub fn from_entries(entries: impl IntoIterator<Item = (u32, char)>) -> Result<Self, CodecError> {
+        let mut table = Self::default();
+        for (index, (pointer, code_point)) in entries.into_iter().enumerate() {
+            table.insert(pointer, code_point).ok_or(CodecError::InvalidIndex { line: index + 1 })?;
+        }
+        Ok(table)
+    }
+
+    fn insert(&mut self, pointer: u32, code_point: char) -> Option<()> {
+        if pointer >= EUC_KR_POINTERS {
+            return None;
+        }
+        let index = pointer as usize;
+        if index >= self.code_points.len() {
+            self.code_points.resize(index + 1, None);
+        }
+        self.code_points[index] = Some(code_point);
+        self.pointers.entry(code_point).and_modify(|p| *p = pointer.min(*p)).or_insert(pointer);
+        Some(())
+    }
+
+    /// Returns the code point of a pointer.
+    pub fn code_point(&self, pointer: u32) -> Option<char> {
+        self.code_points.get(pointer as usize).copied().flatten()
+    }
+
+    /// Returns the lowest pointer of a code point.
+    pub fn pointer(&self, code_point: char) -> Option<u32> {
+        self.pointers.get(&code_point).copied()
+    }
+
+    /// Returns the lowest pointer of a code point greater than `after`.
+    fn pointer_after(&self, code_point: char, after: u32) -> Option<u32> {
+        self.code_points.iter().enumerate().skip(after as usize + 1)
+            .find(|(_, c)| **c == Some(code_point))
+            .map(|(pointer, _)| pointer as u32)
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.code_points.iter().flatten().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pointers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unicode() {
+        assert_eq!(TextEncoding::Utf16Be.encode("a😀").unwrap(), [0x00, 0x61, 0xD8, 0x3D, 0xDE, 0x00]);
+        assert_eq!(TextEncoding::Utf16Le.decode(&[0x3D, 0xD8, 0x00, 0xDE]).unwrap(), "😀");
+        assert_eq!(TextEncoding::Utf16Le.decode(&[0x00, 0xDE]), Err(CodecError::InvalidSequence { position: 0 }));
+        assert_eq!(TextEncoding::Utf16Le.decode_lossy(&[0x61, 0x00, 0x3D, 0xD8, 0x61]), "a\u{FFFD}");
+        assert_eq!(TextEncoding::Utf8.decode_lossy(b"a\xFFb\xE2\x82"), "a\u{FFFD}b\u{FFFD}");
+        assert_eq!(TextEncoding::detect_bom(b"\xFF\xFEa\x00"), Some((TextEncoding::Utf16Le, 2)));
+
+        let mut decoder = TextEncoding::Utf16Le.decoder();
+        assert_eq!(decoder.update(&[0x61, 0x00, 0x3D][..]).unwrap(), "a");
+        assert_eq!(decoder.update(&[0xD8, 0x00][..]).unwrap(), "");
+        assert_eq!(decoder.update(&[0xDE][..]).unwrap(), "😀");
+        assert_eq!(decoder.finish().unwrap(), "");
+    }
+
+    #[test]
+    fn single_byte() {
+        assert_eq!(TextEncoding::Latin1.decode(b"caf\xE9").unwrap(), "café");
+        assert_eq!(TextEncoding::Latin1.encode("a€"), Err(CodecError::UnmappableCharacter { position: 1, character: '€' }));
+        assert_eq!(TextEncoding::Windows1252.encode("€5 – ÿ").unwrap(), b"\x805 \x96 \xFF");
+        assert_eq!(TextEncoding::Windows1252.decode(b"\x81").unwrap(), "\u{81}");
+    }
+
+    #[test]
+    fn double_byte() {
+        let shift_jis = TextEncoding::ShiftJis(Arc::new(IndexTable::from_entries([(283, 'あ'), (8272, '纊'), (10716, '纊'), (11280, 'い')]).unwrap()));
+        assert_eq!(shift_jis.encode("¥あ纊").unwrap(), [0x5C, 0x82, 0xA0, 0xFA, 0x40]);
+        assert_eq!(shift_jis.decode(&[0xED, 0x40, 0xFA, 0x40]).unwrap(), "纊纊");
+        assert_eq!(shift_jis.decode(&[0xF0, 0x40]).unwrap(), "\u{E000}");
+        assert_eq!(shift_jis.decode_lossy(&[0x82, 0x41, 0x82]), "\u{FFFD}A\u{FFFD}");
+        assert_eq!(shift_jis.encode("い"), Err(CodecError::UnmappableCharacter { position: 0, character: 'い' }));
+
+        let table = IndexTable::parse("9026\t0xAC00\t가 (<CJK>)\n\n# comment\n9027 0xAC01\n").unwrap();
+        assert_eq!(table.len(), 2);
+        let euc_kr = TextEncoding::EucKr(Arc::new(table));
+        assert_eq!(euc_kr.encode("a가각").unwrap(), [0x61, 0xB0, 0xA1, 0xB0, 0xA2]);
+        assert_eq!(euc_kr.decode(&[0xB0, 0xA2, 0x61]).unwrap(), "각a");
+        assert_eq!(euc_kr.decode(&[0xB0, 0xFF]), Err(CodecError::InvalidSequence { position: 0 }));
+        assert_eq!(IndexTable::parse("1 0x41\nx 0x42"), Err(CodecError::InvalidIndex { line: 2 }));
+        assert_eq!(IndexTable::parse("1 0x41\n4294967295 0x42"), Err(CodecError::InvalidIndex { line: 2 }));
+        assert_eq!(IndexTable::from_entries([(23939, 'a'), (23940, 'b')]), Err(CodecError::InvalidIndex { line: 2 }));
+    }
+}
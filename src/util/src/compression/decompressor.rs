@@ -0,0 +1,248 @@
+use futures::{AsyncRead, AsyncReadExt};
+#[cfg(not(target_arch = "wasm32"))]
+use zstd::stream::raw::{InBuffer, Operation, OutBuffer};
+use super::{CompressionError, CompressionFormat, CHUNK_SIZE};
+#[cfg(not(target_arch = "wasm32"))]
+use super::ZSTD_WINDOW_LOG;
+use crate::bytes::{Buffer, Bytes, BytesMut};
+
+const GZIP_FLAG_HEADER_CRC: u8 = 0x02;
+const GZIP_FLAG_EXTRA: u8 = 0x04;
+const GZIP_FLAG_NAME: u8 = 0x08;
+const GZIP_FLAG_COMMENT: u8 = 0x10;
+
+/// A streaming decompressor, which returns decompressed data as it
+/// becomes available and fails once it exceeds a size limit.
+///
+/// Incomplete data is kept until the next chunk, and [`Decompressor::finish`]
+/// fails if the compressed data ends early.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::compression::{CompressionFormat, Decompressor};
+///
+/// let compressed = CompressionFormat::Deflate.compress(&b"level data"[..]);
+/// let (first, second) = compressed.split_at(3);
+///
+/// let mut decompressor = Decompressor::new(CompressionFormat::Deflate, 64 * 1024);
+/// let mut data = decompressor.update(first).unwrap().to_vec();
+/// data.extend_from_slice(&decompressor.update(second).unwrap());
+/// data.extend_from_slice(&decompressor.finish().unwrap());
+/// assert_eq!(data, b"level data");
+/// ```
+pub struct Decompressor {
+    format: CompressionFormat,
+    limit: usize,
+    length: usize,
+    engine: Engine,
+    stage: Stage,
+    pending: Vec<u8>,
+    crc: flate2::Crc,
+}
+
+enum Engine {
+    Flate(Box<flate2::Decompress>),
+    #[cfg(not(target_arch = "wasm32"))]
+    Zstd(Box<zstd::stream::raw::Decoder<'static>>),
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Stage {
+    GzipHeader,
+    Body,
+    GzipTrailer,
+    End,
+}
+
+impl Decompressor {
+    /// Constructs a decompressor that fails once the decompressed
+    /// data exceeds `limit` bytes.
+    pub fn new(format: CompressionFormat, limit: usize) -> Self {
+        let engine = match format {
+            CompressionFormat::Deflate | CompressionFormat::Gzip => Engine::Flate(Box::new(flate2::Decompress::new(false))),
+            CompressionFormat::Zlib => Engine::Flate(Box::new(flate2::Decompress::new(true))),
+            #[cfg(not(target_arch = "wasm32"))]
+            CompressionFormat::Zstd => {
+                // frames declaring a larger window fail with invalid data
+                let window_log = limit.checked_next_power_of_two().map_or(usize::BITS, usize::trailing_zeros).clamp(ZSTD_WINDOW_LOG, 31);
+                let mut decoder = zstd::stream::raw::Decoder::new().unwrap();
+                decoder.set_parameter(zstd::stream::raw::DParameter::WindowLogMax(window_log)).unwrap();
+                Engine::Zstd(Box::new(decoder))
+            },
+        };
+        let stage = if format == CompressionFormat::Gzip { Stage::GzipHeader } else { Stage::Body };
+        Self { format, limit, length: 0, engine, stage, pending: vec![], crc: flate2::Crc::new() }
+    }
+
+    /// Returns the format of the compressed data.
+    pub fn format(&self) -> CompressionFormat {
+        self.format
+    }
+
+    /// Returns the maximum size of the decompressed data, in bytes.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Decompresses data, returning the decompressed data available so far.
+    pub fn update(&mut self, mut input: impl Buffer) -> Result<Bytes, CompressionError> {
+        while input.has_remaining() {
+            let length = input.chunk().len();
+            self.pending.extend_from_slice(input.chunk());
+            input.advance(length);
+        }
+        let mut output = BytesMut::new();
+        self.run(&mut output)?;
+        Ok(output.freeze())
+    }
+
+    /// Returns the remaining decompressed data, failing if the
+    /// compressed data is incomplete.
+    pub fn finish(mut self) -> Result<Bytes, CompressionError> {
+        let mut output = BytesMut::new();
+        self.run(&mut output)?;
+        if self.stage != Stage::End {
+            return Err(CompressionError::invalid_data("unexpected end of compressed data"));
+        }
+        Ok(output.freeze())
+    }
+
+    /// Decompresses all data read from an asynchronous reader.
+    pub async fn decompress_reader(mut self, mut reader: impl AsyncRead + Unpin) -> Result<Bytes, CompressionError> {
+        let mut output = BytesMut::new();
+        let mut buffer = vec![0; CHUNK_SIZE];
+        loop {
+            let length = reader.read(&mut buffer).await?;
+            if length == 0 {
+                break;
+            }
+            output.extend_from_slice(&self.update(&buffer[..length])?);
+        }
+        output.extend_from_slice(&self.finish()?);
+        Ok(output.freeze())
+    }
+
+    /// Decompresses as much of the pending data as possible.
+    fn run(&mut self, output: &mut BytesMut) -> Result<(), CompressionError> {
+        let mut buffer = vec![0; CHUNK_SIZE];
+        loop {
+            match self.stage {
+                Stage::GzipHeader => {
+                    let Some(length) = gzip_header_length(&self.pending)? else {
+                        return Ok(());
+                    };
+                    self.pending.drain(..length);
+                    self.stage = Stage::Body;
+                },
+                Stage::Body => {
+                    let (consumed, produced, end) = self.engine.run(&self.pending, &mut buffer)?;
+                    self.pending.drain(..consumed);
+                    self.length += produced;
+                    if self.length > self.limit {
+                        return Err(CompressionError::LimitExceeded { limit: self.limit });
+                    }
+                    if self.format == CompressionFormat::Gzip {
+                        self.crc.update(&buffer[..produced]);
+                    }
+                    output.extend_from_slice(&buffer[..produced]);
+                    if end {
+                        self.stage = if self.format == CompressionFormat::Gzip { Stage::GzipTrailer } else { Stage::End };
+                    } else if consumed == 0 && produced == 0 {
+                        return Ok(());
+                    }
+                },
+                Stage::GzipTrailer => {
+                    let Some(trailer) = self.pending.get(..8) else {
+                        return Ok(());
+                    };
+                    let crc = u32::from_le_bytes(trailer[..4].try_into().unwrap());
+                    let length = u32::from_le_bytes(trailer[4..].try_into().unwrap());
+                    if crc != self.crc.sum() || length != self.crc.amount() {
+                        return Err(CompressionError::invalid_data("gzip checksum mismatch"));
+                    }
+                    self.pending.drain(..8);
+                    self.stage = Stage::End;
+                },
+                Stage::End if self.pending.is_empty() => return Ok(()),
+                // another gzip member or zstd frame follows
+                Stage::End => match (self.format, &mut self.engine) {
+                    (CompressionFormat::Gzip, Engine::Flate(decompress)) => {
+                        decompress.reset(false);
+                        self.crc.reset();
+                        self.stage = Stage::GzipHeader;
+                    },
+                    #[cfg(not(target_arch = "wasm32"))]
+                    (CompressionFormat::Zstd, _) => self.stage = Stage::Body,
+                    _ => return Err(CompressionError::invalid_data("unexpected data after the end of the compressed stream")),
+                },
+            }
+        }
+    }
+}
+
+impl Engine {
+    /// Decompresses from `input` into `buffer`, returning the number
+    /// of bytes consumed and produced and whether the stream ended.
+    fn run(&mut self, input: &[u8], buffer: &mut [u8]) -> Result<(usize, usize, bool), CompressionError> {
+        match self {
+            Self::Flate(decompress) => {
+                let (total_in, total_out) = (decompress.total_in(), decompress.total_out());
+                let status = decompress.decompress(input, buffer, flate2::FlushDecompress::None)
+                    .map_err(CompressionError::invalid_data)?;
+                let consumed = (decompress.total_in() - total_in) as usize;
+                let produced = (decompress.total_out() - total_out) as usize;
+                Ok((consumed, produced, status == flate2::Status::StreamEnd))
+            },
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Zstd(decoder) => {
+                let mut input = InBuffer::around(input);
+                let mut output = OutBuffer::around(buffer);
+                // a hint of zero indicates that a frame is complete
+                let hint = decoder.run(&mut input, &mut output).map_err(CompressionError::invalid_data)?;
+                Ok((input.pos(), output.pos(), hint == 0))
+            },
+        }
+    }
+}
+
+impl std::fmt::Debug for Decompressor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Decompressor")
+            .field("format", &self.format)
+            .field("limit", &self.limit)
+            .field("length", &self.length)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the length of a gzip member header at the start of `bytes`,
+/// or `None` if the header is incomplete.
+fn gzip_header_length(bytes: &[u8]) -> Result<Option<usize>, CompressionError> {
+    if !bytes.iter().zip([0x1F, 0x8B, 0x08]).all(|(byte, expected)| *byte == expected) {
+        return Err(CompressionError::invalid_data("invalid gzip header"));
+    }
+    if bytes.len() < 10 {
+        return Ok(None);
+    }
+    let flags = bytes[3];
+    let mut length = 10;
+    if flags & GZIP_FLAG_EXTRA != 0 {
+        let Some(extra_length) = bytes.get(10..12) else {
+            return Ok(None);
+        };
+        length += 2 + usize::from(u16::from_le_bytes([extra_length[0], extra_length[1]]));
+    }
+    for flag in [GZIP_FLAG_NAME, GZIP_FLAG_COMMENT] {
+        if flags & flag != 0 {
+            let Some(end) = bytes.get(length..).and_then(|rest| rest.iter().position(|byte| *byte == 0)) else {
+                return Ok(None);
+            };
+            length += end + 1;
+        }
+    }
+    if flags & GZIP_FLAG_HEADER_CRC != 0 {
+        length += 2;
+    }
+    Ok((bytes.len() >= length).then_some(length))
+}
@@ -0,0 +1,376 @@
+/*!
+Compression and decompression in the deflate, gzip, zlib and zstd formats.
+
+Data is compressed at once through [`CompressionFormat::compress`] or in chunks
+through a [`Compressor`], and decompressed at once through [`CompressionFormat::decompress`]
+or in chunks through a [`Decompressor`]. Chunks are given as any
+[`Buffer`](crate::bytes::Buffer), such as `Bytes`, `BytesMut` or a chain of buffers,
+and data can also be read from an asynchronous reader.
+
+Decompression takes a limit on the size of the decompressed data, which guards
+against small inputs that decompress to huge outputs, known as decompression bombs.
+The limit is checked as the data is decompressed, so memory use stays within it.
+zstd frames also need a window of recent output, which is bounded by the limit
+or 8 MiB, whichever is greater; frames that declare a larger window are rejected.
+
+The zstd format is not available when targeting WebAssembly.
+
+# Example
+
+```
+use rialight_util::compression::{CompressionError, CompressionFormat, CompressionLevel, Compressor};
+
+let data = "save game ".repeat(1000);
+let compressed = CompressionFormat::Gzip.compress(data.as_bytes());
+assert!(compressed.len() < 100);
+assert_eq!(CompressionFormat::detect(&compressed), Some(CompressionFormat::Gzip));
+assert_eq!(CompressionFormat::Gzip.decompress(compressed.clone(), 1 << 20).unwrap(), data.as_bytes());
+assert_eq!(CompressionFormat::Gzip.decompress(compressed, 1000), Err(CompressionError::LimitExceeded { limit: 1000 }));
+
+let mut compressor = Compressor::new(CompressionFormat::Zstd).with_level(CompressionLevel::Best);
+let mut compressed = compressor.update(data.as_bytes()).to_vec();
+compressed.extend_from_slice(&compressor.finish());
+assert_eq!(CompressionFormat::Zstd.decompress(&compressed[..], 1 << 20).unwrap(), data.as_bytes());
+```
+*/
+
+use std::{fmt::Display, io::Write};
+use futures::{AsyncRead, AsyncReadExt};
+use crate::bytes::{Buffer, Bytes, BytesMut};
+
+mod decompressor;
+pub use decompressor::Decompressor;
+
+/// Size of the chunks read from asynchronous readers and produced by decompression.
+const CHUNK_SIZE: usize = 32 * 1024;
+
+/// Base 2 logarithm of the largest zstd window accepted regardless of the
+/// decompression limit, and of the largest window used by compression.
+#[cfg(not(target_arch = "wasm32"))]
+const ZSTD_WINDOW_LOG: u32 = 23;
+
+/// A compressed data format.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum CompressionFormat {
+    /// Raw DEFLATE data (RFC 1951), without header or checksum.
+    Deflate,
+    /// The gzip file format (RFC 1952). Decompression accepts
+    /// several concatenated members.
+    Gzip,
+    /// The zlib format (RFC 1950), DEFLATE data with a header and an Adler-32 checksum.
+    Zlib,
+    /// The Zstandard format (RFC 8878). Decompression accepts
+    /// several concatenated frames. Not available on WebAssembly.
+    #[cfg(not(target_arch = "wasm32"))]
+    Zstd,
+}
+
+impl CompressionFormat {
+    /// Detects the format of compressed data from its first bytes.
+    /// Raw deflate data has no header and is never detected.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [0x1F, 0x8B, ..] => Some(Self::Gzip),
+            #[cfg(not(target_arch = "wasm32"))]
+            [0x28, 0xB5, 0x2F, 0xFD, ..] => Some(Self::Zstd),
+            [cmf, flg, ..] if cmf & 0x0F == 8 && cmf >> 4 <= 7 && (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0 => Some(Self::Zlib),
+            _ => None,
+        }
+    }
+
+    /// Compresses data at the default level.
+    pub fn compress(self, input: impl Buffer) -> Bytes {
+        let mut compressor = Compressor::new(self);
+        let mut output = BytesMut::from(&compressor.update(input)[..]);
+        output.extend_from_slice(&compressor.finish());
+        output.freeze()
+    }
+
+    /// Decompresses data, failing if the decompressed data exceeds `limit` bytes.
+    pub fn decompress(self, input: impl Buffer, limit: usize) -> Result<Bytes, CompressionError> {
+        let mut decompressor = Decompressor::new(self, limit);
+        let mut output = BytesMut::from(&decompressor.update(input)?[..]);
+        output.extend_from_slice(&decompressor.finish()?);
+        Ok(output.freeze())
+    }
+}
+
+/// The trade-off between compression speed and compressed size.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+pub enum CompressionLevel {
+    Fastest,
+    #[default]
+    Default,
+    /// The smallest output at a reasonable speed.
+    Best,
+    /// A level specific to the format, from 0 to 9 for the DEFLATE-based
+    /// formats and from 1 to 22 for zstd. Levels out of range are clamped.
+    Precise(i32),
+}
+
+impl CompressionLevel {
+    fn flate_level(self) -> flate2::Compression {
+        flate2::Compression::new(match self {
+            Self::Fastest => 1,
+            Self::Default => 6,
+            Self::Best => 9,
+            Self::Precise(level) => level.clamp(0, 9) as u32,
+        })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn zstd_level(self) -> i32 {
+        match self {
+            Self::Fastest => 1,
+            Self::Default => zstd::DEFAULT_COMPRESSION_LEVEL,
+            Self::Best => 19,
+            Self::Precise(level) => level.clamp(1, *zstd::compression_level_range().end()),
+        }
+    }
+}
+
+/// A streaming compressor, which returns compressed data as it
+/// becomes available.
+pub struct Compressor {
+    format: CompressionFormat,
+    encoder: Encoder,
+}
+
+enum Encoder {
+    Deflate(flate2::write::DeflateEncoder<Vec<u8>>),
+    Gzip(flate2::write::GzEncoder<Vec<u8>>),
+    Zlib(flate2::write::ZlibEncoder<Vec<u8>>),
+    #[cfg(not(target_arch = "wasm32"))]
+    Zstd(zstd::stream::write::Encoder<'static, Vec<u8>>),
+}
+
+impl Compressor {
+    /// Constructs a compressor at the default level.
+    pub fn new(format: CompressionFormat) -> Self {
+        Self { format, encoder: Self::encoder(format, CompressionLevel::Default) }
+    }
+
+    /// Sets the compression level. This must be called before
+    /// any data is given.
+    pub fn with_level(mut self, level: CompressionLevel) -> Self {
+        self.encoder = Self::encoder(self.format, level);
+        self
+    }
+
+    fn encoder(format: CompressionFormat, level: CompressionLevel) -> Encoder {
+        match format {
+            CompressionFormat::Deflate => Encoder::Deflate(flate2::write::DeflateEncoder::new(vec![], level.flate_level())),
+            CompressionFormat::Gzip => Encoder::Gzip(flate2::write::GzEncoder::new(vec![], level.flate_level())),
+            CompressionFormat::Zlib => Encoder::Zlib(flate2::write::ZlibEncoder::new(vec![], level.flate_level())),
+            #[cfg(not(target_arch = "wasm32"))]
+            CompressionFormat::Zstd => {
+                let mut encoder = zstd::stream::write::Encoder::new(vec![], level.zstd_level()).unwrap();
+                // the highest levels use windows the decompressor may reject under small limits
+                if level.zstd_level() > 19 {
+                    encoder.window_log(ZSTD_WINDOW_LOG).unwrap();
+                }
+                Encoder::Zstd(encoder)
+            },
+        }
+    }
+
+    /// Returns the format of the compressed data.
+    pub fn format(&self) -> CompressionFormat {
+        self.format
+    }
+
+    /// Compresses data, returning the compressed data available so far.
+    pub fn update(&mut self, mut input: impl Buffer) -> Bytes {
+        // the encoders write into memory, so they do not fail
+        while input.has_remaining() {
+            let length = input.chunk().len();
+            match &mut self.encoder {
+                Encoder::Deflate(encoder) => encoder.write_all(input.chunk()),
+                Encoder::Gzip(encoder) => encoder.write_all(input.chunk()),
+                Encoder::Zlib(encoder) => encoder.write_all(input.chunk()),
+                #[cfg(not(target_arch = "wasm32"))]
+                Encoder::Zstd(encoder) => encoder.write_all(input.chunk()),
+            }.unwrap();
+            input.advance(length);
+        }
+        let output = match &mut self.encoder {
+            Encoder::Deflate(encoder) => encoder.get_mut(),
+            Encoder::Gzip(encoder) => encoder.get_mut(),
+            Encoder::Zlib(encoder) => encoder.get_mut(),
+            #[cfg(not(target_arch = "wasm32"))]
+            Encoder::Zstd(encoder) => encoder.get_mut(),
+        };
+        std::mem::take(output).into()
+    }
+
+    /// Finishes the compressed data, returning its remaining bytes.
+    pub fn finish(self) -> Bytes {
+        match self.encoder {
+            Encoder::Deflate(encoder) => encoder.finish(),
+            Encoder::Gzip(encoder) => encoder.finish(),
+            Encoder::Zlib(encoder) => encoder.finish(),
+            #[cfg(not(target_arch = "wasm32"))]
+            Encoder::Zstd(encoder) => encoder.finish(),
+        }.unwrap().into()
+    }
+
+    /// Compresses all data read from an asynchronous reader.
+    pub async fn compress_reader(mut self, mut reader: impl AsyncRead + Unpin) -> Result<Bytes, CompressionError> {
+        let mut output = BytesMut::new();
+        let mut buffer = vec![0; CHUNK_SIZE];
+        loop {
+            let length = reader.read(&mut buffer).await?;
+            if length == 0 {
+                break;
+            }
+            output.extend_from_slice(&self.update(&buffer[..length]));
+        }
+        output.extend_from_slice(&self.finish());
+        Ok(output.freeze())
+    }
+}
+
+impl std::fmt::Debug for Compressor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Compressor").field("format", &self.format).finish_non_exhaustive()
+    }
+}
+
+/// Error returned by decompression and by reading data to compress.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum CompressionError {
+    /// Compressed data is malformed or ends early.
+    InvalidData { message: String },
+    /// Decompressed data exceeds the size limit.
+    LimitExceeded { limit: usize },
+    /// Reading from an asynchronous reader failed.
+    Io { kind: std::io::ErrorKind, message: String },
+}
+
+impl CompressionError {
+    fn invalid_data(message: impl Display) -> Self {
+        Self::InvalidData { message: message.to_string() }
+    }
+}
+
+impl Display for CompressionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidData { message } => write!(f, "invalid compressed data: {message}"),
+            Self::LimitExceeded { limit } => write!(f, "decompressed data exceeds the limit of {limit} bytes"),
+            Self::Io { message, .. } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for CompressionError {}
+
+impl From<std::io::Error> for CompressionError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io { kind: error.kind(), message: error.to_string() }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bytes::Buffer;
+
+    const FORMATS: &[CompressionFormat] = &[
+        CompressionFormat::Deflate,
+        CompressionFormat::Gzip,
+        CompressionFormat::Zlib,
+        #[cfg(not(target_arch = "wasm32"))]
+        CompressionFormat::Zstd,
+    ];
+
+    /// Formats whose decompression accepts concatenated streams.
+    const CONCATENATED_FORMATS: &[CompressionFormat] = &[
+        CompressionFormat::Gzip,
+        #[cfg(not(target_arch = "wasm32"))]
+        CompressionFormat::Zstd,
+    ];
+
+    #[test]
+    fn round_trip() {
+        let data: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8 ^ (i / 1000) as u8).collect();
+        for &format in FORMATS {
+            for level in [CompressionLevel::Fastest, CompressionLevel::Best, CompressionLevel::Precise(4)] {
+                let mut compressor = Compressor::new(format).with_level(level);
+                let mut compressed = BytesMut::new();
+                for chunk in data.chunks(7000) {
+                    compressed.extend_from_slice(&compressor.update(chunk));
+                }
+                compressed.extend_from_slice(&compressor.finish());
+                assert!(compressed.len() < data.len() / 2, "{format:?}");
+
+                let mut decompressor = Decompressor::new(format, data.len());
+                let mut decompressed = BytesMut::new();
+                for chunk in compressed.chunks(1000) {
+                    decompressed.extend_from_slice(&decompressor.update(chunk).unwrap());
+                }
+                decompressed.extend_from_slice(&decompressor.finish().unwrap());
+                assert_eq!(decompressed, data, "{format:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn concatenation() {
+        for &format in CONCATENATED_FORMATS {
+            let input = format.compress(&b"abc"[..]).chain(format.compress(&b"def"[..]));
+            assert_eq!(format.decompress(input, 6).unwrap(), &b"abcdef"[..]);
+        }
+        let input = CompressionFormat::Zlib.compress(&b"abc"[..]).chain(&b"!"[..]);
+        assert!(matches!(CompressionFormat::Zlib.decompress(input, 6), Err(CompressionError::InvalidData { .. })));
+    }
+
+    #[test]
+    fn invalid_data() {
+        for &format in FORMATS {
+            let compressed = format.compress(&[1u8; 10_000][..]);
+            let truncated = compressed.slice(..compressed.len() - 1);
+            assert!(matches!(format.decompress(truncated, 10_000), Err(CompressionError::InvalidData { .. })), "{format:?}");
+            assert!(matches!(format.decompress(Bytes::new(), 10_000), Err(CompressionError::InvalidData { .. })), "{format:?}");
+            assert_eq!(format.decompress(compressed, 9_999), Err(CompressionError::LimitExceeded { limit: 9_999 }));
+        }
+        let mut corrupted = CompressionFormat::Gzip.compress(&b"hello"[..]).to_vec();
+        let length = corrupted.len();
+        corrupted[length - 8] ^= 1;
+        assert!(matches!(CompressionFormat::Gzip.decompress(&corrupted[..], 100), Err(CompressionError::InvalidData { .. })));
+        let mut corrupted = CompressionFormat::Zlib.compress(&b"hello"[..]).to_vec();
+        let length = corrupted.len();
+        corrupted[length - 1] ^= 1;
+        assert!(matches!(CompressionFormat::Zlib.decompress(&corrupted[..], 100), Err(CompressionError::InvalidData { .. })));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn zstd_window() {
+        let mut encoder = zstd::stream::write::Encoder::new(vec![], 3).unwrap();
+        encoder.window_log(27).unwrap();
+        encoder.write_all(b"hello").unwrap();
+        let compressed = encoder.finish().unwrap();
+        assert!(matches!(CompressionFormat::Zstd.decompress(&compressed[..], 1 << 20), Err(CompressionError::InvalidData { .. })));
+        assert_eq!(CompressionFormat::Zstd.decompress(&compressed[..], 1 << 27).unwrap(), &b"hello"[..]);
+
+        let data = "save game ".repeat(1000);
+        let mut compressor = Compressor::new(CompressionFormat::Zstd).with_level(CompressionLevel::Precise(22));
+        let mut compressed = compressor.update(data.as_bytes()).to_vec();
+        compressed.extend_from_slice(&compressor.finish());
+        assert_eq!(CompressionFormat::Zstd.decompress(&compressed[..], data.len()).unwrap(), data.as_bytes());
+    }
+
+    #[test]
+    fn readers() {
+        futures::executor::block_on(async {
+            let data = b"asset pack ".repeat(500);
+            let compressed = Compressor::new(CompressionFormat::Zlib).compress_reader(&data[..]).await.unwrap();
+            assert_eq!(CompressionFormat::detect(&compressed), Some(CompressionFormat::Zlib));
+            let decompressed = Decompressor::new(CompressionFormat::Zlib, data.len()).decompress_reader(&compressed[..]).await.unwrap();
+            assert_eq!(decompressed, data);
+            let result = Decompressor::new(CompressionFormat::Zlib, 100).decompress_reader(&compressed[..]).await;
+            assert_eq!(result, Err(CompressionError::LimitExceeded { limit: 100 }));
+        });
+    }
+}
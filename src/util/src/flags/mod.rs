@@ -144,13 +144,13 @@ The following methods are defined for the generated `struct`s:
 - `all`: the set of all defined flags
 - `bits`: the raw value of the flags currently stored
 - `from_bits`: convert from underlying bit representation, unless that
-               representation contains bits that do not correspond to a
-               defined flag
+  representation contains bits that do not correspond to a
+  defined flag
 - `from_bits_truncate`: convert from underlying bit representation, dropping
-                        any bits that do not correspond to defined flags
+  any bits that do not correspond to defined flags
 - `from_bits_unchecked`: convert from underlying bit representation, keeping
-                         all bits (even those not corresponding to defined
-                         flags)
+  all bits (even those not corresponding to defined
+  flags)
 - `is_empty`: `true` if no flags are currently stored
 - `is_all`: `true` if currently set flags exactly equal all defined flags
 - `intersects`: `true` if there are flags common to both `self` and `other`
@@ -158,20 +158,20 @@ The following methods are defined for the generated `struct`s:
 - `insert`: inserts the specified flags in-place
 - `remove`: removes the specified flags in-place
 - `toggle`: the specified flags will be inserted if not present, and removed
-            if they are.
+  if they are.
 - `set`: inserts or removes the specified flags depending on the passed value
 - `intersection`: returns a new set of flags, containing only the flags present
-                  in both `self` and `other` (the argument to the function).
+  in both `self` and `other` (the argument to the function).
 - `union`: returns a new set of flags, containing any flags present in
-           either `self` or `other` (the argument to the function).
+  either `self` or `other` (the argument to the function).
 - `difference`: returns a new set of flags, containing all flags present in
-                `self` without any of the flags present in `other` (the
-                argument to the function).
+  `self` without any of the flags present in `other` (the
+  argument to the function).
 - `symmetric_difference`: returns a new set of flags, containing all flags
-                          present in either `self` or `other` (the argument
-                          to the function), but not both.
+  present in either `self` or `other` (the argument
+  to the function), but not both.
 - `complement`: returns a new set of flags, containing all flags which are
-                not set in `self`, but which are allowed for this type.
+  not set in `self`, but which are allowed for this type.
 
 ## Default
 
@@ -0,0 +1,287 @@
+use std::sync::Mutex;
+use crate::timing::{background_timeout, wait, CancellationToken, Duration, Instant};
+
+/// Delays the execution of a function until a given duration
+/// has elapsed without another call, such as for autosaving after
+/// the user stops typing.
+///
+/// Each call to [`Debounce::call`] cancels the previously scheduled
+/// function, if it has not been executed yet.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::{futures::Debounce, timing::Duration};
+///
+/// fn on_input(debounce: &Debounce, text: String) {
+///     debounce.call(move || {
+///         println!("autosaving {text}");
+///     });
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Debounce {
+    duration: Duration,
+    scheduled: Mutex<Option<CancellationToken>>,
+}
+
+impl Debounce {
+    /// Creates a `Debounce` that executes functions
+    /// after `duration` of inactivity.
+    pub fn new(duration: Duration) -> Self {
+        Self { duration, scheduled: Mutex::new(None) }
+    }
+
+    /// Returns the duration of inactivity after which
+    /// functions are executed.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Schedules `callback` to be executed after the debounce duration,
+    /// cancelling the previously scheduled function.
+    pub fn call<F>(&self, callback: F)
+    where
+        F: FnOnce() + 'static,
+    {
+        let timeout = background_timeout(callback, self.duration);
+        if let Some(previous) = self.scheduled.lock().unwrap().replace(timeout.cancellation_token()) {
+            previous.cancel();
+        }
+    }
+
+    /// Cancels the scheduled function, if any.
+    pub fn cancel(&self) {
+        if let Some(scheduled) = self.scheduled.lock().unwrap().take() {
+            scheduled.cancel();
+        }
+    }
+}
+
+impl Drop for Debounce {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// Limits an operation to once per given duration, such as for
+/// handling scroll events or spacing out network requests.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::{futures::Throttle, timing::Duration};
+///
+/// async fn poll_server(throttle: &Throttle) {
+///     loop {
+///         // at most once per second
+///         throttle.ready().await;
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Throttle {
+    duration: Duration,
+    last: Mutex<Option<Instant>>,
+}
+
+impl Throttle {
+    /// Creates a `Throttle` allowing one operation per `duration`.
+    pub fn new(duration: Duration) -> Self {
+        Self { duration, last: Mutex::new(None) }
+    }
+
+    /// Returns the minimum duration between two operations.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns `true` and reserves the current time slot if
+    /// the operation is allowed now; otherwise returns `false`.
+    pub fn try_ready(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// Waits until the operation is allowed, reserving
+    /// the time slot.
+    pub async fn ready(&self) {
+        loop {
+            let remaining = self.remaining();
+            if remaining.is_zero() {
+                return;
+            }
+            wait(remaining).await;
+        }
+    }
+
+    /// Returns the time remaining until the next operation is allowed,
+    /// reserving the time slot if it is zero.
+    fn remaining(&self) -> Duration {
+        let now = Instant::now();
+        let mut last = self.last.lock().unwrap();
+        let remaining = last.map_or(Duration::ZERO, |last| self.duration.saturating_sub(now.since(last)));
+        if remaining.is_zero() {
+            *last = Some(now);
+        }
+        remaining
+    }
+}
+
+/// A token bucket rate limiter. The bucket holds up to a capacity
+/// of tokens and gains one token every refill period; each operation
+/// takes one token.
+///
+/// This allows bursts of up to the capacity while limiting the average
+/// rate to one operation per refill period. The bucket starts full.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::{futures::TokenBucket, timing::Duration};
+///
+/// async fn send_messages(bucket: &TokenBucket, messages: Vec<String>) {
+///     for message in messages {
+///         bucket.acquire().await;
+///         // send message
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct TokenBucket {
+    capacity: u32,
+    refill_period: Duration,
+    state: Mutex<TokenBucketState>,
+}
+
+#[derive(Debug)]
+struct TokenBucketState {
+    tokens: u32,
+    last_refill: Option<Instant>,
+}
+
+impl TokenBucket {
+    /// Creates a full token bucket.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `refill_period` is zero.
+    pub fn new(capacity: u32, refill_period: Duration) -> Self {
+        assert!(!refill_period.is_zero(), "rialight::util::futures::TokenBucket must be given non-zero refill period");
+        Self {
+            capacity,
+            refill_period,
+            state: Mutex::new(TokenBucketState { tokens: capacity, last_refill: None }),
+        }
+    }
+
+    /// Returns the maximum number of tokens.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Returns the duration after which one token is added to the bucket.
+    pub fn refill_period(&self) -> Duration {
+        self.refill_period
+    }
+
+    /// Returns the number of tokens currently available.
+    pub fn available(&self) -> u32 {
+        let mut state = self.state.lock().unwrap();
+        self.refill(&mut state, Instant::now());
+        state.tokens
+    }
+
+    /// Takes a token if one is available, returning whether
+    /// the operation is allowed.
+    pub fn try_acquire(&self) -> bool {
+        self.time_until_token().is_zero()
+    }
+
+    /// Waits until a token is available and takes it.
+    pub async fn acquire(&self) {
+        loop {
+            let remaining = self.time_until_token();
+            if remaining.is_zero() {
+                return;
+            }
+            wait(remaining).await;
+        }
+    }
+
+    /// Takes a token if one is available, returning zero; otherwise
+    /// returns the time remaining until the next token is added.
+    fn time_until_token(&self) -> Duration {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        self.refill(&mut state, now);
+        if state.tokens != 0 {
+            state.tokens -= 1;
+            return Duration::ZERO;
+        }
+        let last_refill = state.last_refill.unwrap_or(now);
+        (last_refill + self.refill_period).since(now).max(Duration::from_nanos(1))
+    }
+
+    fn refill(&self, state: &mut TokenBucketState, now: Instant) {
+        let Some(last_refill) = state.last_refill else {
+            state.last_refill = Some(now);
+            return;
+        };
+        if state.tokens >= self.capacity {
+            state.last_refill = Some(now);
+            return;
+        }
+        let periods = now.since(last_refill).as_nanos() / self.refill_period.as_nanos();
+        let added: u32 = periods.try_into().unwrap_or(u32::MAX);
+        if added == 0 {
+            return;
+        }
+        state.tokens = state.tokens.saturating_add(added).min(self.capacity);
+        state.last_refill = Some(if state.tokens == self.capacity { now } else { last_refill + self.refill_period * added });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::timing::TestClock;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn debounce() {
+        let clock = TestClock::new();
+        let calls = Rc::new(RefCell::new(vec![]));
+        let debounce = Debounce::new(Duration::from_millis(100));
+        for i in 0..3 {
+            let guard = clock.enter();
+            let calls = Rc::clone(&calls);
+            debounce.call(move || calls.borrow_mut().push((i, TestClock::current().unwrap().elapsed())));
+            drop(guard);
+            clock.advance(Duration::from_millis(50));
+        }
+        clock.advance(Duration::from_millis(100));
+        assert_eq!(*calls.borrow(), [(2, Duration::from_millis(200))]);
+    }
+
+    #[test]
+    fn throttle_and_token_bucket() {
+        let clock = TestClock::new();
+        clock.resume();
+        clock.block_on(async {
+            let throttle = Throttle::new(Duration::from_millis(100));
+            assert!(throttle.try_ready());
+            assert!(!throttle.try_ready());
+            throttle.ready().await;
+            assert_eq!(clock.elapsed(), Duration::from_millis(100));
+
+            let bucket = TokenBucket::new(2, Duration::from_millis(10));
+            assert!(bucket.try_acquire());
+            assert!(bucket.try_acquire());
+            assert!(!bucket.try_acquire());
+            bucket.acquire().await;
+            assert_eq!(clock.elapsed(), Duration::from_millis(110));
+            clock.advance(Duration::from_millis(100));
+            assert_eq!(bucket.available(), 2);
+        });
+    }
+}
@@ -18,18 +18,52 @@ exec_future(async {
 });
 # }
 ```
+
+# Tasks
+
+[`spawn`] executes a future as a task and returns a [`JoinHandle`],
+which can be awaited for the task's output or used to abort the task.
+A [`TaskGroup`] aborts all of its tasks when dropped.
+
+# Flow control
+
+[`Debounce`], [`Throttle`] and [`TokenBucket`] limit how often
+an operation runs, based on the timing API.
+
+# Retrying
+
+[`retry`] and [`retry_if`] retry a failing operation with
+exponential backoff, as configured by a [`RetryPolicy`].
+
+# Streams
+
+The [`Stream`] trait represents an asynchronous sequence of values,
+with combinators provided by [`StreamExt`]. Observables convert from
+and to streams through the [`observable`](crate::observable) module.
 */
 
 use crate::incorrect_runtime_panic;
 
 pub use std::future::Future;
 
+mod task;
+pub use task::{spawn, JoinError, JoinHandle, TaskGroup};
+
+mod flow_control;
+pub use flow_control::{Debounce, Throttle, TokenBucket};
+
+mod retry;
+pub use retry::{retry, retry_if, Jitter, RetryError, RetryPolicy};
+
 /// Executes a future without awaiting for its completion. Its result
-/// is ignored.
+/// is ignored. Use [`spawn`] to await or abort the future instead.
 /// 
 /// The following example uses an `async` block of the Rust language,
 /// which returns a future.
 /// 
+/// While a [`TestClock`](crate::timing::TestClock) is entered, the future
+/// is executed by the test clock instead of the runtime.
+/// 
 /// # Example
 /// 
 /// ```
@@ -46,8 +80,12 @@ pub fn exec_future<F>(future: F)
 where
     F: Future<Output = ()> + 'static,
 {
+    if let Some(clock) = crate::timing::TestClock::current() {
+        clock.spawn(future);
+        return;
+    }
     #[cfg(not(any(feature = "rialight_default_export", feature = "rialight_browser_export")))] {
-        let _ = future;
+        drop(future);
         incorrect_runtime_panic!();
     }
     #[cfg(feature = "rialight_default_export")] {
@@ -60,6 +98,8 @@ where
 
 pub use futures::future::ready as ready_future;
 
+pub use futures::stream::{self, Stream, StreamExt};
+
 /// The `future_race` function takes an iterable of futures as input and returns
 /// a single [`Future`]. The returned future completes with
 /// a group (_v_, _i_), where _v_ is the output from the first
@@ -74,7 +114,7 @@ pub use futures::future::ready as ready_future;
 /// 
 /// ```
 /// # use rialight_util::futures::*;
-/// # async fn f() {
+/// # async fn f(list_of_futures: Vec<std::pin::Pin<Box<dyn Future<Output = ()>>>>) {
 /// let (value, index) = future_race(list_of_futures).await;
 /// # }
 /// ```
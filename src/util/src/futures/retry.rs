@@ -0,0 +1,287 @@
+use std::{
+    collections::hash_map::RandomState,
+    fmt::Display,
+    hash::{BuildHasher, Hasher},
+};
+use super::Future;
+use crate::timing::{timeout_at, wait, Duration, Instant};
+
+/// Randomization applied to the delays of a [`RetryPolicy`], which prevents
+/// many clients from retrying at the same instants.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum Jitter {
+    /// Uses the computed delay unchanged.
+    None,
+    /// Uses a random delay between zero and the computed delay.
+    #[default]
+    Full,
+    /// Uses half of the computed delay plus a random delay between
+    /// zero and the other half.
+    Equal,
+}
+
+/// Configures how [`retry`] and [`retry_if`] retry a failing operation
+/// with exponential backoff.
+///
+/// The delay before the retry following the _n_-th failed attempt is
+/// `base_delay * multiplier^(n - 1)`, limited to `max_delay` and randomized
+/// according to `jitter`.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::{futures::*, timing::Duration};
+///
+/// let policy = RetryPolicy {
+///     max_attempts: 10,
+///     deadline: Some(Duration::from_secs(60)),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one. Default: 5.
+    pub max_attempts: u32,
+    /// Delay before the first retry. Default: 100 milliseconds.
+    pub base_delay: Duration,
+    /// Maximum delay between two attempts. Default: 30 seconds.
+    pub max_delay: Duration,
+    /// Factor by which the delay grows after each attempt. Default: 2.
+    pub multiplier: f64,
+    /// Randomization of the delays. Default: [`Jitter::Full`].
+    pub jitter: Jitter,
+    /// Overall duration, from the first attempt, after which no more
+    /// attempts are made and the current attempt is cancelled. Default: `None`.
+    pub deadline: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: Jitter::default(),
+            deadline: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns the delay before the retry following
+    /// the given failed attempt, counted from 1.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay = self.base_delay.as_secs_f64() * self.multiplier.powi(exponent);
+        let delay = if delay.is_finite() {
+            Duration::from_secs_f64(delay.clamp(0.0, self.max_delay.as_secs_f64()))
+        } else {
+            self.max_delay
+        };
+        match self.jitter {
+            Jitter::None => delay,
+            Jitter::Full => delay.mul_f64(random_fraction()),
+            Jitter::Equal => delay / 2 + (delay / 2).mul_f64(random_fraction()),
+        }
+    }
+}
+
+/// Returns a random number in the range `0.0..=1.0`.
+fn random_fraction() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+    (random >> 11) as f64 / (u64::MAX >> 11) as f64
+}
+
+/// Error returned by [`retry`] and [`retry_if`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum RetryError<E> {
+    /// The last attempt failed with an error that is not retryable,
+    /// or the maximum number of attempts was reached.
+    Failed {
+        error: E,
+        attempts: u32,
+    },
+    /// The deadline elapsed. Contains the error of the last
+    /// completed attempt, if any.
+    DeadlineElapsed {
+        last_error: Option<E>,
+        attempts: u32,
+    },
+}
+
+impl<E> RetryError<E> {
+    /// Returns the number of attempts made, including
+    /// an attempt cancelled by the deadline.
+    pub fn attempts(&self) -> u32 {
+        match self {
+            Self::Failed { attempts, .. } => *attempts,
+            Self::DeadlineElapsed { attempts, .. } => *attempts,
+        }
+    }
+
+    /// Returns the error of the last completed attempt, if any.
+    pub fn into_last_error(self) -> Option<E> {
+        match self {
+            Self::Failed { error, .. } => Some(error),
+            Self::DeadlineElapsed { last_error, .. } => last_error,
+        }
+    }
+}
+
+impl<E: Display> Display for RetryError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Failed { error, attempts } => write!(f, "Operation failed after {attempts} attempts: {error}"),
+            Self::DeadlineElapsed { last_error: Some(error), attempts } => write!(f, "Retry deadline elapsed after {attempts} attempts: {error}"),
+            Self::DeadlineElapsed { last_error: None, attempts } => write!(f, "Retry deadline elapsed after {attempts} attempts"),
+        }
+    }
+}
+
+impl<E: std::error::Error> std::error::Error for RetryError<E> {}
+
+/// Retries a fallible asynchronous operation according to `policy`
+/// until it succeeds, treating every error as retryable.
+///
+/// The delays between attempts are awaited with [`wait`], so this
+/// function works on every Rialight runtime.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::futures::*;
+///
+/// async fn load() -> Result<String, std::io::Error> {
+///     # Ok(String::new())
+///     // ...
+/// }
+///
+/// async fn example_fn() {
+///     let content = retry(&RetryPolicy::default(), load).await;
+/// }
+/// ```
+pub async fn retry<T, E, F, Fut>(policy: &RetryPolicy, operation: F) -> Result<T, RetryError<E>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    retry_if(policy, |_| true, operation).await
+}
+
+/// Retries a fallible asynchronous operation according to `policy`
+/// until it succeeds or fails with an error for which `is_retryable`
+/// returns `false`.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::futures::*;
+/// use std::io::ErrorKind;
+///
+/// async fn load() -> Result<String, std::io::Error> {
+///     # Ok(String::new())
+///     // ...
+/// }
+///
+/// async fn example_fn() {
+///     let content = retry_if(
+///         &RetryPolicy::default(),
+///         |error| error.kind() != ErrorKind::NotFound,
+///         load,
+///     ).await;
+/// }
+/// ```
+pub async fn retry_if<T, E, P, F, Fut>(policy: &RetryPolicy, mut is_retryable: P, mut operation: F) -> Result<T, RetryError<E>>
+where
+    P: FnMut(&E) -> bool,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let deadline = policy.deadline.map(|deadline| Instant::now() + deadline);
+    let mut attempts = 0;
+    let mut last_error = None;
+    loop {
+        attempts += 1;
+        let result = match deadline {
+            Some(deadline) => match timeout_at(deadline, operation()).await {
+                Ok(result) => result,
+                Err(_) => return Err(RetryError::DeadlineElapsed { last_error, attempts }),
+            },
+            None => operation().await,
+        };
+        let error = match result {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+        if attempts >= policy.max_attempts || !is_retryable(&error) {
+            return Err(RetryError::Failed { error, attempts });
+        }
+        let delay = policy.delay(attempts);
+        if let Some(deadline) = deadline {
+            if Instant::now() + delay >= deadline {
+                return Err(RetryError::DeadlineElapsed { last_error: Some(error), attempts });
+            }
+        }
+        last_error = Some(error);
+        wait(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::timing::TestClock;
+    use std::cell::Cell;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy { jitter: Jitter::None, ..Default::default() }
+    }
+
+    #[test]
+    fn backoff() {
+        let clock = TestClock::new();
+        clock.resume();
+        let attempts = Cell::new(0);
+        let result = clock.block_on(retry(&policy(), || {
+            attempts.set(attempts.get() + 1);
+            async { if attempts.get() < 3 { Err("unavailable") } else { Ok(attempts.get()) } }
+        }));
+        assert_eq!(result, Ok(3));
+        assert_eq!(clock.elapsed(), Duration::from_millis(300));
+
+        let result = clock.block_on(retry_if(&policy(), |error| *error != "not found", || async { Err::<(), _>("not found") }));
+        assert_eq!(result, Err(RetryError::Failed { error: "not found", attempts: 1 }));
+
+        let result = clock.block_on(retry(&policy(), || async { Err::<(), _>("unavailable") }));
+        assert_eq!(result.unwrap_err().attempts(), 5);
+    }
+
+    #[test]
+    fn deadline() {
+        let clock = TestClock::new();
+        clock.resume();
+        let policy = RetryPolicy { deadline: Some(Duration::from_millis(250)), ..policy() };
+        let result = clock.block_on(retry(&policy, || async { Err::<(), _>("unavailable") }));
+        assert_eq!(result, Err(RetryError::DeadlineElapsed { last_error: Some("unavailable"), attempts: 2 }));
+        assert_eq!(clock.elapsed(), Duration::from_millis(100));
+
+        let result = clock.block_on(retry(&policy, || async {
+            wait(Duration::from_secs(1)).await;
+            Ok::<_, ()>(())
+        }));
+        assert_eq!(result, Err(RetryError::DeadlineElapsed { last_error: None, attempts: 1 }));
+        assert_eq!(clock.elapsed(), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn jitter() {
+        let policy = RetryPolicy { jitter: Jitter::Equal, ..Default::default() };
+        for attempt in 1..20 {
+            let delay = policy.delay(attempt);
+            let max = RetryPolicy { jitter: Jitter::None, ..policy.clone() }.delay(attempt);
+            assert!(delay >= max / 2 && delay <= max);
+        }
+        assert!(policy.delay(40) <= policy.max_delay);
+    }
+}
@@ -0,0 +1,306 @@
+use std::{
+    any::Any,
+    fmt::{Debug, Display},
+    panic::AssertUnwindSafe,
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll},
+};
+use futures::{channel::oneshot, FutureExt};
+use super::{exec_future, Future};
+use crate::timing::CancellationToken;
+
+/// Error returned by a [`JoinHandle`] when its task did not complete.
+pub struct JoinError {
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+impl JoinError {
+    fn aborted() -> Self {
+        Self { panic: None }
+    }
+
+    /// Indicates whether the task was aborted.
+    pub fn is_aborted(&self) -> bool {
+        self.panic.is_none()
+    }
+
+    /// Indicates whether the task panicked.
+    pub fn is_panic(&self) -> bool {
+        self.panic.is_some()
+    }
+
+    /// Returns the payload of the panic of the task, or the
+    /// error itself if the task was aborted.
+    ///
+    /// The payload can be resumed with [`std::panic::resume_unwind`].
+    pub fn try_into_panic(self) -> Result<Box<dyn Any + Send>, Self> {
+        match self.panic {
+            Some(panic) => Ok(panic),
+            None => Err(self),
+        }
+    }
+}
+
+impl Debug for JoinError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_aborted() { write!(f, "JoinError::Aborted") } else { write!(f, "JoinError::Panicked(..)") }
+    }
+}
+
+impl Display for JoinError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_aborted() { write!(f, "Task aborted") } else { write!(f, "Task panicked") }
+    }
+}
+
+impl std::error::Error for JoinError {}
+
+/// Handle to a task spawned by [`spawn`] or [`TaskGroup::spawn`].
+///
+/// Awaiting the handle completes with the output of the task, or with
+/// a [`JoinError`] if the task was aborted or panicked. Dropping the
+/// handle detaches the task, which keeps running.
+pub struct JoinHandle<T> {
+    token: CancellationToken,
+    finished: CancellationToken,
+    result: oneshot::Receiver<Result<T, JoinError>>,
+}
+
+impl<T> JoinHandle<T> {
+    /// Aborts the task. The task is dropped the next time
+    /// it would be polled and its handle completes with
+    /// an aborted [`JoinError`].
+    ///
+    /// Aborting a finished task has no effect.
+    pub fn abort(&self) {
+        self.token.cancel();
+    }
+
+    /// Indicates whether the task has finished, either by
+    /// completing, panicking or being aborted.
+    pub fn is_finished(&self) -> bool {
+        self.finished.is_cancelled()
+    }
+
+    /// Returns the token that aborts the task when cancelled.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+}
+
+impl<T> Debug for JoinHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JoinHandle").field("finished", &self.is_finished()).finish_non_exhaustive()
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.result.poll_unpin(cx) {
+            Poll::Ready(Ok(result)) => Poll::Ready(result),
+            // the runtime dropped the task before it finished
+            Poll::Ready(Err(oneshot::Canceled)) => Poll::Ready(Err(JoinError::aborted())),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Spawns a future as a task and returns a [`JoinHandle`] to it.
+///
+/// Unlike [`exec_future`], the output of the task can be awaited and
+/// the task can be aborted. A panic inside the task is caught and
+/// reported by the handle; note that on the browser export, panics
+/// abort the program instead, as WebAssembly does not unwind.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::futures::spawn;
+///
+/// async fn example_fn() {
+///     let handle = spawn(async {
+///         // asynchronous code
+///         10
+///     });
+///     assert_eq!(handle.await.unwrap(), 10);
+/// }
+/// ```
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + 'static,
+    F::Output: 'static,
+{
+    let token = CancellationToken::new();
+    let finished = CancellationToken::new();
+    let (sender, result) = oneshot::channel();
+    exec_future({
+        let (token, finished) = (token.clone(), finished.clone());
+        async move {
+            let output = match token.run(AssertUnwindSafe(future).catch_unwind()).await {
+                Ok(Ok(output)) => Ok(output),
+                Ok(Err(panic)) => Err(JoinError { panic: Some(panic) }),
+                Err(_) => Err(JoinError::aborted()),
+            };
+            finished.cancel();
+            let _ = sender.send(output);
+        }
+    });
+    JoinHandle { token, finished, result }
+}
+
+/// A group of tasks that are aborted together. Dropping the group
+/// aborts every task spawned through it that has not finished, so the
+/// tasks do not outlive the scope that owns the group.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::{futures::TaskGroup, timing::*};
+///
+/// async fn example_fn() {
+///     let group = TaskGroup::new();
+///     for i in 0..4 {
+///         group.spawn(async move {
+///             wait(Duration::from_secs(i)).await;
+///         });
+///     }
+///     // wait for every task; dropping `group` instead would abort them
+///     group.join_all().await;
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct TaskGroup {
+    tasks: Mutex<Vec<TaskGroupEntry>>,
+}
+
+#[derive(Debug)]
+struct TaskGroupEntry {
+    token: CancellationToken,
+    finished: CancellationToken,
+}
+
+impl TaskGroup {
+    /// Creates an empty task group.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawns a future as a task of the group.
+    /// See [`spawn`] for details.
+    pub fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        let handle = spawn(future);
+        let mut tasks = self.tasks.lock().unwrap();
+        tasks.retain(|task| !task.finished.is_cancelled());
+        tasks.push(TaskGroupEntry { token: handle.cancellation_token(), finished: handle.finished.clone() });
+        handle
+    }
+
+    /// Returns the number of tasks of the group that have not finished.
+    pub fn len(&self) -> usize {
+        self.tasks.lock().unwrap().iter().filter(|task| !task.finished.is_cancelled()).count()
+    }
+
+    /// Indicates whether every task of the group has finished.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Aborts every task of the group.
+    pub fn abort_all(&self) {
+        for task in std::mem::take(&mut *self.tasks.lock().unwrap()) {
+            task.token.cancel();
+        }
+    }
+
+    /// Waits until every task of the group has finished,
+    /// including tasks spawned while waiting.
+    pub async fn join_all(&self) {
+        loop {
+            let pending = self.tasks.lock().unwrap().iter()
+                .find(|task| !task.finished.is_cancelled())
+                .map(|task| task.finished.clone());
+            match pending {
+                Some(finished) => finished.cancelled().await,
+                None => return,
+            }
+        }
+    }
+}
+
+impl Drop for TaskGroup {
+    fn drop(&mut self) {
+        self.abort_all();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::timing::{wait, Duration, TestClock};
+    use std::{cell::Cell, rc::Rc};
+
+    #[test]
+    fn join_handles() {
+        let clock = TestClock::new();
+        clock.resume();
+        clock.block_on(async {
+            let handle = spawn(async {
+                wait(Duration::from_millis(10)).await;
+                10
+            });
+            assert!(!handle.is_finished());
+            assert_eq!(handle.await.unwrap(), 10);
+
+            let handle = spawn(wait(Duration::from_secs(1)));
+            handle.abort();
+            assert!(handle.await.unwrap_err().is_aborted());
+
+            let handle = spawn(async { panic!("task failure") });
+            let panic = handle.await.unwrap_err().try_into_panic().unwrap();
+            assert_eq!(panic.downcast_ref::<&str>(), Some(&"task failure"));
+        });
+        assert_eq!(clock.elapsed(), Duration::from_millis(10));
+    }
+
+    #[test]
+    fn task_groups() {
+        let clock = TestClock::new();
+        let _guard = clock.enter();
+        let completed = Rc::new(Cell::new(0));
+        let group = TaskGroup::new();
+        let handles: Vec<_> = (1..=3).map(|i| {
+            let completed = Rc::clone(&completed);
+            group.spawn(async move {
+                wait(Duration::from_millis(i * 100)).await;
+                completed.set(completed.get() + 1);
+            })
+        }).collect();
+        clock.advance(Duration::from_millis(150));
+        assert_eq!((completed.get(), group.len()), (1, 2));
+        drop(group);
+        clock.run_until_stalled();
+        assert_eq!(clock.pending_timers(), 0);
+        assert_eq!(completed.get(), 1);
+        assert!(handles.iter().all(JoinHandle::is_finished));
+
+        let group = TaskGroup::new();
+        for i in 1..=3 {
+            let completed = Rc::clone(&completed);
+            group.spawn(async move {
+                wait(Duration::from_millis(i * 100)).await;
+                completed.set(completed.get() + 1);
+            });
+        }
+        clock.resume();
+        clock.block_on(group.join_all());
+        assert!(group.is_empty());
+        assert_eq!(completed.get(), 4);
+    }
+}
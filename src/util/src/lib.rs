@@ -10,10 +10,13 @@ pub mod collections;
 pub mod collection_literals;
 pub mod flags;
 pub mod bytes;
+pub mod codecs;
+pub mod compression;
 pub mod serialization;
 pub mod reg_exp;
 pub mod uri;
 pub mod observable;
+pub mod signals;
 pub mod string;
 pub mod timing;
 pub mod futures;
@@ -0,0 +1,467 @@
+use std::{cmp::Ordering, fmt::Display, hash::{Hash, Hasher}, ops::{Add, Mul, Neg, Sub}, str::FromStr};
+use num_integer::Integer;
+use num_traits::{Signed, Zero};
+use crate::serialization::{Deserialize, Deserializer, Serialize, Serializer, generic_deserialization};
+use super::{f64_parts, BigInt, CommaSeparated, ParseNumberError, Rational};
+
+/// How to round a value that lies between two representable values.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum RoundingMode {
+    /// Rounds away from zero.
+    Up,
+    /// Rounds towards zero.
+    Down,
+    /// Rounds towards positive infinity.
+    Ceiling,
+    /// Rounds towards negative infinity.
+    Floor,
+    /// Rounds to the nearest value, with ties away from zero.
+    HalfUp,
+    /// Rounds to the nearest value, with ties towards zero.
+    HalfDown,
+    /// Rounds to the nearest value, with ties to the even value.
+    HalfEven,
+}
+
+/// Divides `numerator` by a positive `denominator`, rounding the quotient
+/// to an integer.
+pub(super) fn round_quotient(numerator: &BigInt, denominator: &BigInt, mode: RoundingMode) -> BigInt {
+    let (quotient, remainder) = numerator.div_rem(denominator);
+    if remainder.is_zero() {
+        return quotient;
+    }
+    let positive = numerator.is_positive();
+    let away = match mode {
+        RoundingMode::Up => true,
+        RoundingMode::Down => false,
+        RoundingMode::Ceiling => positive,
+        RoundingMode::Floor => !positive,
+        RoundingMode::HalfUp | RoundingMode::HalfDown | RoundingMode::HalfEven => match (remainder.abs() * 2u32).cmp(denominator) {
+            Ordering::Greater => true,
+            Ordering::Less => false,
+            Ordering::Equal => match mode {
+                RoundingMode::HalfUp => true,
+                RoundingMode::HalfDown => false,
+                _ => quotient.is_odd(),
+            },
+        },
+    };
+    if !away {
+        quotient
+    } else if positive {
+        quotient + 1u32
+    } else {
+        quotient - 1u32
+    }
+}
+
+/// Returns `10^exponent`, or `None` if the exponent does not fit in a `u32`.
+pub(super) fn pow10(exponent: u64) -> Option<BigInt> {
+    Some(BigInt::from(10u32).pow(u32::try_from(exponent).ok()?))
+}
+
+pub(super) const SCALE_OUT_OF_RANGE: &str = "decimal scale out of range";
+
+/// Maximum absolute exponent of a parsed decimal, which bounds the powers
+/// of ten computed for decimals parsed from untrusted input.
+const MAX_PARSED_EXPONENT: u64 = 10_000;
+
+/// An arbitrary-precision decimal number, consisting of an unscaled
+/// integer and a scale, the number of digits after the decimal point.
+///
+/// Addition, subtraction and multiplication are exact. Division and rounding
+/// take an explicit scale and [`RoundingMode`]. Equality and ordering
+/// compare values, so `1.0` equals `1.00`, although they display differently.
+///
+/// A decimal is serialized as a string, which preserves every digit.
+///
+/// # Panics
+///
+/// Arithmetic and rounding panic if they scale a decimal by more
+/// than `u32::MAX` digits.
+///
+/// ```
+/// use rialight_util::number::{BigDecimal, CommaSeparated, RoundingMode};
+///
+/// let price: BigDecimal = "19.99".parse().unwrap();
+/// let total = &price * &BigDecimal::from(3) - "0.5".parse::<BigDecimal>().unwrap();
+/// assert_eq!(total.to_string(), "59.47");
+///
+/// let share = total.divide(&BigDecimal::from(4), 2, RoundingMode::HalfEven).unwrap();
+/// assert_eq!(share.to_string(), "14.87");
+/// assert_eq!(format!("{share:.1}"), "14.9");
+/// assert_eq!("1234567.5".parse::<BigDecimal>().unwrap().comma_separated(), "1,234,567.5");
+/// ```
+#[derive(Clone, Debug)]
+pub struct BigDecimal {
+    unscaled: BigInt,
+    scale: i64,
+}
+
+impl BigDecimal {
+    /// Constructs a decimal equal to `unscaled × 10^-scale`.
+    pub fn new(unscaled: BigInt, scale: i64) -> Self {
+        Self { unscaled, scale }
+    }
+
+    /// Returns zero.
+    pub fn zero() -> Self {
+        Self::new(BigInt::zero(), 0)
+    }
+
+    /// Returns the unscaled integer.
+    pub fn unscaled(&self) -> &BigInt {
+        &self.unscaled
+    }
+
+    /// Returns the number of digits after the decimal point, which
+    /// is negative for trailing zeros before it.
+    pub fn scale(&self) -> i64 {
+        self.scale
+    }
+
+    /// Returns the number of significant digits of the unscaled integer.
+    pub fn precision(&self) -> u64 {
+        self.unscaled.magnitude().to_string().len() as u64
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.unscaled.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.unscaled.is_negative()
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::new(self.unscaled.abs(), self.scale)
+    }
+
+    /// Returns the unscaled integer for a scale not less than the current one.
+    fn unscaled_at(&self, scale: i64) -> BigInt {
+        &self.unscaled * pow10(scale.abs_diff(self.scale)).expect(SCALE_OUT_OF_RANGE)
+    }
+
+    /// Returns the position of the leading digit relative to the decimal
+    /// point, within one digit, estimated from the bit length.
+    fn leading_digit_position(&self) -> i128 {
+        (self.unscaled.bits() as f64 * std::f64::consts::LOG10_2) as i128 - i128::from(self.scale)
+    }
+
+    /// Rounds to `scale` digits after the decimal point. A greater scale
+    /// than the current one appends zeros.
+    pub fn round(&self, scale: i64, mode: RoundingMode) -> Self {
+        if scale >= self.scale {
+            return Self::new(self.unscaled_at(scale), scale);
+        }
+        Self::new(round_quotient(&self.unscaled, &pow10(self.scale.abs_diff(scale)).expect(SCALE_OUT_OF_RANGE), mode), scale)
+    }
+
+    /// Rounds to at most `digits` significant digits.
+    pub fn round_to_precision(&self, digits: u64, mode: RoundingMode) -> Self {
+        let precision = self.precision();
+        if precision <= digits {
+            return self.clone();
+        }
+        let rounded = self.round(self.scale - (precision - digits) as i64, mode);
+        // rounding up may carry into a new digit, such as 999 to 1000
+        if rounded.precision() > digits {
+            rounded.round(rounded.scale - 1, mode)
+        } else {
+            rounded
+        }
+    }
+
+    /// Removes trailing zeros after the decimal point, keeping the value.
+    pub fn normalized(&self) -> Self {
+        if self.is_zero() {
+            return Self::zero();
+        }
+        let mut result = self.clone();
+        let ten = BigInt::from(10u32);
+        while result.scale > 0 {
+            let (quotient, remainder) = result.unscaled.div_rem(&ten);
+            if !remainder.is_zero() {
+                break;
+            }
+            result = Self::new(quotient, result.scale - 1);
+        }
+        result
+    }
+
+    /// Divides by `divisor`, rounding the quotient to `scale` digits after the
+    /// decimal point, or returns `None` if the divisor is zero.
+    pub fn divide(&self, divisor: &Self, scale: i64, mode: RoundingMode) -> Option<Self> {
+        Some(Rational::from(self.clone()).checked_div(&Rational::from(divisor.clone()))?.to_decimal(scale, mode))
+    }
+
+    /// Converts the exact binary value of a `f64`, or returns `None`
+    /// if it is infinite or NaN. `0.1` converts to
+    /// `0.1000000000000000055511151231257827021181583404541015625`.
+    pub fn from_f64(value: f64) -> Option<Self> {
+        let (negative, mantissa, exponent) = f64_parts(value)?;
+        let mut unscaled = BigInt::from(mantissa);
+        let mut scale = 0;
+        if exponent >= 0 {
+            unscaled <<= exponent as usize;
+        } else {
+            unscaled *= BigInt::from(5u32).pow(exponent.unsigned_abs());
+            scale = -exponent as i64;
+        }
+        Some(Self::new(if negative { -unscaled } else { unscaled }, scale).normalized())
+    }
+
+    /// Converts the shortest decimal that rounds to a `f64`, or returns `None`
+    /// if it is infinite or NaN. `0.1` converts to `0.1`.
+    pub fn from_f64_lossy(value: f64) -> Option<Self> {
+        if !value.is_finite() {
+            return None;
+        }
+        format!("{value:e}").parse().ok()
+    }
+
+    /// Converts to the nearest `f64`, which may lose precision or
+    /// be infinite.
+    pub fn to_f64_lossy(&self) -> f64 {
+        format!("{}e{}", self.unscaled, -self.scale).parse().unwrap()
+    }
+
+    fn to_plain_string(&self) -> String {
+        let digits = self.unscaled.magnitude().to_string();
+        let sign = if self.is_negative() { "-" } else { "" };
+        if self.scale <= 0 {
+            let zeros = if self.is_zero() { 0 } else { self.scale.unsigned_abs() as usize };
+            return format!("{sign}{digits}{}", "0".repeat(zeros));
+        }
+        let scale = self.scale as usize;
+        let digits = format!("{digits:0>width$}", width = scale + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        format!("{sign}{integer}.{fraction}")
+    }
+}
+
+impl Default for BigDecimal {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl PartialEq for BigDecimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for BigDecimal {}
+
+impl PartialOrd for BigDecimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigDecimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let ordering = self.unscaled.sign().cmp(&other.unscaled.sign());
+        if ordering != Ordering::Equal || self.is_zero() {
+            return ordering;
+        }
+        // decimals whose leading digits are apart are ordered without
+        // scaling them, which could take any time and memory
+        let (position, other_position) = (self.leading_digit_position(), other.leading_digit_position());
+        if position.abs_diff(other_position) > 1 {
+            let ordering = position.cmp(&other_position);
+            return if self.is_negative() { ordering.reverse() } else { ordering };
+        }
+        let scale = self.scale.max(other.scale);
+        self.unscaled_at(scale).cmp(&other.unscaled_at(scale))
+    }
+}
+
+impl Hash for BigDecimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let normalized = self.normalized();
+        normalized.unscaled.hash(state);
+        normalized.scale.hash(state);
+    }
+}
+
+impl<'a> Add<&'a BigDecimal> for &'a BigDecimal {
+    type Output = BigDecimal;
+    fn add(self, other: Self) -> BigDecimal {
+        let scale = self.scale.max(other.scale);
+        BigDecimal::new(self.unscaled_at(scale) + other.unscaled_at(scale), scale)
+    }
+}
+
+impl<'a> Sub<&'a BigDecimal> for &'a BigDecimal {
+    type Output = BigDecimal;
+    fn sub(self, other: Self) -> BigDecimal {
+        let scale = self.scale.max(other.scale);
+        BigDecimal::new(self.unscaled_at(scale) - other.unscaled_at(scale), scale)
+    }
+}
+
+impl<'a> Mul<&'a BigDecimal> for &'a BigDecimal {
+    type Output = BigDecimal;
+    fn mul(self, other: Self) -> BigDecimal {
+        BigDecimal::new(&self.unscaled * &other.unscaled, self.scale + other.scale)
+    }
+}
+
+impl Neg for BigDecimal {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.unscaled, self.scale)
+    }
+}
+
+forward_binary_operators!(BigDecimal: Add add, Sub sub, Mul mul);
+
+macro_rules! from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigDecimal {
+                fn from(value: $t) -> Self {
+                    Self::new(BigInt::from(value), 0)
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(BigInt, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128, isize, usize);
+
+impl FromStr for BigDecimal {
+    type Err = ParseNumberError;
+
+    /// Parses a decimal such as `-12.5`, `.5` or `1.5e-3`. The exponent
+    /// must be between -10,000 and 10,000.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = ParseNumberError::new("invalid decimal number");
+        let (mantissa, exponent) = match s.find(['e', 'E']) {
+            Some(index) => (&s[..index], s[index + 1..].parse::<i64>().map_err(|_| error)?),
+            None => (s, 0),
+        };
+        if exponent.unsigned_abs() > MAX_PARSED_EXPONENT {
+            return Err(ParseNumberError::new("decimal exponent out of range"));
+        }
+        let (negative, mantissa) = match mantissa.as_bytes().first() {
+            Some(b'-') => (true, &mantissa[1..]),
+            Some(b'+') => (false, &mantissa[1..]),
+            _ => (false, mantissa),
+        };
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let digits = integer.to_owned() + fraction;
+        if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(error);
+        }
+        let unscaled = BigInt::from_str(&digits).map_err(|_| error)?;
+        let scale = (fraction.len() as i64).checked_sub(exponent).ok_or(error)?;
+        Ok(Self::new(if negative { -unscaled } else { unscaled }, scale))
+    }
+}
+
+impl Display for BigDecimal {
+    /// Formats in plain notation, keeping the scale, or rounding half
+    /// to even if a precision is given.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = match f.precision() {
+            Some(precision) => self.round(precision as i64, RoundingMode::HalfEven).to_plain_string(),
+            None => self.to_plain_string(),
+        };
+        f.pad_integral(!self.is_negative(), "", string.trim_start_matches('-'))
+    }
+}
+
+impl CommaSeparated for BigDecimal {
+    fn comma_separated(&self) -> String {
+        // the sign is taken from the decimal, as the integer part of
+        // a value between -1 and 0 is `-0`
+        let string = self.abs().to_plain_string();
+        let (integer, fraction) = string.split_once('.').map_or((string.as_str(), String::new()), |(integer, fraction)| (integer, ".".to_owned() + fraction));
+        let sign = if self.is_negative() { "-" } else { "" };
+        sign.to_owned() + &BigInt::from_str(integer).unwrap().comma_separated() + &fraction
+    }
+}
+
+impl Serialize for BigDecimal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for BigDecimal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let decimal = String::deserialize(deserializer)?;
+        decimal.parse().map_err(generic_deserialization::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decimal(s: &str) -> BigDecimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn arithmetic_and_rounding() {
+        assert_eq!(decimal("1.50"), decimal("1.5"));
+        assert_eq!(decimal("1.50").to_string(), "1.50");
+        assert_eq!(decimal("-.5e3").to_string(), "-500");
+        assert_eq!(decimal("12e-4").to_string(), "0.0012");
+        assert!("1.2.3".parse::<BigDecimal>().is_err());
+        assert!(decimal("-0.1") < decimal("0"));
+
+        assert_eq!((decimal("0.1") + decimal("0.2")).to_string(), "0.3");
+        assert_eq!((decimal("1.5") * decimal("-0.25")).to_string(), "-0.375");
+        assert_eq!(decimal("1").divide(&decimal("3"), 4, RoundingMode::HalfUp).unwrap().to_string(), "0.3333");
+        assert_eq!(decimal("1").divide(&decimal("0"), 4, RoundingMode::HalfUp), None);
+
+        let modes = [RoundingMode::Up, RoundingMode::Down, RoundingMode::Ceiling, RoundingMode::Floor, RoundingMode::HalfUp, RoundingMode::HalfDown, RoundingMode::HalfEven];
+        let rounded = |s: &str| modes.map(|mode| decimal(s).round(0, mode).to_string());
+        assert_eq!(rounded("2.5"), ["3", "2", "3", "2", "3", "2", "2"]);
+        assert_eq!(rounded("-3.5"), ["-4", "-3", "-3", "-4", "-4", "-3", "-4"]);
+        assert_eq!(rounded("-1.2"), ["-2", "-1", "-1", "-2", "-1", "-1", "-1"]);
+        assert_eq!(decimal("9996").round_to_precision(3, RoundingMode::HalfUp).to_string(), "10000");
+        assert_eq!(decimal("1.2300").normalized().scale(), 2);
+
+        assert_eq!(format!("{:>8.2}", decimal("-3.14159")), "   -3.14");
+        assert_eq!(decimal("-1234.5").comma_separated(), "-1,234.5");
+        assert_eq!(decimal("-0.5").comma_separated(), "-0.5");
+        assert_eq!(decimal("-0.005").comma_separated(), "-0.005");
+    }
+
+    #[test]
+    fn exponent_range() {
+        assert_eq!(decimal("1e10000").precision(), 1);
+        assert_eq!(decimal("1e-10000").scale(), 10_000);
+        for s in ["1e-5000000000", "1e10001", "1.5e-10001", "1e-9223372036854775808"] {
+            assert_eq!(s.parse::<BigDecimal>(), Err(ParseNumberError::new("decimal exponent out of range")), "{s}");
+        }
+        assert!(crate::serialization::json::deserialize::<BigDecimal>("\"1e-5000000000\"").is_err());
+
+        // decimals of distant scales are compared without scaling them
+        let tiny = BigDecimal::new(BigInt::from(1), 5_000_000_000);
+        let huge = BigDecimal::new(BigInt::from(-1), -5_000_000_000);
+        assert!(tiny < BigDecimal::from(1) && tiny > BigDecimal::zero());
+        assert!(huge < BigDecimal::from(-1));
+        assert!(decimal("99.9") < decimal("100") && decimal("-99.9") > decimal("-100"));
+        assert_eq!(decimal("1e3"), decimal("1000.000"));
+    }
+
+    #[test]
+    fn conversion() {
+        assert_eq!(BigDecimal::from_f64(0.1).unwrap().to_string(), "0.1000000000000000055511151231257827021181583404541015625");
+        assert_eq!(BigDecimal::from_f64(-1024.0).unwrap().to_string(), "-1024");
+        assert_eq!(BigDecimal::from_f64_lossy(0.1).unwrap().to_string(), "0.1");
+        assert_eq!(BigDecimal::from_f64(f64::NAN), None);
+        assert_eq!(decimal("2.5e-3").to_f64_lossy(), 0.0025);
+
+        let json = crate::serialization::json::serialize(&decimal("0.10")).unwrap();
+        assert_eq!(json, "\"0.10\"");
+        assert_eq!(crate::serialization::json::deserialize::<BigDecimal>(&json).unwrap().scale(), 2);
+    }
+}
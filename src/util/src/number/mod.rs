@@ -1,10 +1,11 @@
 /*!
 Work with numbers.
 
-This module provides big integer types.
+This module provides big integer types, the [`BigDecimal`] type for exact
+decimal arithmetic, such as of currency, and the [`Rational`] type for exact fractions.
 */
 
-use std::str::FromStr;
+use std::{fmt::Display, str::FromStr};
 
 // Define:
 // - `BigInt`
@@ -16,8 +17,82 @@ pub use num_bigint::{
 
 use crate::reg_exp::*;
 
+/// Implements a binary operator for the owned and borrowed combinations
+/// of operands, given its implementation for two borrowed operands.
+macro_rules! forward_binary_operators {
+    ($t:ty: $($trait:ident $method:ident),+) => {
+        $(
+            impl $trait<$t> for $t {
+                type Output = $t;
+                fn $method(self, other: $t) -> $t {
+                    (&self).$method(&other)
+                }
+            }
+
+            impl $trait<&$t> for $t {
+                type Output = $t;
+                fn $method(self, other: &$t) -> $t {
+                    (&self).$method(other)
+                }
+            }
+
+            impl $trait<$t> for &$t {
+                type Output = $t;
+                fn $method(self, other: $t) -> $t {
+                    self.$method(&other)
+                }
+            }
+        )+
+    };
+}
+
+mod decimal;
+pub use decimal::{BigDecimal, RoundingMode};
+
+mod rational;
+pub use rational::Rational;
+
+/// Error returned when parsing a [`BigDecimal`] or a [`Rational`].
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct ParseNumberError {
+    message: &'static str,
+}
+
+impl ParseNumberError {
+    pub(crate) fn new(message: &'static str) -> Self {
+        Self { message }
+    }
+}
+
+impl Display for ParseNumberError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ParseNumberError {}
+
+/// Decomposes a finite `f64` into its sign, mantissa and binary exponent,
+/// such that its value is `mantissa × 2^exponent`.
+fn f64_parts(value: f64) -> Option<(bool, u64, i32)> {
+    if !value.is_finite() {
+        return None;
+    }
+    let bits = value.to_bits();
+    let exponent = ((bits >> 52) & 0x7ff) as i32;
+    let mantissa = bits & ((1 << 52) - 1);
+    Some(if exponent == 0 {
+        (value.is_sign_negative(), mantissa, -1074)
+    } else {
+        (value.is_sign_negative(), mantissa | (1 << 52), exponent - 1075)
+    })
+}
+
 /// Allows separating a number into commas for every 3 digits,
 /// such as `10,000`.
+///
+/// This does not follow the conventions of the user's locale; for text
+/// shown to users, use `NumberFormat` from the internationalization API.
 /// 
 /// # Example
 /// 
@@ -122,7 +197,7 @@ impl CommaSeparated for f64 {
         let mut split = reg_exp!(r"\.").split(s);
         let i = split.next().unwrap();
         let d = split.next();
-        let d = if d.is_none() { "".to_owned() } else { ".".to_owned() + d.unwrap() };
+        let d = match d { Some(d) => ".".to_owned() + d, None => "".to_owned() };
         BigInt::from_str(i).unwrap().comma_separated() + &d
     }
 }
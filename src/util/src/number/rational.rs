@@ -0,0 +1,306 @@
+use std::{cmp::Ordering, fmt::Display, ops::{Add, Div, Mul, Neg, Sub}, str::FromStr};
+use num_integer::Integer;
+use num_traits::{One, Signed, Zero};
+use crate::serialization::{Deserialize, Deserializer, Serialize, Serializer, generic_deserialization};
+use super::{decimal::{pow10, round_quotient, SCALE_OUT_OF_RANGE}, f64_parts, BigDecimal, BigInt, CommaSeparated, ParseNumberError, RoundingMode};
+
+/// An exact fraction of [`BigInt`]s, always kept in lowest terms with
+/// a positive denominator.
+///
+/// A rational is displayed and serialized as `numerator/denominator`,
+/// or as just the numerator if the denominator is one.
+///
+/// ```
+/// use rialight_util::number::{Rational, RoundingMode};
+///
+/// let third: Rational = "1/3".parse().unwrap();
+/// let sum = &third + &"1/6".parse::<Rational>().unwrap();
+/// assert_eq!(sum.to_string(), "1/2");
+/// assert_eq!((sum * Rational::from(4)).to_string(), "2");
+/// assert_eq!(third.to_decimal(3, RoundingMode::HalfUp).to_string(), "0.333");
+/// assert_eq!("1.25".parse::<Rational>().unwrap().to_string(), "5/4");
+/// ```
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub struct Rational {
+    numerator: BigInt,
+    denominator: BigInt,
+}
+
+impl Rational {
+    /// Constructs a rational in lowest terms, or returns `None`
+    /// if the denominator is zero.
+    pub fn new(numerator: BigInt, denominator: BigInt) -> Option<Self> {
+        if denominator.is_zero() {
+            return None;
+        }
+        let mut divisor = numerator.gcd(&denominator);
+        if denominator.is_negative() {
+            divisor = -divisor;
+        }
+        Some(Self { numerator: numerator / &divisor, denominator: denominator / divisor })
+    }
+
+    /// Returns zero.
+    pub fn zero() -> Self {
+        Self::from(BigInt::zero())
+    }
+
+    pub fn numerator(&self) -> &BigInt {
+        &self.numerator
+    }
+
+    /// Returns the denominator, which is always positive.
+    pub fn denominator(&self) -> &BigInt {
+        &self.denominator
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numerator.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.numerator.is_negative()
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator.is_one()
+    }
+
+    pub fn abs(&self) -> Self {
+        Self { numerator: self.numerator.abs(), denominator: self.denominator.clone() }
+    }
+
+    /// Returns the reciprocal, or `None` if the rational is zero.
+    pub fn recip(&self) -> Option<Self> {
+        Self::new(self.denominator.clone(), self.numerator.clone())
+    }
+
+    /// Divides by `divisor`, or returns `None` if the divisor is zero.
+    pub fn checked_div(&self, divisor: &Self) -> Option<Self> {
+        Self::new(&self.numerator * &divisor.denominator, &self.denominator * &divisor.numerator)
+    }
+
+    /// Rounds to an integer.
+    pub fn round(&self, mode: RoundingMode) -> BigInt {
+        round_quotient(&self.numerator, &self.denominator, mode)
+    }
+
+    /// Returns the greatest integer less than or equal to the rational.
+    pub fn floor(&self) -> BigInt {
+        self.round(RoundingMode::Floor)
+    }
+
+    /// Returns the least integer greater than or equal to the rational.
+    pub fn ceil(&self) -> BigInt {
+        self.round(RoundingMode::Ceiling)
+    }
+
+    /// Returns the integer part of the rational.
+    pub fn trunc(&self) -> BigInt {
+        self.round(RoundingMode::Down)
+    }
+
+    /// Converts to a decimal rounded to `scale` digits after the decimal point.
+    pub fn to_decimal(&self, scale: i64, mode: RoundingMode) -> BigDecimal {
+        let power = pow10(scale.unsigned_abs()).expect(SCALE_OUT_OF_RANGE);
+        let unscaled = if scale >= 0 {
+            round_quotient(&(&self.numerator * power), &self.denominator, mode)
+        } else {
+            round_quotient(&self.numerator, &(&self.denominator * power), mode)
+        };
+        BigDecimal::new(unscaled, scale)
+    }
+
+    /// Converts the exact binary value of a `f64`, or returns `None`
+    /// if it is infinite or NaN.
+    pub fn from_f64(value: f64) -> Option<Self> {
+        let (negative, mantissa, exponent) = f64_parts(value)?;
+        let mantissa = if negative { -BigInt::from(mantissa) } else { BigInt::from(mantissa) };
+        if exponent >= 0 {
+            Some(Self::from(mantissa << exponent as usize))
+        } else {
+            Self::new(mantissa, BigInt::one() << exponent.unsigned_abs() as usize)
+        }
+    }
+
+    /// Converts to the nearest `f64`, which may lose precision or
+    /// be infinite.
+    pub fn to_f64_lossy(&self) -> f64 {
+        // 17 significant digits identify a `f64`
+        let magnitude = |n: &BigInt| n.magnitude().to_string().len() as i64;
+        let scale = 17 + magnitude(&self.denominator) - magnitude(&self.numerator);
+        self.to_decimal(scale, RoundingMode::HalfEven).to_f64_lossy()
+    }
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.numerator * &other.denominator).cmp(&(&other.numerator * &self.denominator))
+    }
+}
+
+impl<'a> Add<&'a Rational> for &'a Rational {
+    type Output = Rational;
+    fn add(self, other: Self) -> Rational {
+        Rational::new(&self.numerator * &other.denominator + &other.numerator * &self.denominator, &self.denominator * &other.denominator).unwrap()
+    }
+}
+
+impl<'a> Sub<&'a Rational> for &'a Rational {
+    type Output = Rational;
+    fn sub(self, other: Self) -> Rational {
+        Rational::new(&self.numerator * &other.denominator - &other.numerator * &self.denominator, &self.denominator * &other.denominator).unwrap()
+    }
+}
+
+impl<'a> Mul<&'a Rational> for &'a Rational {
+    type Output = Rational;
+    fn mul(self, other: Self) -> Rational {
+        Rational::new(&self.numerator * &other.numerator, &self.denominator * &other.denominator).unwrap()
+    }
+}
+
+impl<'a> Div<&'a Rational> for &'a Rational {
+    type Output = Rational;
+
+    /// # Panics
+    ///
+    /// Panics if the divisor is zero.
+    fn div(self, other: Self) -> Rational {
+        self.checked_div(other).expect("attempt to divide by zero")
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self { numerator: -self.numerator, denominator: self.denominator }
+    }
+}
+
+forward_binary_operators!(Rational: Add add, Sub sub, Mul mul, Div div);
+
+macro_rules! from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Rational {
+                fn from(value: $t) -> Self {
+                    Self { numerator: BigInt::from(value), denominator: BigInt::one() }
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(BigInt, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128, isize, usize);
+
+impl From<BigDecimal> for Rational {
+    fn from(value: BigDecimal) -> Self {
+        let power = pow10(value.scale().unsigned_abs()).expect(SCALE_OUT_OF_RANGE);
+        if value.scale() >= 0 {
+            Self::new(value.unscaled().clone(), power).unwrap()
+        } else {
+            Self::from(value.unscaled() * power)
+        }
+    }
+}
+
+impl FromStr for Rational {
+    type Err = ParseNumberError;
+
+    /// Parses a fraction such as `-3/4`, or a decimal such as `0.75`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((numerator, denominator)) = s.split_once('/') else {
+            return s.parse::<BigDecimal>().map(Self::from).map_err(|_| ParseNumberError::new("invalid rational number"));
+        };
+        let integer = |s: &str| BigInt::from_str(s).map_err(|_| ParseNumberError::new("invalid rational number"));
+        Self::new(integer(numerator)?, integer(denominator)?).ok_or(ParseNumberError::new("zero denominator"))
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = if self.is_integer() {
+            self.numerator.magnitude().to_string()
+        } else {
+            format!("{}/{}", self.numerator.magnitude(), self.denominator)
+        };
+        f.pad_integral(!self.is_negative(), "", &string)
+    }
+}
+
+impl CommaSeparated for Rational {
+    fn comma_separated(&self) -> String {
+        if self.is_integer() {
+            self.numerator.comma_separated()
+        } else {
+            format!("{}/{}", self.numerator.comma_separated(), self.denominator.comma_separated())
+        }
+    }
+}
+
+impl Serialize for Rational {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rational {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let rational = String::deserialize(deserializer)?;
+        rational.parse().map_err(generic_deserialization::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rational(s: &str) -> Rational {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(rational("6/-8").to_string(), "-3/4");
+        assert_eq!(rational("-0.125"), rational("-1/8"));
+        assert_eq!(rational("1e2"), Rational::from(100));
+        assert!("1/0".parse::<Rational>().is_err());
+        assert!(rational("1/3") > rational("0.333"));
+
+        assert_eq!(rational("1/3") - rational("1/2"), rational("-1/6"));
+        assert_eq!(rational("2/3") / rational("-4/9"), rational("-3/2"));
+        assert_eq!(Rational::zero().recip(), None);
+        assert_eq!(rational("-7/2").floor(), BigInt::from(-4));
+        assert_eq!(rational("-7/2").ceil(), BigInt::from(-3));
+        assert_eq!(rational("-7/2").round(RoundingMode::HalfEven), BigInt::from(-4));
+        assert_eq!(rational("1234567/2").to_decimal(-3, RoundingMode::HalfUp).to_string(), "617000");
+        assert_eq!(rational("-2500/3").comma_separated(), "-2,500/3");
+        assert_eq!(format!("{:>6}", rational("-1/2")), "  -1/2");
+    }
+
+    #[test]
+    fn conversion() {
+        assert_eq!(Rational::from_f64(0.375), Some(rational("3/8")));
+        assert_eq!(Rational::from_f64(-3.0e20), Some(Rational::from(-300_000_000_000_000_000_000i128)));
+        assert_eq!(Rational::from_f64(f64::INFINITY), None);
+        assert_eq!(rational("1/3").to_f64_lossy(), 1.0 / 3.0);
+        assert_eq!(Rational::new(-BigInt::from(10u32).pow(400), BigInt::from(10u32).pow(399)).unwrap().to_f64_lossy(), -10.0);
+
+        let json = crate::serialization::json::serialize(&rational("-5/3")).unwrap();
+        assert_eq!(json, "\"-5/3\"");
+        assert_eq!(crate::serialization::json::deserialize::<Rational>(&json).unwrap(), rational("-5/3"));
+    }
+}
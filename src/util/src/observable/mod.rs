@@ -5,9 +5,9 @@ The [`Observable`] type can be used to model push-based
 data sources. In addition, observables are:
 
 - _Compositional:_ Observables can be composed with higher-order
-combinators.
+  combinators.
 - _Lazy:_ Observables do not start emitting data until an **observer**
-has subscribed.
+  has subscribed.
 
 This module follows the [TC39 `Observable`](https://github.com/tc39/proposal-observable) proposal.
 User observers other than `Observer` can be defined by implementing
@@ -42,8 +42,8 @@ let _ = my_observable()
 
 // you can also use functional methods such as `filter` and `map`.
 let _ = my_observable()
-    .filter(|value| true)
-    .map(|value| value);
+    .filter::<fn(_) -> _>(|value| true)
+    .map::<_, fn(_) -> _>(|value| value);
 ```
 
 You can directly construct an `Observable` from a list of values:
@@ -57,6 +57,137 @@ Observable::from(["red", "green", "blue"])
         },
     });
 ```
+
+# Operators
+
+The [`Operators`] trait adds combination and transformation operators,
+such as `merge`, `combine_latest`, `switch_map` and `scan`, to observables.
+
+```
+# use rialight_util::observable::*;
+let clicks = Subject::<(i32, i32)>::new();
+let stop = Subject::<()>::new();
+let _ = clicks.observable()
+    .distinct_until_changed()
+    .take_until(&stop.observable())
+    .buffer(2)
+    .subscribe(observer! {
+        next: |pair| println!("{:?}", pair),
+    });
+```
+
+# Timing operators
+
+The [`TimingOperators`] trait adds time-based operators
+such as `debounce` and `throttle` to observables.
+
+# Subjects
+
+[`Subject`], [`BehaviorSubject`] and [`ReplaySubject`] are hot observables:
+they emit the values given to them to their current subscribers,
+as opposed to running a new producer for each subscription.
+
+# Streams
+
+An observable converts to a stream through [`Operators::to_stream`],
+and [`from_stream`] and [`from_try_stream`] convert streams to observables.
 */
 
-pub use rust_observable::*;
\ No newline at end of file
+use std::sync::{Arc, Mutex};
+
+pub use rust_observable::*;
+
+mod operators;
+pub use operators::Operators;
+
+mod timing_operators;
+pub use timing_operators::TimingOperators;
+
+mod subject;
+pub use subject::{Subject, BehaviorSubject, ReplaySubject};
+
+mod stream;
+pub use stream::{ObservableStream, from_stream, from_try_stream};
+
+/// Listeners used by an operator to handle the events of its source
+/// observable, returned from the function given to [`derive_observable`].
+/// The error and completion are forwarded unchanged when omitted.
+pub(crate) struct OperatorListeners<T, Error = ()> {
+    pub next: Box<dyn Fn(T) + Send + Sync>,
+    pub error: Option<Box<dyn Fn(Error) + Send + Sync>>,
+    pub complete: Option<Box<dyn Fn() + Send + Sync>>,
+    pub cleanup: Option<Box<dyn Fn() + Send + Sync>>,
+}
+
+impl<T, Error> OperatorListeners<T, Error> {
+    pub fn new(next: impl Fn(T) + Send + Sync + 'static) -> Self {
+        Self { next: Box::new(next), error: None, complete: None, cleanup: None }
+    }
+
+    pub fn error(mut self, error: impl Fn(Error) + Send + Sync + 'static) -> Self {
+        self.error = Some(Box::new(error));
+        self
+    }
+
+    pub fn complete(mut self, complete: impl Fn() + Send + Sync + 'static) -> Self {
+        self.complete = Some(Box::new(complete));
+        self
+    }
+
+    pub fn cleanup(mut self, cleanup: impl Fn() + Send + Sync + 'static) -> Self {
+        self.cleanup = Some(Box::new(cleanup));
+        self
+    }
+}
+
+/// Returns an observable that, for each of its subscriptions, subscribes to
+/// `source` with the listeners returned by `operator`.
+pub(crate) fn derive_observable<T, U, Error>(
+    source: &Observable<T, Error>,
+    operator: impl Fn(Arc<SubscriptionObserver<U, Error>>) -> OperatorListeners<T, Error> + Send + Sync + 'static,
+) -> Observable<U, Error>
+where
+    T: Send + Sync + 'static,
+    U: Send + Sync + 'static,
+    Error: Send + Sync + 'static,
+{
+    let source = source.clone();
+    Observable::new(move |observer| {
+        let observer = Arc::new(observer);
+        let OperatorListeners { next, error, complete, cleanup } = operator(Arc::clone(&observer));
+        // the source subscription, given before the source emits, so that
+        // a source emitting synchronously stops once the operator completes
+        let source_subscription = Arc::new(Mutex::new(None::<Arc<Subscription<T, Error>>>));
+        let subscription = source.subscribe(Observer {
+            next: Box::new({
+                let (observer, source_subscription) = (Arc::clone(&observer), Arc::clone(&source_subscription));
+                move |value| {
+                    next(value);
+                    if observer.closed() {
+                        let subscription = source_subscription.lock().unwrap().take();
+                        if let Some(subscription) = subscription {
+                            subscription.unsubscribe();
+                        }
+                    }
+                }
+            }),
+            error: error.unwrap_or_else(|| {
+                let observer = Arc::clone(&observer);
+                Box::new(move |error| observer.error(error))
+            }),
+            complete: complete.unwrap_or_else(|| {
+                let observer = Arc::clone(&observer);
+                Box::new(move || observer.complete())
+            }),
+            start: Box::new(move |subscription| {
+                *source_subscription.lock().unwrap() = Some(subscription);
+            }),
+        });
+        move || {
+            subscription.unsubscribe();
+            if let Some(cleanup) = cleanup.as_ref() {
+                cleanup();
+            }
+        }
+    })
+}
\ No newline at end of file
@@ -0,0 +1,588 @@
+use std::{
+    collections::HashMap,
+    sync::{atomic::{AtomicUsize, Ordering}, Arc, Mutex},
+};
+use super::{derive_observable, Observable, ObservableStream, Observer, OperatorListeners, Subscription, SubscriptionObserver};
+
+/// Combination and transformation operators for observables.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::observable::*;
+///
+/// let totals = Observable::from([1, 2, 2, 3, 4])
+///     .distinct_until_changed()
+///     .scan(0, |total, value| total + value)
+///     .skip(1)
+///     .take(2);
+/// // emits 3 and 6
+/// ```
+pub trait Operators<T, Error = ()>
+where
+    T: Send + Sync + 'static,
+    Error: Send + Sync + 'static,
+{
+    /// Emits the values of both observables as they arrive,
+    /// completing once both complete.
+    fn merge(&self, other: &Observable<T, Error>) -> Observable<T, Error>;
+
+    /// Emits the latest values of both observables each time either emits,
+    /// once both have emitted. Completes once both complete, or once either
+    /// completes without having emitted.
+    fn combine_latest<U>(&self, other: &Observable<U, Error>) -> Observable<(T, U), Error>
+    where
+        T: Clone,
+        U: Clone + Send + Sync + 'static;
+
+    /// Pairs the values of both observables by their order, completing once
+    /// either completes and its values have all been paired.
+    fn zip<U>(&self, other: &Observable<U, Error>) -> Observable<(T, U), Error>
+    where
+        U: Send + Sync + 'static;
+
+    /// Maps each value to an observable and emits the values of the
+    /// latest one, unsubscribing from the previous one.
+    fn switch_map<U>(&self, map_fn: impl Fn(T) -> Observable<U, Error> + Send + Sync + 'static) -> Observable<U, Error>
+    where
+        U: Send + Sync + 'static;
+
+    /// Maps each value to an observable and emits the values
+    /// of every one of them as they arrive.
+    fn flat_map<U>(&self, map_fn: impl Fn(T) -> Observable<U, Error> + Send + Sync + 'static) -> Observable<U, Error>
+    where
+        U: Send + Sync + 'static;
+
+    /// Emits the result of accumulating each value into `seed`.
+    fn scan<A>(&self, seed: A, accumulator: impl Fn(A, T) -> A + Send + Sync + 'static) -> Observable<A, Error>
+    where
+        A: Clone + Send + Sync + 'static;
+
+    /// Emits a value only if it differs from the last emitted value.
+    fn distinct_until_changed(&self) -> Observable<T, Error>
+    where
+        T: Clone + PartialEq;
+
+    /// Emits the first `count` values, then completes.
+    fn take(&self, count: usize) -> Observable<T, Error>;
+
+    /// Emits values until `notifier` emits, then completes.
+    fn take_until<U>(&self, notifier: &Observable<U, Error>) -> Observable<T, Error>
+    where
+        U: Send + Sync + 'static;
+
+    /// Ignores the first `count` values.
+    fn skip(&self, count: usize) -> Observable<T, Error>;
+
+    /// Emits the given values before the values of the source.
+    fn start_with(&self, values: impl IntoIterator<Item = T>) -> Observable<T, Error>
+    where
+        T: Clone;
+
+    /// Emits the values in lists of `count` values. The remaining
+    /// values are emitted when the source completes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is zero.
+    fn buffer(&self, count: usize) -> Observable<Vec<T>, Error>;
+
+    /// Subscribes to the observable, returning a stream of its values.
+    /// See [`ObservableStream`].
+    fn to_stream(&self) -> ObservableStream<T, Error>;
+}
+
+impl<T, Error> Operators<T, Error> for Observable<T, Error>
+where
+    T: Send + Sync + 'static,
+    Error: Send + Sync + 'static,
+{
+    fn merge(&self, other: &Observable<T, Error>) -> Observable<T, Error> {
+        let other = other.clone();
+        derive_observable(self, move |observer| {
+            let remaining = Arc::new(AtomicUsize::new(2));
+            let complete = Arc::new({
+                let observer = Arc::clone(&observer);
+                move || {
+                    if remaining.fetch_sub(1, Ordering::SeqCst) == 1 {
+                        observer.complete();
+                    }
+                }
+            });
+            let other_subscription = subscribe_inner(&other, &observer, {
+                let observer = Arc::clone(&observer);
+                move |value| observer.next(value)
+            }, {
+                let complete = Arc::clone(&complete);
+                move || complete()
+            });
+            OperatorListeners::new(move |value| observer.next(value))
+                .complete(move || complete())
+                .cleanup(move || other_subscription.unsubscribe())
+        })
+    }
+
+    fn combine_latest<U>(&self, other: &Observable<U, Error>) -> Observable<(T, U), Error>
+    where
+        T: Clone,
+        U: Clone + Send + Sync + 'static,
+    {
+        let other = other.clone();
+        derive_observable(self, move |observer| {
+            let latest = Arc::new(Mutex::new((None::<T>, None::<U>)));
+            let remaining = Arc::new(AtomicUsize::new(2));
+            let complete = Arc::new({
+                let observer = Arc::clone(&observer);
+                move |emitted: bool| {
+                    if !emitted || remaining.fetch_sub(1, Ordering::SeqCst) == 1 {
+                        observer.complete();
+                    }
+                }
+            });
+            let other_subscription = subscribe_inner(&other, &observer, {
+                let (observer, latest) = (Arc::clone(&observer), Arc::clone(&latest));
+                move |value| {
+                    let mut latest = latest.lock().unwrap();
+                    latest.1 = Some(value);
+                    let pair = latest.0.clone().zip(latest.1.clone());
+                    drop(latest);
+                    if let Some(pair) = pair {
+                        observer.next(pair);
+                    }
+                }
+            }, {
+                let (complete, latest) = (Arc::clone(&complete), Arc::clone(&latest));
+                move || {
+                    let emitted = latest.lock().unwrap().1.is_some();
+                    complete(emitted)
+                }
+            });
+            OperatorListeners::new({
+                let latest = Arc::clone(&latest);
+                move |value| {
+                    let mut latest = latest.lock().unwrap();
+                    latest.0 = Some(value);
+                    let pair = latest.0.clone().zip(latest.1.clone());
+                    drop(latest);
+                    if let Some(pair) = pair {
+                        observer.next(pair);
+                    }
+                }
+            })
+            .complete(move || {
+                let emitted = latest.lock().unwrap().0.is_some();
+                complete(emitted)
+            })
+            .cleanup(move || other_subscription.unsubscribe())
+        })
+    }
+
+    fn zip<U>(&self, other: &Observable<U, Error>) -> Observable<(T, U), Error>
+    where
+        U: Send + Sync + 'static,
+    {
+        let other = other.clone();
+        derive_observable(self, move |observer| {
+            let state = Arc::new(Mutex::new(ZipState::<T, U>::default()));
+            let other_subscription = subscribe_inner(&other, &observer, {
+                let (observer, state) = (Arc::clone(&observer), Arc::clone(&state));
+                move |value| {
+                    let mut state = state.lock().unwrap();
+                    match state.left.pop_front() {
+                        Some(left) => {
+                            let complete = state.left_complete && state.left.is_empty();
+                            drop(state);
+                            observer.next((left, value));
+                            if complete {
+                                observer.complete();
+                            }
+                        },
+                        None => state.right.push_back(value),
+                    }
+                }
+            }, {
+                let (observer, state) = (Arc::clone(&observer), Arc::clone(&state));
+                move || {
+                    let mut state = state.lock().unwrap();
+                    state.right_complete = true;
+                    let complete = state.right.is_empty();
+                    drop(state);
+                    if complete {
+                        observer.complete();
+                    }
+                }
+            });
+            OperatorListeners::new({
+                let (observer, state) = (Arc::clone(&observer), Arc::clone(&state));
+                move |value| {
+                    let mut state = state.lock().unwrap();
+                    match state.right.pop_front() {
+                        Some(right) => {
+                            let complete = state.right_complete && state.right.is_empty();
+                            drop(state);
+                            observer.next((value, right));
+                            if complete {
+                                observer.complete();
+                            }
+                        },
+                        None => state.left.push_back(value),
+                    }
+                }
+            })
+            .complete(move || {
+                let mut state = state.lock().unwrap();
+                state.left_complete = true;
+                let complete = state.left.is_empty();
+                drop(state);
+                if complete {
+                    observer.complete();
+                }
+            })
+            .cleanup(move || other_subscription.unsubscribe())
+        })
+    }
+
+    fn switch_map<U>(&self, map_fn: impl Fn(T) -> Observable<U, Error> + Send + Sync + 'static) -> Observable<U, Error>
+    where
+        U: Send + Sync + 'static,
+    {
+        flatten(self, map_fn, true)
+    }
+
+    fn flat_map<U>(&self, map_fn: impl Fn(T) -> Observable<U, Error> + Send + Sync + 'static) -> Observable<U, Error>
+    where
+        U: Send + Sync + 'static,
+    {
+        flatten(self, map_fn, false)
+    }
+
+    fn scan<A>(&self, seed: A, accumulator: impl Fn(A, T) -> A + Send + Sync + 'static) -> Observable<A, Error>
+    where
+        A: Clone + Send + Sync + 'static,
+    {
+        let accumulator = Arc::new(accumulator);
+        derive_observable(self, move |observer| {
+            let accumulated = Mutex::new(seed.clone());
+            let accumulator = Arc::clone(&accumulator);
+            OperatorListeners::new(move |value| {
+                let mut accumulated = accumulated.lock().unwrap();
+                *accumulated = accumulator(accumulated.clone(), value);
+                let value = accumulated.clone();
+                drop(accumulated);
+                observer.next(value);
+            })
+        })
+    }
+
+    fn distinct_until_changed(&self) -> Observable<T, Error>
+    where
+        T: Clone + PartialEq,
+    {
+        derive_observable(self, move |observer| {
+            let last = Mutex::new(None::<T>);
+            OperatorListeners::new(move |value| {
+                let mut last = last.lock().unwrap();
+                if last.as_ref() != Some(&value) {
+                    *last = Some(value.clone());
+                    drop(last);
+                    observer.next(value);
+                }
+            })
+        })
+    }
+
+    fn take(&self, count: usize) -> Observable<T, Error> {
+        derive_observable(self, move |observer| {
+            if count == 0 {
+                observer.complete();
+            }
+            let remaining = Mutex::new(count);
+            OperatorListeners::new(move |value| {
+                let mut remaining = remaining.lock().unwrap();
+                if *remaining == 0 {
+                    return;
+                }
+                *remaining -= 1;
+                let complete = *remaining == 0;
+                drop(remaining);
+                observer.next(value);
+                if complete {
+                    observer.complete();
+                }
+            })
+        })
+    }
+
+    fn take_until<U>(&self, notifier: &Observable<U, Error>) -> Observable<T, Error>
+    where
+        U: Send + Sync + 'static,
+    {
+        let notifier = notifier.clone();
+        derive_observable(self, move |observer| {
+            let notifier_subscription = subscribe_inner(&notifier, &observer, {
+                let observer = Arc::clone(&observer);
+                move |_| observer.complete()
+            }, || {});
+            OperatorListeners::new(move |value| observer.next(value))
+                .cleanup(move || notifier_subscription.unsubscribe())
+        })
+    }
+
+    fn skip(&self, count: usize) -> Observable<T, Error> {
+        derive_observable(self, move |observer| {
+            let remaining = Mutex::new(count);
+            OperatorListeners::new(move |value| {
+                let mut remaining = remaining.lock().unwrap();
+                if *remaining == 0 {
+                    drop(remaining);
+                    observer.next(value);
+                } else {
+                    *remaining -= 1;
+                }
+            })
+        })
+    }
+
+    fn start_with(&self, values: impl IntoIterator<Item = T>) -> Observable<T, Error>
+    where
+        T: Clone,
+    {
+        let values = values.into_iter().collect::<Vec<T>>();
+        derive_observable(self, move |observer| {
+            for value in &values {
+                observer.next(value.clone());
+            }
+            OperatorListeners::new(move |value| observer.next(value))
+        })
+    }
+
+    fn buffer(&self, count: usize) -> Observable<Vec<T>, Error> {
+        assert!(count != 0, "rialight::util::observable::Operators::buffer must be given non-zero count");
+        derive_observable(self, move |observer| {
+            let buffer = Arc::new(Mutex::new(Vec::with_capacity(count)));
+            OperatorListeners::new({
+                let (observer, buffer) = (Arc::clone(&observer), Arc::clone(&buffer));
+                move |value| {
+                    let mut buffer = buffer.lock().unwrap();
+                    buffer.push(value);
+                    if buffer.len() == count {
+                        let values = std::mem::replace(&mut *buffer, Vec::with_capacity(count));
+                        drop(buffer);
+                        observer.next(values);
+                    }
+                }
+            })
+            .complete(move || {
+                let values = std::mem::take(&mut *buffer.lock().unwrap());
+                if !values.is_empty() {
+                    observer.next(values);
+                }
+                observer.complete();
+            })
+        })
+    }
+
+    fn to_stream(&self) -> ObservableStream<T, Error> {
+        ObservableStream::new(self)
+    }
+}
+
+struct ZipState<T, U> {
+    left: std::collections::VecDeque<T>,
+    right: std::collections::VecDeque<U>,
+    left_complete: bool,
+    right_complete: bool,
+}
+
+impl<T, U> Default for ZipState<T, U> {
+    fn default() -> Self {
+        Self { left: Default::default(), right: Default::default(), left_complete: false, right_complete: false }
+    }
+}
+
+/// Subscribes to an observable other than the source of an operator,
+/// forwarding its error to `observer`.
+fn subscribe_inner<T, U, Error>(
+    source: &Observable<T, Error>,
+    observer: &Arc<SubscriptionObserver<U, Error>>,
+    next: impl Fn(T) + Send + Sync + 'static,
+    complete: impl Fn() + Send + Sync + 'static,
+) -> Arc<Subscription<T, Error>>
+where
+    T: Send + Sync + 'static,
+    U: Send + Sync + 'static,
+    Error: Send + Sync + 'static,
+{
+    let observer = Arc::clone(observer);
+    source.subscribe(Observer {
+        next: Box::new(next),
+        error: Box::new(move |error| observer.error(error)),
+        complete: Box::new(complete),
+        start: Box::new(|_| {}),
+    })
+}
+
+/// Inner subscriptions of `switch_map` and `flat_map`, by identifier.
+/// An entry exists from the moment the inner observable is subscribed
+/// until it completes.
+struct InnerSubscriptions<U, Error>
+where
+    U: Send + Sync + 'static,
+    Error: Send + Sync + 'static,
+{
+    next_id: u64,
+    subscriptions: HashMap<u64, Option<Arc<Subscription<U, Error>>>>,
+    source_complete: bool,
+}
+
+fn flatten<T, U, Error>(
+    source: &Observable<T, Error>,
+    map_fn: impl Fn(T) -> Observable<U, Error> + Send + Sync + 'static,
+    switch: bool,
+) -> Observable<U, Error>
+where
+    T: Send + Sync + 'static,
+    U: Send + Sync + 'static,
+    Error: Send + Sync + 'static,
+{
+    let map_fn = Arc::new(map_fn);
+    derive_observable(source, move |observer| {
+        let inner = Arc::new(Mutex::new(InnerSubscriptions::<U, Error> {
+            next_id: 0,
+            subscriptions: HashMap::new(),
+            source_complete: false,
+        }));
+        let map_fn = Arc::clone(&map_fn);
+        OperatorListeners::new({
+            let (observer, inner) = (Arc::clone(&observer), Arc::clone(&inner));
+            move |value| {
+                let mut state = inner.lock().unwrap();
+                let id = state.next_id;
+                state.next_id += 1;
+                let previous = if switch { std::mem::take(&mut state.subscriptions) } else { HashMap::new() };
+                state.subscriptions.insert(id, None);
+                drop(state);
+                for subscription in previous.into_values().flatten() {
+                    subscription.unsubscribe();
+                }
+                let subscription = subscribe_inner(&map_fn(value), &observer, {
+                    let observer = Arc::clone(&observer);
+                    move |value| observer.next(value)
+                }, {
+                    let (observer, inner) = (Arc::clone(&observer), Arc::clone(&inner));
+                    move || {
+                        let mut state = inner.lock().unwrap();
+                        state.subscriptions.remove(&id);
+                        let complete = state.source_complete && state.subscriptions.is_empty();
+                        drop(state);
+                        if complete {
+                            observer.complete();
+                        }
+                    }
+                });
+                let mut state = inner.lock().unwrap();
+                match state.subscriptions.get_mut(&id) {
+                    Some(entry) => *entry = Some(subscription),
+                    // completed, or switched while subscribing
+                    None => {
+                        drop(state);
+                        subscription.unsubscribe();
+                    },
+                }
+            }
+        })
+        .complete({
+            let (observer, inner) = (Arc::clone(&observer), Arc::clone(&inner));
+            move || {
+                let mut state = inner.lock().unwrap();
+                state.source_complete = true;
+                let complete = state.subscriptions.is_empty();
+                drop(state);
+                if complete {
+                    observer.complete();
+                }
+            }
+        })
+        .cleanup(move || {
+            let subscriptions = std::mem::take(&mut inner.lock().unwrap().subscriptions);
+            for subscription in subscriptions.into_values().flatten() {
+                subscription.unsubscribe();
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::observable::Subject;
+
+    fn collect<T: Clone + Send + Sync + 'static>(observable: &Observable<T>) -> (Arc<Mutex<Vec<T>>>, Arc<Mutex<bool>>) {
+        let (values, complete) = (Arc::new(Mutex::new(vec![])), Arc::new(Mutex::new(false)));
+        observable.subscribe(Observer {
+            next: Box::new({
+                let values = Arc::clone(&values);
+                move |value| values.lock().unwrap().push(value)
+            }),
+            complete: Box::new({
+                let complete = Arc::clone(&complete);
+                move || *complete.lock().unwrap() = true
+            }),
+            ..Default::default()
+        });
+        (values, complete)
+    }
+
+    fn values<T: Clone + Send + Sync + 'static>(observable: &Observable<T>) -> Vec<T> {
+        let (values, complete) = collect(observable);
+        assert!(*complete.lock().unwrap());
+        let values = values.lock().unwrap().clone();
+        values
+    }
+
+    #[test]
+    fn transformation() {
+        let source = Observable::from([1, 2, 2, 3, 3, 1]);
+        assert_eq!(values(&source.distinct_until_changed()), [1, 2, 3, 1]);
+        assert_eq!(values(&source.scan(0, |total, value| total + value)), [1, 3, 5, 8, 11, 12]);
+        assert_eq!(values(&source.take(2)), [1, 2]);
+        assert_eq!(values(&source.take(0)), Vec::<i32>::new());
+        assert_eq!(values(&source.skip(4)), [3, 1]);
+        assert_eq!(values(&source.start_with([0])), [0, 1, 2, 2, 3, 3, 1]);
+        assert_eq!(values(&source.buffer(4)), [vec![1, 2, 2, 3], vec![3, 1]]);
+        assert_eq!(values(&source.take(3).flat_map(|n| Observable::from(vec![n; n as usize]))), [1, 2, 2, 2, 2]);
+    }
+
+    #[test]
+    fn combination() {
+        let (a, b, c) = (Subject::<u32>::new(), Subject::<&str>::new(), Subject::<u32>::new());
+        let (merged, merged_complete) = collect(&a.observable().merge(&c.observable()));
+        let (latest, _) = collect(&a.observable().combine_latest(&b.observable()));
+        let (zipped, zip_complete) = collect(&a.observable().zip(&b.observable()));
+        let (until, until_complete) = collect(&a.observable().take_until(&b.observable()));
+        let (switched, _) = collect(&b.observable().switch_map({
+            let a = a.clone();
+            move |_| a.observable().take(1)
+        }));
+
+        a.next(1);
+        a.next(2);
+        c.next(100);
+        c.complete();
+        b.next("x");
+        a.next(3);
+        b.next("y");
+        b.next("z");
+        a.next(4);
+        a.complete();
+
+        assert_eq!(*merged.lock().unwrap(), [1, 2, 100, 3, 4]);
+        assert!(*merged_complete.lock().unwrap());
+        assert_eq!(*latest.lock().unwrap(), [(2, "x"), (3, "x"), (3, "y"), (3, "z"), (4, "z")]);
+        assert_eq!(*zipped.lock().unwrap(), [(1, "x"), (2, "y"), (3, "z")]);
+        assert!(!*zip_complete.lock().unwrap());
+        assert_eq!(*until.lock().unwrap(), [1, 2]);
+        assert!(*until_complete.lock().unwrap());
+        assert_eq!(*switched.lock().unwrap(), [3, 4]);
+    }
+}
@@ -0,0 +1,209 @@
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+use super::{Observable, Observer, Subscription};
+use crate::{
+    futures::{exec_future, Stream, StreamExt},
+    timing::CancellationToken,
+};
+
+/// A stream of the values of an observable, returned by
+/// [`Operators::to_stream`](super::Operators::to_stream).
+///
+/// The stream subscribes to the observable when created and unsubscribes
+/// when dropped. Values emitted before they are polled are queued.
+/// An error is yielded as the last item of the stream.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::{futures::StreamExt, observable::*};
+///
+/// async fn example_fn() {
+///     let mut stream = Observable::from([1, 2, 3]).to_stream();
+///     while let Some(Ok(value)) = stream.next().await {
+///         println!("{value}");
+///     }
+/// }
+/// ```
+pub struct ObservableStream<T, Error = ()>
+where
+    T: Send + Sync + 'static,
+    Error: Send + Sync + 'static,
+{
+    state: Arc<Mutex<StreamState<T, Error>>>,
+    subscription: Arc<Subscription<T, Error>>,
+}
+
+struct StreamState<T, Error> {
+    queue: VecDeque<Result<T, Error>>,
+    complete: bool,
+    waker: Option<Waker>,
+}
+
+impl<T, Error> StreamState<T, Error> {
+    fn push(&mut self, item: Option<Result<T, Error>>) {
+        match item {
+            Some(item) => self.queue.push_back(item),
+            None => self.complete = true,
+        }
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T, Error> ObservableStream<T, Error>
+where
+    T: Send + Sync + 'static,
+    Error: Send + Sync + 'static,
+{
+    /// Subscribes to an observable, returning a stream of its values.
+    pub fn new(observable: &Observable<T, Error>) -> Self {
+        let state = Arc::new(Mutex::new(StreamState { queue: VecDeque::new(), complete: false, waker: None }));
+        let subscription = observable.subscribe(Observer {
+            next: Box::new({
+                let state = Arc::clone(&state);
+                move |value| state.lock().unwrap().push(Some(Ok(value)))
+            }),
+            error: Box::new({
+                let state = Arc::clone(&state);
+                move |error| {
+                    let mut state = state.lock().unwrap();
+                    state.push(Some(Err(error)));
+                    state.push(None);
+                }
+            }),
+            complete: Box::new({
+                let state = Arc::clone(&state);
+                move || state.lock().unwrap().push(None)
+            }),
+            start: Box::new(|_| {}),
+        });
+        Self { state, subscription }
+    }
+}
+
+impl<T, Error> Stream for ObservableStream<T, Error>
+where
+    T: Send + Sync + 'static,
+    Error: Send + Sync + 'static,
+{
+    type Item = Result<T, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(item) = state.queue.pop_front() {
+            return Poll::Ready(Some(item));
+        }
+        if state.complete {
+            return Poll::Ready(None);
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl<T, Error> Drop for ObservableStream<T, Error>
+where
+    T: Send + Sync + 'static,
+    Error: Send + Sync + 'static,
+{
+    fn drop(&mut self) {
+        self.subscription.unsubscribe();
+    }
+}
+
+/// Returns an observable emitting the items of a stream.
+///
+/// The stream is consumed by the first subscription, from a future
+/// executed in the Rialight runtime, and is dropped when that subscription
+/// is unsubscribed. Later subscriptions complete immediately.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::{futures::stream, observable::*};
+///
+/// let observable: Observable<u32> = from_stream(stream::iter([1, 2, 3]));
+/// ```
+pub fn from_stream<S, Error>(stream: S) -> Observable<S::Item, Error>
+where
+    S: Stream + Send + 'static,
+    S::Item: Send + Sync + 'static,
+    Error: Send + Sync + 'static,
+{
+    from_try_stream(stream.map(Ok))
+}
+
+/// Returns an observable emitting the values of a stream of results,
+/// erroring on its first error. See [`from_stream`].
+pub fn from_try_stream<S, T, Error>(stream: S) -> Observable<T, Error>
+where
+    S: Stream<Item = Result<T, Error>> + Send + 'static,
+    T: Send + Sync + 'static,
+    Error: Send + Sync + 'static,
+{
+    let stream = Mutex::new(Some(stream));
+    Observable::new(move |observer| {
+        let token = CancellationToken::new();
+        let stream = stream.lock().unwrap().take();
+        match stream {
+            Some(stream) => exec_future({
+                let token = token.clone();
+                async move {
+                    let _ = token.run(async {
+                        let mut stream = std::pin::pin!(stream);
+                        while let Some(item) = stream.next().await {
+                            match item {
+                                Ok(value) => observer.next(value),
+                                Err(error) => return observer.error(error),
+                            }
+                        }
+                        observer.complete();
+                    }).await;
+                }
+            }),
+            None => observer.complete(),
+        }
+        move || token.cancel()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{futures::stream, observable::{Operators, Subject}, timing::TestClock};
+
+    #[test]
+    fn streams() {
+        let clock = TestClock::new();
+        let subject = Subject::<u32, &str>::new();
+        let mut values = subject.observable().to_stream();
+        subject.next(1);
+        subject.next(2);
+        subject.error("failure");
+        let values = clock.block_on(async move {
+            let mut collected = vec![];
+            while let Some(item) = values.next().await {
+                collected.push(item);
+            }
+            collected
+        });
+        assert_eq!(values, [Ok(1), Ok(2), Err("failure")]);
+
+        let observable = from_try_stream(stream::iter([Ok(1), Err("failure"), Ok(3)]));
+        let values = clock.block_on(async move {
+            let mut stream = observable.to_stream();
+            let mut collected = vec![];
+            while let Some(item) = stream.next().await {
+                collected.push(item);
+            }
+            collected
+        });
+        assert_eq!(values, [Ok(1), Err("failure")]);
+    }
+}
@@ -0,0 +1,332 @@
+use std::{
+    collections::VecDeque,
+    ops::Deref,
+    sync::{Arc, Mutex, Weak},
+};
+use super::{Observable, SubscriptionObserver};
+
+/// A hot observable that multicasts the values given to
+/// [`Subject::next`] to its current subscribers.
+///
+/// Once the subject completes or errors, later subscribers
+/// immediately receive the completion or the error.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::observable::*;
+///
+/// let subject = Subject::<String>::new();
+/// subject.observable().subscribe(observer! {
+///     next: |value| println!("{value}"),
+/// });
+/// subject.next("hello".into());
+/// subject.complete();
+/// ```
+pub struct Subject<T, Error = ()>
+where
+    T: Clone + Send + Sync + 'static,
+    Error: Clone + Send + Sync + 'static,
+{
+    inner: Arc<Mutex<SubjectInner<T, Error>>>,
+}
+
+struct SubjectInner<T, Error>
+where
+    T: Clone + Send + Sync + 'static,
+    Error: Clone + Send + Sync + 'static,
+{
+    observers: Vec<(u64, Arc<SubscriptionObserver<T, Error>>)>,
+    next_id: u64,
+    replay: VecDeque<T>,
+    replay_capacity: usize,
+    termination: Option<Result<(), Error>>,
+}
+
+impl<T, Error> Subject<T, Error>
+where
+    T: Clone + Send + Sync + 'static,
+    Error: Clone + Send + Sync + 'static,
+{
+    /// Creates a subject.
+    pub fn new() -> Self {
+        Self::with_replay(0, [])
+    }
+
+    fn with_replay(replay_capacity: usize, values: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(SubjectInner {
+                observers: vec![],
+                next_id: 0,
+                replay: values.into_iter().collect(),
+                replay_capacity,
+                termination: None,
+            })),
+        }
+    }
+
+    /// Returns an observable of the subject.
+    pub fn observable(&self) -> Observable<T, Error> {
+        let inner = Arc::downgrade(&self.inner);
+        Observable::new(move |observer| {
+            let observer = Arc::new(observer);
+            let mut id = None;
+            let (replay, termination) = match inner.upgrade() {
+                Some(inner) => {
+                    let mut inner = inner.lock().unwrap();
+                    if inner.termination.is_none() {
+                        id = Some(inner.next_id);
+                        inner.next_id += 1;
+                        inner.observers.push((id.unwrap(), Arc::clone(&observer)));
+                    }
+                    (inner.replay.clone(), inner.termination.clone())
+                },
+                None => (VecDeque::new(), Some(Ok(()))),
+            };
+            for value in replay {
+                observer.next(value);
+            }
+            match termination {
+                Some(Ok(())) => observer.complete(),
+                Some(Err(error)) => observer.error(error),
+                None => {},
+            }
+            let inner: Weak<Mutex<SubjectInner<T, Error>>> = inner.clone();
+            move || {
+                if let (Some(inner), Some(id)) = (inner.upgrade(), id) {
+                    inner.lock().unwrap().observers.retain(|(observer_id, _)| *observer_id != id);
+                }
+            }
+        })
+    }
+
+    /// Emits a value to the current subscribers.
+    /// Does nothing if the subject has completed or errored.
+    pub fn next(&self, value: T) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.termination.is_some() {
+            return;
+        }
+        if inner.replay_capacity != 0 {
+            if inner.replay.len() == inner.replay_capacity {
+                inner.replay.pop_front();
+            }
+            inner.replay.push_back(value.clone());
+        }
+        let observers = inner.observers.iter().map(|(_, observer)| Arc::clone(observer)).collect::<Vec<_>>();
+        drop(inner);
+        for observer in observers {
+            observer.next(value.clone());
+        }
+    }
+
+    /// Emits an error to the current and later subscribers.
+    pub fn error(&self, error: Error) {
+        for observer in self.terminate(Err(error.clone())) {
+            observer.error(error.clone());
+        }
+    }
+
+    /// Completes the current and later subscribers.
+    pub fn complete(&self) {
+        for observer in self.terminate(Ok(())) {
+            observer.complete();
+        }
+    }
+
+    fn terminate(&self, termination: Result<(), Error>) -> Vec<Arc<SubscriptionObserver<T, Error>>> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.termination.is_some() {
+            return vec![];
+        }
+        inner.termination = Some(termination);
+        std::mem::take(&mut inner.observers).into_iter().map(|(_, observer)| observer).collect()
+    }
+
+    /// Returns the number of current subscribers.
+    pub fn observer_count(&self) -> usize {
+        self.inner.lock().unwrap().observers.len()
+    }
+
+    /// Indicates whether the subject has completed or errored.
+    pub fn is_terminated(&self) -> bool {
+        self.inner.lock().unwrap().termination.is_some()
+    }
+}
+
+impl<T, Error> Default for Subject<T, Error>
+where
+    T: Clone + Send + Sync + 'static,
+    Error: Clone + Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, Error> Clone for Subject<T, Error>
+where
+    T: Clone + Send + Sync + 'static,
+    Error: Clone + Send + Sync + 'static,
+{
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+/// A [`Subject`] holding a current value, which is emitted
+/// to each subscriber as it subscribes.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::observable::*;
+///
+/// let volume = BehaviorSubject::<u32>::new(50);
+/// volume.next(70);
+/// assert_eq!(volume.value(), 70);
+/// // emits 70, then the later values
+/// volume.observable().subscribe(observer! {
+///     next: |volume| println!("{volume}"),
+/// });
+/// ```
+pub struct BehaviorSubject<T, Error = ()>
+where
+    T: Clone + Send + Sync + 'static,
+    Error: Clone + Send + Sync + 'static,
+{
+    subject: Subject<T, Error>,
+}
+
+impl<T, Error> BehaviorSubject<T, Error>
+where
+    T: Clone + Send + Sync + 'static,
+    Error: Clone + Send + Sync + 'static,
+{
+    /// Creates a subject with an initial value.
+    pub fn new(value: T) -> Self {
+        Self { subject: Subject::with_replay(1, [value]) }
+    }
+
+    /// Returns the current value.
+    pub fn value(&self) -> T {
+        self.subject.inner.lock().unwrap().replay.back().unwrap().clone()
+    }
+}
+
+impl<T, Error> Deref for BehaviorSubject<T, Error>
+where
+    T: Clone + Send + Sync + 'static,
+    Error: Clone + Send + Sync + 'static,
+{
+    type Target = Subject<T, Error>;
+    fn deref(&self) -> &Self::Target {
+        &self.subject
+    }
+}
+
+impl<T, Error> Clone for BehaviorSubject<T, Error>
+where
+    T: Clone + Send + Sync + 'static,
+    Error: Clone + Send + Sync + 'static,
+{
+    fn clone(&self) -> Self {
+        Self { subject: self.subject.clone() }
+    }
+}
+
+/// A [`Subject`] that emits its last values, up to a capacity,
+/// to each subscriber as it subscribes.
+pub struct ReplaySubject<T, Error = ()>
+where
+    T: Clone + Send + Sync + 'static,
+    Error: Clone + Send + Sync + 'static,
+{
+    subject: Subject<T, Error>,
+}
+
+impl<T, Error> ReplaySubject<T, Error>
+where
+    T: Clone + Send + Sync + 'static,
+    Error: Clone + Send + Sync + 'static,
+{
+    /// Creates a subject replaying its last `capacity` values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity != 0, "rialight::util::observable::ReplaySubject must be given non-zero capacity");
+        Self { subject: Subject::with_replay(capacity, []) }
+    }
+}
+
+impl<T, Error> Deref for ReplaySubject<T, Error>
+where
+    T: Clone + Send + Sync + 'static,
+    Error: Clone + Send + Sync + 'static,
+{
+    type Target = Subject<T, Error>;
+    fn deref(&self) -> &Self::Target {
+        &self.subject
+    }
+}
+
+impl<T, Error> Clone for ReplaySubject<T, Error>
+where
+    T: Clone + Send + Sync + 'static,
+    Error: Clone + Send + Sync + 'static,
+{
+    fn clone(&self) -> Self {
+        Self { subject: self.subject.clone() }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::observable::Observer;
+
+    fn collect(observable: Observable<u32, &'static str>) -> Arc<Mutex<Vec<Result<u32, &'static str>>>> {
+        let events = Arc::new(Mutex::new(vec![]));
+        observable.subscribe(Observer {
+            next: Box::new({
+                let events = Arc::clone(&events);
+                move |value| events.lock().unwrap().push(Ok(value))
+            }),
+            error: Box::new({
+                let events = Arc::clone(&events);
+                move |error| events.lock().unwrap().push(Err(error))
+            }),
+            ..Default::default()
+        });
+        events
+    }
+
+    #[test]
+    fn subjects() {
+        let subject = Subject::new();
+        subject.next(1);
+        let early = collect(subject.observable());
+        subject.next(2);
+        assert_eq!(subject.observer_count(), 1);
+        subject.error("failure");
+        let late = collect(subject.observable());
+        assert_eq!(*early.lock().unwrap(), [Ok(2), Err("failure")]);
+        assert_eq!(*late.lock().unwrap(), [Err("failure")]);
+        assert_eq!(subject.observer_count(), 0);
+
+        let subject = BehaviorSubject::new(1);
+        subject.next(2);
+        let events = collect(subject.observable());
+        subject.next(3);
+        assert_eq!(subject.value(), 3);
+        assert_eq!(*events.lock().unwrap(), [Ok(2), Ok(3)]);
+
+        let subject = ReplaySubject::new(2);
+        for value in 1..=3 {
+            subject.next(value);
+        }
+        assert_eq!(*collect(subject.observable()).lock().unwrap(), [Ok(2), Ok(3)]);
+    }
+}
@@ -0,0 +1,236 @@
+use std::sync::{Arc, Mutex};
+use super::{derive_observable, Observable, OperatorListeners};
+use crate::{
+    futures::{Debounce, Throttle, TokenBucket},
+    timing::{background_default_interval, Duration},
+};
+
+/// Time-based operators for observables, based on the timing API.
+///
+/// Operators that schedule work, such as `debounce` and `sample`, must be
+/// subscribed to from the Rialight runtime, as they execute futures.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::{observable::*, timing::Duration};
+///
+/// fn search_queries(input: Observable<String>) -> Observable<String> {
+///     input.debounce(Duration::from_millis(300))
+/// }
+/// ```
+pub trait TimingOperators<T, Error = ()>
+where
+    T: Send + Sync + 'static,
+    Error: Send + Sync + 'static,
+{
+    /// Emits a value only after `duration` has elapsed without
+    /// the source emitting another value. A pending value
+    /// is emitted when the source completes.
+    fn debounce(&self, duration: Duration) -> Observable<T, Error>;
+
+    /// Emits a value and then ignores the values emitted by the source
+    /// until `duration` has elapsed.
+    fn throttle(&self, duration: Duration) -> Observable<T, Error>;
+
+    /// Emits the most recent value emitted by the source, if any,
+    /// once every `period`.
+    fn sample(&self, period: Duration) -> Observable<T, Error>;
+
+    /// Emits values as long as a token bucket of `capacity` tokens,
+    /// refilled by one token every `refill_period`, has tokens available,
+    /// dropping the values emitted while it is empty.
+    fn rate_limit(&self, capacity: u32, refill_period: Duration) -> Observable<T, Error>;
+
+    /// Emits the values emitted by the source during each `period`
+    /// as a list, skipping periods without values. The remaining
+    /// values are emitted when the source completes.
+    fn buffer_time(&self, period: Duration) -> Observable<Vec<T>, Error>;
+}
+
+impl<T, Error> TimingOperators<T, Error> for Observable<T, Error>
+where
+    T: Send + Sync + 'static,
+    Error: Send + Sync + 'static,
+{
+    fn debounce(&self, duration: Duration) -> Observable<T, Error> {
+        derive_observable(self, move |observer| {
+            let debounce = Arc::new(Debounce::new(duration));
+            let pending = Arc::new(Mutex::new(None::<T>));
+            OperatorListeners::new({
+                let (observer, debounce, pending) = (Arc::clone(&observer), Arc::clone(&debounce), Arc::clone(&pending));
+                move |value| {
+                    *pending.lock().unwrap() = Some(value);
+                    let (observer, pending) = (Arc::clone(&observer), Arc::clone(&pending));
+                    debounce.call(move || {
+                        if let Some(value) = pending.lock().unwrap().take() {
+                            observer.next(value);
+                        }
+                    });
+                }
+            })
+            .error({
+                let (observer, debounce) = (Arc::clone(&observer), Arc::clone(&debounce));
+                move |error| {
+                    debounce.cancel();
+                    observer.error(error);
+                }
+            })
+            .complete({
+                let (observer, debounce, pending) = (Arc::clone(&observer), Arc::clone(&debounce), Arc::clone(&pending));
+                move || {
+                    debounce.cancel();
+                    if let Some(value) = pending.lock().unwrap().take() {
+                        observer.next(value);
+                    }
+                    observer.complete();
+                }
+            })
+            .cleanup(move || debounce.cancel())
+        })
+    }
+
+    fn throttle(&self, duration: Duration) -> Observable<T, Error> {
+        derive_observable(self, move |observer| {
+            let throttle = Throttle::new(duration);
+            OperatorListeners::new(move |value| {
+                if throttle.try_ready() {
+                    observer.next(value);
+                }
+            })
+        })
+    }
+
+    fn sample(&self, period: Duration) -> Observable<T, Error> {
+        derive_observable(self, move |observer| {
+            let latest = Arc::new(Mutex::new(None::<T>));
+            let interval = Arc::new(background_default_interval({
+                let (observer, latest) = (Arc::clone(&observer), Arc::clone(&latest));
+                move |_| {
+                    if let Some(value) = latest.lock().unwrap().take() {
+                        observer.next(value);
+                    }
+                }
+            }, period));
+            OperatorListeners::new(move |value| {
+                *latest.lock().unwrap() = Some(value);
+            })
+            .error({
+                let (observer, interval) = (Arc::clone(&observer), Arc::clone(&interval));
+                move |error| {
+                    interval.stop();
+                    observer.error(error);
+                }
+            })
+            .complete({
+                let interval = Arc::clone(&interval);
+                move || {
+                    interval.stop();
+                    observer.complete();
+                }
+            })
+            .cleanup(move || interval.stop())
+        })
+    }
+
+    fn rate_limit(&self, capacity: u32, refill_period: Duration) -> Observable<T, Error> {
+        derive_observable(self, move |observer| {
+            let bucket = TokenBucket::new(capacity, refill_period);
+            OperatorListeners::new(move |value| {
+                if bucket.try_acquire() {
+                    observer.next(value);
+                }
+            })
+        })
+    }
+
+    fn buffer_time(&self, period: Duration) -> Observable<Vec<T>, Error> {
+        derive_observable(self, move |observer| {
+            let buffer = Arc::new(Mutex::new(Vec::<T>::new()));
+            let interval = Arc::new(background_default_interval({
+                let (observer, buffer) = (Arc::clone(&observer), Arc::clone(&buffer));
+                move |_| {
+                    let values = std::mem::take(&mut *buffer.lock().unwrap());
+                    if !values.is_empty() {
+                        observer.next(values);
+                    }
+                }
+            }, period));
+            OperatorListeners::new({
+                let buffer = Arc::clone(&buffer);
+                move |value| buffer.lock().unwrap().push(value)
+            })
+            .error({
+                let (observer, interval) = (Arc::clone(&observer), Arc::clone(&interval));
+                move |error| {
+                    interval.stop();
+                    observer.error(error);
+                }
+            })
+            .complete({
+                let interval = Arc::clone(&interval);
+                move || {
+                    interval.stop();
+                    let values = std::mem::take(&mut *buffer.lock().unwrap());
+                    if !values.is_empty() {
+                        observer.next(values);
+                    }
+                    observer.complete();
+                }
+            })
+            .cleanup(move || interval.stop())
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{futures::exec_future, observable::Observer, timing::{wait, TestClock}};
+
+    /// Emits each value after waiting the given milliseconds.
+    fn timed_source(values: Vec<(u64, u32)>) -> Observable<u32> {
+        Observable::new(move |observer| {
+            let values = values.clone();
+            exec_future(async move {
+                for (ms, value) in values {
+                    wait(Duration::from_millis(ms)).await;
+                    observer.next(value);
+                }
+                observer.complete();
+            });
+            || {}
+        })
+    }
+
+    fn collect<T: Clone + Send + Sync + 'static>(observable: Observable<T>) -> Vec<(T, Duration)> {
+        let clock = TestClock::new();
+        let emitted = Arc::new(Mutex::new(vec![]));
+        {
+            let _guard = clock.enter();
+            observable.subscribe(Observer {
+                next: Box::new({
+                    let emitted = Arc::clone(&emitted);
+                    move |value| emitted.lock().unwrap().push((value, TestClock::current().unwrap().elapsed()))
+                }),
+                ..Default::default()
+            });
+        }
+        clock.advance(Duration::from_secs(10));
+        let emitted = emitted.lock().unwrap().clone();
+        emitted
+    }
+
+    #[test]
+    fn operators() {
+        let ms = Duration::from_millis;
+        let source = || timed_source(vec![(0, 1), (50, 2), (50, 3), (200, 4), (50, 5)]);
+        assert_eq!(collect(source().debounce(ms(100))), [(3, ms(200)), (5, ms(350))]);
+        assert_eq!(collect(source().throttle(ms(100))), [(1, ms(0)), (3, ms(100)), (4, ms(300))]);
+        assert_eq!(collect(source().sample(ms(80))), [(2, ms(80)), (3, ms(160)), (4, ms(320))]);
+        assert_eq!(collect(source().rate_limit(2, ms(200))), [(1, ms(0)), (2, ms(50)), (4, ms(300))]);
+
+        let buffers = collect(source().buffer_time(ms(80)));
+        assert_eq!(buffers, [(vec![1, 2], ms(80)), (vec![3], ms(160)), (vec![4], ms(320)), (vec![5], ms(350))]);
+    }
+}
@@ -42,9 +42,9 @@ Precedence in character classes, from most binding to least:
 1. Ranges: `[a-cd]` == `[[a-c]d]`
 2. Union: `[ab&&bc]` == `[[ab]&&[bc]]`
 3. Intersection, difference, symmetric difference.
-All three have equivalent precedence, and are evaluated in
-left-to-right order. For example, `[\pL--\p{Greek}&&\p{Uppercase}]` ==
-`[[\pL--\p{Greek}]&&\p{Uppercase}]`.
+   All three have equivalent precedence, and are evaluated in
+   left-to-right order. For example, `[\pL--\p{Greek}&&\p{Uppercase}]` ==
+   `[[\pL--\p{Greek}]&&\p{Uppercase}]`.
 4. Negation: `[^a-z&&b]` == `[^[a-z&&b]]`.
 
 # Composites
@@ -0,0 +1,188 @@
+/*!
+Working with compact binary serialization.
+
+This format, based on [bincode](https://docs.rs/bincode), encodes integers
+with a variable length and does not describe its data: fields are encoded
+in order, without names. It is the most compact format, suited to network
+packets and asset caches, but data can only be deserialized into the same
+type it was serialized from.
+
+```
+use rialight_util::serialization::{bincode, Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Position {
+    x: i32,
+    y: i32,
+}
+
+let bytes = bincode::serialize(&Position { x: 1, y: -1 }).unwrap();
+assert_eq!(bytes.len(), 2);
+assert_eq!(bincode::deserialize_from_slice::<Position>(&bytes).unwrap(), Position { x: 1, y: -1 });
+```
+
+There is no untyped representation of this format, as it does not
+describe its data.
+
+# Versioning
+
+Since the format does not describe its data, data that must remain
+readable after its type changes, such as save games, should be versioned.
+[`serialize_versioned`] prefixes the data with a version number, which
+[`read_version`] reads to select the type to deserialize into.
+
+```
+use rialight_util::serialization::{bincode, Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct SaveV1 { level: u32 }
+
+#[derive(Serialize, Deserialize)]
+struct SaveV2 { level: u32, checkpoint: u32 }
+
+fn load(bytes: &[u8]) -> bincode::Result<SaveV2> {
+    let (version, data) = bincode::read_version(bytes)?;
+    Ok(match version {
+        1 => {
+            let save: SaveV1 = bincode::deserialize_from_slice(data)?;
+            SaveV2 { level: save.level, checkpoint: 0 }
+        },
+        _ => bincode::deserialize_from_slice(data)?,
+    })
+}
+
+let bytes = bincode::serialize_versioned(1, &SaveV1 { level: 3 }).unwrap();
+assert_eq!(load(&bytes).unwrap().level, 3);
+```
+*/
+
+use std::fmt::Display;
+use ::bincode::{config::Configuration, error::{DecodeError, EncodeError}};
+use super::{generic_deserialization::DeserializeOwned, Deserialize, Serialize};
+use crate::bytes::{BufferMut, Bytes, BytesMut};
+
+const CONFIGURATION: Configuration = ::bincode::config::standard();
+
+/// Error returned by binary serialization.
+#[derive(Debug)]
+pub enum Error {
+    /// A value could not be serialized.
+    Serialization(EncodeError),
+    /// The data could not be deserialized.
+    Deserialization(DecodeError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Serialization(error) => write!(f, "{error}"),
+            Self::Deserialization(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<EncodeError> for Error {
+    fn from(error: EncodeError) -> Self {
+        Self::Serialization(error)
+    }
+}
+
+impl From<DecodeError> for Error {
+    fn from(error: DecodeError) -> Self {
+        Self::Deserialization(error)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Deserializes binary data given as a sequence of bytes into a value.
+pub fn deserialize_from_slice<'a, T>(slice: &'a [u8]) -> Result<T>
+    where T: Deserialize<'a>
+{
+    Ok(::bincode::serde::borrow_decode_from_slice(slice, CONFIGURATION)?.0)
+}
+
+/// Deserializes binary data from a reader into a value.
+pub fn deserialize_from_reader<R, T>(mut reader: R) -> Result<T>
+    where
+        R: std::io::Read,
+        T: DeserializeOwned
+{
+    Ok(::bincode::serde::decode_from_std_read(&mut reader, CONFIGURATION)?)
+}
+
+/// Serializes a value into binary data.
+pub fn serialize<T>(value: &T) -> Result<Bytes>
+    where T: ?Sized + Serialize
+{
+    Ok(serialize_as_byte_vec(value)?.into())
+}
+
+/// Serializes a value into binary data as a byte vector.
+pub fn serialize_as_byte_vec<T>(value: &T) -> Result<Vec<u8>>
+    where T: ?Sized + Serialize
+{
+    Ok(::bincode::serde::encode_to_vec(value, CONFIGURATION)?)
+}
+
+/// Serializes a value into binary data using an I/O stream.
+pub fn serialize_with_writer<W, T>(mut writer: W, value: &T) -> Result<()>
+    where
+        W: std::io::Write,
+        T: ?Sized + Serialize
+{
+    ::bincode::serde::encode_into_std_write(value, &mut writer, CONFIGURATION)?;
+    Ok(())
+}
+
+/// Serializes a value into binary data, appending it to a buffer.
+pub fn serialize_into_buffer<T>(buffer: &mut BytesMut, value: &T) -> Result<()>
+    where T: ?Sized + Serialize
+{
+    serialize_with_writer(buffer.writer(), value)
+}
+
+/// Serializes a value into binary data prefixed with a version number.
+pub fn serialize_versioned<T>(version: u32, value: &T) -> Result<Bytes>
+    where T: ?Sized + Serialize
+{
+    let mut buffer = BytesMut::new();
+    serialize_into_buffer(&mut buffer, &version)?;
+    serialize_into_buffer(&mut buffer, value)?;
+    Ok(buffer.freeze())
+}
+
+/// Reads the version number of data serialized by [`serialize_versioned`],
+/// returning it with the remaining data.
+pub fn read_version(slice: &[u8]) -> Result<(u32, &[u8])> {
+    let (version, length) = ::bincode::serde::decode_from_slice(slice, CONFIGURATION)?;
+    Ok((version, &slice[length..]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Packet<'a> {
+        id: u16,
+        name: &'a str,
+    }
+
+    #[test]
+    fn bincode() {
+        let packet = Packet { id: 300, name: "move" };
+        let mut buffer = BytesMut::new();
+        serialize_into_buffer(&mut buffer, &packet).unwrap();
+        assert_eq!(buffer.as_ref(), [251, 44, 1, 4, b'm', b'o', b'v', b'e']);
+        assert_eq!(deserialize_from_slice::<Packet>(&buffer).unwrap(), packet);
+        assert_eq!(deserialize_from_reader::<_, (u16, String)>(buffer.as_ref()).unwrap(), (300, "move".into()));
+
+        let versioned = serialize_versioned(2, &packet).unwrap();
+        let (version, data) = read_version(&versioned).unwrap();
+        assert_eq!((version, deserialize_from_slice::<Packet>(data).unwrap()), (2, packet));
+        assert!(matches!(deserialize_from_slice::<Packet>(&[]), Err(Error::Deserialization(_))));
+    }
+}
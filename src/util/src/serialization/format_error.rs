@@ -0,0 +1,83 @@
+use std::fmt::Display;
+
+/// Error returned by the text formats [`toml`](super::toml), [`yaml`](super::yaml)
+/// and [`ron`](super::ron), with the position of the error in the text
+/// when it is known.
+#[derive(PartialEq, Clone, Debug)]
+pub struct FormatError {
+    message: String,
+    line: Option<usize>,
+    column: Option<usize>,
+}
+
+impl FormatError {
+    pub(crate) fn new(message: impl Display) -> Self {
+        Self { message: message.to_string(), line: None, column: None }
+    }
+
+    pub(crate) fn at(message: impl Display, line: usize, column: usize) -> Self {
+        Self { message: message.to_string(), line: Some(line), column: Some(column) }
+    }
+
+    /// Creates an error at a byte offset of the text, which is moved back
+    /// to the start of the character containing it.
+    pub(crate) fn at_offset(message: impl Display, text: &str, offset: usize) -> Self {
+        let before = &text[..text.floor_char_boundary(offset)];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self::at(message, before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+    }
+
+    /// Returns the description of the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the line of the error, counted from 1.
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// Returns the column of the error, counted from 1.
+    pub fn column(&self) -> Option<usize> {
+        self.column
+    }
+}
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "{} (line {line}, column {column})", self.message),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+impl From<std::io::Error> for FormatError {
+    fn from(error: std::io::Error) -> Self {
+        Self::new(error)
+    }
+}
+
+impl From<std::str::Utf8Error> for FormatError {
+    fn from(error: std::str::Utf8Error) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn at_offset() {
+        let text = "a = 1\nb = \"é\"\n";
+        let error = FormatError::at_offset("error", text, 11);
+        assert_eq!((error.line(), error.column()), (Some(2), Some(6)));
+        let error = FormatError::at_offset("error", text, 12);
+        assert_eq!((error.line(), error.column()), (Some(2), Some(6)));
+        let error = FormatError::at_offset("error", text, 100);
+        assert_eq!((error.line(), error.column()), (Some(3), Some(1)));
+    }
+}
@@ -0,0 +1,228 @@
+use std::{io::{BufRead, Write}, marker::PhantomData};
+use futures::{io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt}, Stream};
+use crate::{bytes::Buffer, serialization::{generic_deserialization::DeserializeOwned, FormatError, Serialize}};
+
+type Result<T> = std::result::Result<T, FormatError>;
+
+/// Converts the error of a record, positioning it at the line of the record.
+fn record_error(error: serde_json::Error, line: usize) -> FormatError {
+    let position = format!(" at line {} column {}", error.line(), error.column());
+    FormatError::at(error.to_string().replacen(&position, "", 1), line, error.column())
+}
+
+/// Reads [JSON Lines](https://jsonlines.org), also known as NDJSON,
+/// where each line is a JSON record of type `T`.
+///
+/// Records are read one at a time through the `Iterator` implementation.
+/// Blank lines are skipped, and errors give the line of the record.
+///
+/// ```
+/// use rialight_util::serialization::{json::LinesReader, Deserialize};
+///
+/// #[derive(Deserialize)]
+/// struct Frame {
+///     time: f64,
+/// }
+///
+/// let log = "{\"time\": 0.016}\n{\"time\": 0.033}\n";
+/// let frames = LinesReader::<_, Frame>::new(log.as_bytes()).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(frames.len(), 2);
+/// ```
+pub struct LinesReader<R, T> {
+    reader: R,
+    line: usize,
+    buffer: String,
+    _record: PhantomData<fn() -> T>,
+}
+
+impl<R: BufRead, T: DeserializeOwned> LinesReader<R, T> {
+    /// Constructs a reader of records from a buffered I/O stream.
+    pub fn new(reader: R) -> Self {
+        Self { reader, line: 0, buffer: String::new(), _record: PhantomData }
+    }
+}
+
+impl<B: Buffer, T: DeserializeOwned> LinesReader<::bytes::buf::Reader<B>, T> {
+    /// Constructs a reader of records from a buffer.
+    pub fn from_buffer(buffer: B) -> Self {
+        Self::new(buffer.reader())
+    }
+}
+
+impl<R: BufRead, T: DeserializeOwned> Iterator for LinesReader<R, T> {
+    type Item = Result<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.buffer.clear();
+            self.line += 1;
+            match self.reader.read_line(&mut self.buffer) {
+                Ok(0) => return None,
+                Ok(_) if self.buffer.trim().is_empty() => {},
+                Ok(_) => return Some(serde_json::from_str(self.buffer.trim_end()).map_err(|error| record_error(error, self.line))),
+                Err(error) => return Some(Err(FormatError::at(error, self.line, 1))),
+            }
+        }
+    }
+}
+
+/// Writes [JSON Lines](https://jsonlines.org), also known as NDJSON,
+/// where each line is a JSON record.
+///
+/// ```
+/// use rialight_util::{bytes::{BufferMut, BytesMut}, serialization::json::{json, LinesWriter}};
+///
+/// let mut writer = LinesWriter::new(BytesMut::new().writer());
+/// writer.write(&json!({ "event": "start" })).unwrap();
+/// writer.write(&json!({ "event": "stop" })).unwrap();
+/// assert_eq!(writer.into_inner().into_inner(), "{\"event\":\"start\"}\n{\"event\":\"stop\"}\n");
+/// ```
+pub struct LinesWriter<W> {
+    writer: W,
+}
+
+impl<W: Write> LinesWriter<W> {
+    /// Constructs a writer of records to an I/O stream.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Writes a record.
+    pub fn write<T>(&mut self, record: &T) -> Result<()>
+        where T: ?Sized + Serialize
+    {
+        serde_json::to_writer(&mut self.writer, record).map_err(FormatError::new)?;
+        Ok(self.writer.write_all(b"\n")?)
+    }
+
+    /// Flushes the underlying I/O stream.
+    pub fn flush(&mut self) -> Result<()> {
+        Ok(self.writer.flush()?)
+    }
+
+    /// Returns the underlying I/O stream.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads [JSON Lines](https://jsonlines.org) from an asynchronous I/O stream.
+///
+/// This is the asynchronous equivalent of [`LinesReader`].
+///
+/// ```
+/// use rialight_util::{futures::StreamExt, serialization::json::AsyncLinesReader, timing::TestClock};
+///
+/// let reader = AsyncLinesReader::<_, u32>::new(&b"1\n2\n"[..]);
+/// let records: Vec<u32> = TestClock::new().block_on(async move {
+///     reader.into_stream().map(Result::unwrap).collect().await
+/// });
+/// assert_eq!(records, [1, 2]);
+/// ```
+pub struct AsyncLinesReader<R, T> {
+    reader: R,
+    line: usize,
+    buffer: String,
+    _record: PhantomData<fn() -> T>,
+}
+
+impl<R: AsyncBufRead + Unpin, T: DeserializeOwned> AsyncLinesReader<R, T> {
+    /// Constructs a reader of records from a buffered asynchronous I/O stream.
+    pub fn new(reader: R) -> Self {
+        Self { reader, line: 0, buffer: String::new(), _record: PhantomData }
+    }
+
+    /// Reads the next record, or returns `None` at the end of the stream.
+    pub async fn read(&mut self) -> Result<Option<T>> {
+        loop {
+            self.buffer.clear();
+            self.line += 1;
+            let length = self.reader.read_line(&mut self.buffer).await.map_err(|error| FormatError::at(error, self.line, 1))?;
+            if length == 0 {
+                return Ok(None);
+            }
+            if !self.buffer.trim().is_empty() {
+                return serde_json::from_str(self.buffer.trim_end()).map(Some).map_err(|error| record_error(error, self.line));
+            }
+        }
+    }
+
+    /// Converts the reader into a stream of records.
+    pub fn into_stream(self) -> impl Stream<Item = Result<T>> {
+        futures::stream::unfold(self, |mut reader| async move {
+            let record = reader.read().await.transpose()?;
+            Some((record, reader))
+        })
+    }
+}
+
+/// Writes [JSON Lines](https://jsonlines.org) to an asynchronous I/O stream.
+///
+/// This is the asynchronous equivalent of [`LinesWriter`].
+pub struct AsyncLinesWriter<W> {
+    writer: W,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncLinesWriter<W> {
+    /// Constructs a writer of records to an asynchronous I/O stream.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Writes a record.
+    pub async fn write<T>(&mut self, record: &T) -> Result<()>
+        where T: ?Sized + Serialize
+    {
+        let mut line = serde_json::to_vec(record).map_err(FormatError::new)?;
+        line.push(b'\n');
+        Ok(self.writer.write_all(&line).await?)
+    }
+
+    /// Flushes the underlying I/O stream.
+    pub async fn flush(&mut self) -> Result<()> {
+        Ok(self.writer.flush().await?)
+    }
+
+    /// Returns the underlying I/O stream.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{serialization::Deserialize, timing::TestClock};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Record {
+        id: u32,
+        text: String,
+    }
+
+    #[test]
+    fn lines() {
+        let records = [Record { id: 1, text: "a\nb".into() }, Record { id: 2, text: "c".into() }];
+        let mut writer = LinesWriter::new(vec![]);
+        for record in &records {
+            writer.write(record).unwrap();
+        }
+        let mut output = writer.into_inner();
+        assert_eq!(output.iter().filter(|byte| **byte == b'\n').count(), 2);
+        output.extend_from_slice(b"\n{\"id\": 3}\n");
+
+        let mut reader = LinesReader::<_, Record>::from_buffer(&output[..]);
+        assert_eq!(reader.next().unwrap().unwrap(), records[0]);
+        assert_eq!(reader.next().unwrap().unwrap(), records[1]);
+        let error = reader.next().unwrap().unwrap_err();
+        assert_eq!(error.to_string(), "missing field `text` (line 4, column 9)");
+        assert!(reader.next().is_none());
+
+        let read = TestClock::new().block_on(async move {
+            let mut writer = AsyncLinesWriter::new(vec![]);
+            writer.write(&records[1]).await.unwrap();
+            let output = writer.into_inner();
+            AsyncLinesReader::<_, Record>::new(&output[..]).read().await.unwrap()
+        });
+        assert_eq!(read, Some(Record { id: 2, text: "c".into() }));
+    }
+}
@@ -17,6 +17,24 @@ fn main() {
     });
 }
 ```
+
+# Pointers and patches
+
+A [`Pointer`] identifies a location within a `Value`. A [`Patch`]
+describes the changes between two values as a sequence of operations,
+and [`merge_patch`] merges a partial value into a value.
+
+# Schemas
+
+A [`Schema`] validates the structure of a `Value`, reporting every
+violation, and can be generated from types deriving [`JsonSchema`].
+
+# Streaming
+
+For documents too large to hold in memory, a [`Tokenizer`] reads JSON
+as a sequence of events, and a [`LinesReader`] reads [JSON Lines](https://jsonlines.org)
+one record at a time. [`AsyncTokenizer`] and [`AsyncLinesReader`] read
+from asynchronous I/O streams.
 */
 
 use super::{Deserialize, Serialize};
@@ -30,6 +48,21 @@ pub use serde_json::{
 pub use serde_json::json;
 pub use self::error::Result;
 
+mod pointer;
+pub use pointer::{Pointer, PointerError};
+
+mod patch;
+pub use patch::{Patch, PatchOperation, PatchError, PatchErrorKind, merge_patch, merge_patch_diff};
+
+mod schema;
+pub use schema::{Schema, SchemaError, ValidationError, JsonSchema};
+
+mod tokenizer;
+pub use tokenizer::{Event, Tokenizer, AsyncTokenizer};
+
+mod lines;
+pub use lines::{LinesReader, LinesWriter, AsyncLinesReader, AsyncLinesWriter};
+
 /// Deserializes a JSON string into a value.
 pub fn deserialize<'a, T>(string: &'a str) -> Result<T>
     where T: Deserialize<'a>
@@ -0,0 +1,315 @@
+use std::fmt::Display;
+use super::{Map, Pointer, PointerError, Value, pointer::insertion_index};
+use crate::serialization::{Deserialize, Serialize};
+
+/// A [JSON Patch](https://www.rfc-editor.org/rfc/rfc6902), a sequence
+/// of operations transforming a JSON value.
+///
+/// A patch serializes into the standard JSON representation, an array
+/// of operation objects.
+///
+/// ```
+/// use rialight_util::serialization::json::{self, json, Patch};
+///
+/// let source = json!({ "name": "Slime", "drops": ["gel"] });
+/// let target = json!({ "name": "Slime", "drops": ["gel", "coin"], "level": 2 });
+///
+/// let patch = Patch::diff(&source, &target);
+/// assert_eq!(json::serialize(&patch).unwrap(),
+///     r#"[{"op":"add","path":"/drops/1","value":"coin"},{"op":"add","path":"/level","value":2}]"#);
+///
+/// let mut value = source.clone();
+/// patch.apply(&mut value).unwrap();
+/// assert_eq!(value, target);
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug, Default)]
+#[serde(transparent)]
+pub struct Patch(pub Vec<PatchOperation>);
+
+/// An operation of a [`Patch`].
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    /// Adds a value to an object, inserts it into an array,
+    /// or replaces the whole value.
+    Add { path: Pointer, value: Value },
+    /// Removes a value.
+    Remove { path: Pointer },
+    /// Replaces an existing value.
+    Replace { path: Pointer, value: Value },
+    /// Removes a value and adds it at another location.
+    Move { from: Pointer, path: Pointer },
+    /// Copies a value to another location.
+    Copy { from: Pointer, path: Pointer },
+    /// Checks that a value is equal to the given value.
+    Test { path: Pointer, value: Value },
+}
+
+impl Patch {
+    /// Returns a patch transforming `source` into `target`.
+    pub fn diff(source: &Value, target: &Value) -> Self {
+        let mut operations = vec![];
+        diff(&Pointer::root(), source, target, &mut operations);
+        Self(operations)
+    }
+
+    /// Applies the patch to a value. If an operation fails,
+    /// the value is left unchanged.
+    pub fn apply(&self, target: &mut Value) -> Result<(), PatchError> {
+        let mut result = target.clone();
+        for (operation, patch_operation) in self.0.iter().enumerate() {
+            apply(patch_operation, &mut result).map_err(|kind| PatchError { operation, kind })?;
+        }
+        *target = result;
+        Ok(())
+    }
+}
+
+impl From<Vec<PatchOperation>> for Patch {
+    fn from(operations: Vec<PatchOperation>) -> Self {
+        Self(operations)
+    }
+}
+
+fn diff(path: &Pointer, source: &Value, target: &Value, operations: &mut Vec<PatchOperation>) {
+    if source == target {
+        return;
+    }
+    match (source, target) {
+        (Value::Object(source), Value::Object(target)) => {
+            for key in source.keys().filter(|key| !target.contains_key(*key)) {
+                operations.push(PatchOperation::Remove { path: path.join(key) });
+            }
+            for (key, target) in target {
+                match source.get(key) {
+                    Some(source) => diff(&path.join(key), source, target, operations),
+                    None => operations.push(PatchOperation::Add { path: path.join(key), value: target.clone() }),
+                }
+            }
+        },
+        (Value::Array(source), Value::Array(target)) => {
+            for (index, (source, target)) in source.iter().zip(target).enumerate() {
+                diff(&path.join(index), source, target, operations);
+            }
+            for index in (target.len()..source.len()).rev() {
+                operations.push(PatchOperation::Remove { path: path.join(index) });
+            }
+            for (index, target) in target.iter().enumerate().skip(source.len()) {
+                operations.push(PatchOperation::Add { path: path.join(index), value: target.clone() });
+            }
+        },
+        _ => operations.push(PatchOperation::Replace { path: path.clone(), value: target.clone() }),
+    }
+}
+
+fn apply(operation: &PatchOperation, target: &mut Value) -> Result<(), PatchErrorKind> {
+    match operation {
+        PatchOperation::Add { path, value } => add(path, target, value.clone()).map_err(pointer_error(path)),
+        PatchOperation::Remove { path } => path.remove(target).map(drop).map_err(pointer_error(path)),
+        PatchOperation::Replace { path, value } => {
+            *path.get_mut(target).ok_or(PointerError::NotFound).map_err(pointer_error(path))? = value.clone();
+            Ok(())
+        },
+        PatchOperation::Move { from, path } => {
+            if from == path {
+                return Ok(());
+            }
+            if path.starts_with(from) {
+                return Err(PatchErrorKind::MoveIntoDescendant { from: from.clone(), path: path.clone() });
+            }
+            let value = from.remove(target).map_err(pointer_error(from))?;
+            add(path, target, value).map_err(pointer_error(path))
+        },
+        PatchOperation::Copy { from, path } => {
+            let value = from.get(target).cloned().ok_or(PointerError::NotFound).map_err(pointer_error(from))?;
+            add(path, target, value).map_err(pointer_error(path))
+        },
+        PatchOperation::Test { path, value } => {
+            let actual = path.get(target);
+            if actual == Some(value) {
+                return Ok(());
+            }
+            Err(PatchErrorKind::TestFailed { path: path.clone(), expected: value.clone(), actual: actual.cloned() })
+        },
+    }
+}
+
+fn pointer_error(path: &Pointer) -> impl FnOnce(PointerError) -> PatchErrorKind + '_ {
+    move |error| PatchErrorKind::Pointer { path: path.clone(), error }
+}
+
+/// Adds a value as described by the `add` operation, which inserts
+/// into arrays instead of replacing their elements.
+fn add(path: &Pointer, target: &mut Value, value: Value) -> Result<(), PointerError> {
+    let Some((parent, token)) = path.parent_mut(target)? else {
+        *target = value;
+        return Ok(());
+    };
+    match parent {
+        Value::Object(map) => {
+            map.insert(token.clone(), value);
+        },
+        Value::Array(array) => {
+            let index = insertion_index(token, array.len())?;
+            array.insert(index, value);
+        },
+        _ => return Err(PointerError::NotFound),
+    }
+    Ok(())
+}
+
+/// Error returned when applying a [`Patch`].
+#[derive(PartialEq, Clone, Debug)]
+pub struct PatchError {
+    operation: usize,
+    kind: PatchErrorKind,
+}
+
+impl PatchError {
+    /// Returns the index of the operation that failed.
+    pub fn operation(&self) -> usize {
+        self.operation
+    }
+
+    /// Returns the reason the operation failed.
+    pub fn kind(&self) -> &PatchErrorKind {
+        &self.kind
+    }
+}
+
+impl Display for PatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "JSON patch operation {} failed: {}", self.operation, self.kind)
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// The reason a [`Patch`] operation failed.
+#[derive(PartialEq, Clone, Debug)]
+pub enum PatchErrorKind {
+    /// A location could not be resolved.
+    Pointer { path: Pointer, error: PointerError },
+    /// A `move` operation moves a value into one of its descendants.
+    MoveIntoDescendant { from: Pointer, path: Pointer },
+    /// A `test` operation found a different value, or no value.
+    TestFailed { path: Pointer, expected: Value, actual: Option<Value> },
+}
+
+impl Display for PatchErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pointer { path, error } => write!(f, "{error} at \"{path}\""),
+            Self::MoveIntoDescendant { from, path } => write!(f, "cannot move \"{from}\" into its descendant \"{path}\""),
+            Self::TestFailed { path, expected, actual: Some(actual) } => write!(f, "test at \"{path}\" expected {expected}, found {actual}"),
+            Self::TestFailed { path, expected, actual: None } => write!(f, "test at \"{path}\" expected {expected}, found nothing"),
+        }
+    }
+}
+
+/// Applies a [JSON Merge Patch](https://www.rfc-editor.org/rfc/rfc7386)
+/// to a value.
+///
+/// The members of an object patch are merged recursively into the
+/// value, with `null` members removing the corresponding members.
+/// Any other patch replaces the value.
+///
+/// ```
+/// use rialight_util::serialization::json::{self, json};
+///
+/// let mut settings = json!({ "volume": 80, "keys": { "jump": "Space", "crouch": "C" } });
+/// json::merge_patch(&mut settings, &json!({ "volume": 50, "keys": { "crouch": null } }));
+/// assert_eq!(settings, json!({ "volume": 50, "keys": { "jump": "Space" } }));
+/// ```
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let Value::Object(map) = target else {
+        unreachable!();
+    };
+    for (key, value) in patch {
+        if value.is_null() {
+            map.remove(key);
+        } else {
+            merge_patch(map.entry(key).or_insert(Value::Null), value);
+        }
+    }
+}
+
+/// Returns a [JSON Merge Patch](https://www.rfc-editor.org/rfc/rfc7386)
+/// transforming `source` into `target`.
+///
+/// Since `null` removes members, `null` members of `target` objects
+/// cannot be represented and are removed by the patch.
+pub fn merge_patch_diff(source: &Value, target: &Value) -> Value {
+    let (Value::Object(source), Value::Object(target)) = (source, target) else {
+        return target.clone();
+    };
+    let mut patch = Map::new();
+    for key in source.keys().filter(|key| !target.contains_key(*key)) {
+        patch.insert(key.clone(), Value::Null);
+    }
+    for (key, target) in target {
+        match source.get(key) {
+            Some(source) if source == target => {},
+            Some(source) => {
+                patch.insert(key.clone(), merge_patch_diff(source, target));
+            },
+            None => {
+                patch.insert(key.clone(), target.clone());
+            },
+        }
+    }
+    Value::Object(patch)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::{deserialize, json};
+
+    #[test]
+    fn patch() {
+        let mut value = json!({ "foo": ["bar", "baz"], "qux": { "a": 1 } });
+        let patch: Patch = deserialize(r#"[
+            { "op": "add", "path": "/foo/1", "value": "qux" },
+            { "op": "remove", "path": "/foo/0" },
+            { "op": "replace", "path": "/qux/a", "value": 2 },
+            { "op": "copy", "from": "/qux", "path": "/copy" },
+            { "op": "move", "from": "/foo", "path": "/copy/foo" },
+            { "op": "test", "path": "/copy/foo/1", "value": "baz" }
+        ]"#).unwrap();
+        patch.apply(&mut value).unwrap();
+        assert_eq!(value, json!({ "qux": { "a": 2 }, "copy": { "a": 2, "foo": ["qux", "baz"] } }));
+
+        let failing = Patch(vec![
+            PatchOperation::Remove { path: Pointer::parse("/qux").unwrap() },
+            PatchOperation::Test { path: Pointer::parse("/copy/a").unwrap(), value: json!(3) },
+        ]);
+        let error = failing.apply(&mut value).unwrap_err();
+        assert_eq!(error.operation(), 1);
+        assert_eq!(error.to_string(), "JSON patch operation 1 failed: test at \"/copy/a\" expected 3, found 2");
+        assert!(value.get("qux").is_some());
+
+        let source = json!({ "a": [1, 2, 3], "b": { "c": true }, "d": null });
+        let target = json!({ "a": [1, 5], "b": 0, "e": "new" });
+        let mut patched = source.clone();
+        Patch::diff(&source, &target).apply(&mut patched).unwrap();
+        assert_eq!(patched, target);
+    }
+
+    #[test]
+    fn merge() {
+        let mut value = json!({ "title": "Goodbye!", "author": { "givenName": "John", "familyName": "Doe" }, "tags": ["example", "sample"] });
+        let patch = json!({ "title": "Hello!", "author": { "familyName": null }, "tags": ["example"], "phoneNumber": "+01-123-456-7890" });
+        let expected = json!({ "title": "Hello!", "author": { "givenName": "John" }, "tags": ["example"], "phoneNumber": "+01-123-456-7890" });
+        assert_eq!(merge_patch_diff(&value, &expected), patch);
+        merge_patch(&mut value, &patch);
+        assert_eq!(value, expected);
+    }
+}
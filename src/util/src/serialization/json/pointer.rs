@@ -0,0 +1,269 @@
+use std::{fmt::Display, str::FromStr};
+use super::Value;
+use crate::serialization::{Deserialize, Deserializer, Serialize, Serializer, generic_deserialization};
+
+/// A [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901), identifying
+/// a location within a JSON value.
+///
+/// A pointer is a sequence of reference tokens, each selecting an object
+/// member or an array element. It is written as `/` followed by each token,
+/// with `~` and `/` in tokens escaped as `~0` and `~1`. The empty pointer
+/// identifies the whole value.
+///
+/// ```
+/// use rialight_util::serialization::json::{json, Pointer};
+///
+/// let mut value = json!({ "player": { "items": ["sword"] } });
+/// let pointer = Pointer::parse("/player/items/0").unwrap();
+/// assert_eq!(pointer.get(&value), Some(&json!("sword")));
+///
+/// Pointer::parse("/player/items/-").unwrap().set(&mut value, json!("shield")).unwrap();
+/// assert_eq!(value, json!({ "player": { "items": ["sword", "shield"] } }));
+/// ```
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Default)]
+pub struct Pointer {
+    tokens: Vec<String>,
+}
+
+impl Pointer {
+    /// Returns the empty pointer, which identifies the whole value.
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    /// Parses a pointer from its string representation.
+    pub fn parse(pointer: &str) -> Result<Self, PointerError> {
+        if pointer.is_empty() {
+            return Ok(Self::root());
+        }
+        let Some(pointer) = pointer.strip_prefix('/') else {
+            return Err(PointerError::MissingSlash);
+        };
+        let mut tokens = vec![];
+        for token in pointer.split('/') {
+            let mut unescaped = String::with_capacity(token.len());
+            let mut chars = token.chars();
+            while let Some(ch) = chars.next() {
+                unescaped.push(match ch {
+                    '~' => match chars.next() {
+                        Some('0') => '~',
+                        Some('1') => '/',
+                        _ => return Err(PointerError::InvalidEscape),
+                    },
+                    ch => ch,
+                });
+            }
+            tokens.push(unescaped);
+        }
+        Ok(Self { tokens })
+    }
+
+    /// Returns the unescaped reference tokens of the pointer.
+    pub fn tokens(&self) -> &[String] {
+        &self.tokens
+    }
+
+    /// Indicates whether the pointer identifies the whole value.
+    pub fn is_root(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Appends a reference token to the pointer.
+    pub fn push(&mut self, token: impl ToString) {
+        self.tokens.push(token.to_string());
+    }
+
+    /// Returns a pointer with an additional reference token.
+    pub fn join(&self, token: impl ToString) -> Self {
+        let mut pointer = self.clone();
+        pointer.push(token);
+        pointer
+    }
+
+    /// Returns the pointer to the parent location, or `None`
+    /// for the empty pointer.
+    pub fn parent(&self) -> Option<Self> {
+        let (_, tokens) = self.tokens.split_last()?;
+        Some(Self { tokens: tokens.to_vec() })
+    }
+
+    /// Indicates whether `other` is this pointer or one of its ancestors.
+    pub fn starts_with(&self, other: &Pointer) -> bool {
+        self.tokens.starts_with(&other.tokens)
+    }
+
+    /// Returns the value at this location.
+    pub fn get<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        self.tokens.iter().try_fold(value, |value, token| match value {
+            Value::Object(map) => map.get(token),
+            Value::Array(array) => array.get(array_index(token)?),
+            _ => None,
+        })
+    }
+
+    /// Returns a mutable reference to the value at this location.
+    pub fn get_mut<'a>(&self, value: &'a mut Value) -> Option<&'a mut Value> {
+        self.tokens.iter().try_fold(value, |value, token| match value {
+            Value::Object(map) => map.get_mut(token),
+            Value::Array(array) => array.get_mut(array_index(token)?),
+            _ => None,
+        })
+    }
+
+    /// Sets the value at this location, returning the value it replaces.
+    ///
+    /// The parent location must exist. An object member is added if it
+    /// does not exist, and an array element is appended if the last
+    /// token is `-` or the length of the array.
+    pub fn set(&self, target: &mut Value, value: Value) -> Result<Option<Value>, PointerError> {
+        let Some((parent, token)) = self.parent_mut(target)? else {
+            return Ok(Some(std::mem::replace(target, value)));
+        };
+        match parent {
+            Value::Object(map) => Ok(map.insert(token.clone(), value)),
+            Value::Array(array) => {
+                let index = insertion_index(token, array.len())?;
+                if index == array.len() {
+                    array.push(value);
+                    Ok(None)
+                } else {
+                    Ok(Some(std::mem::replace(&mut array[index], value)))
+                }
+            },
+            _ => Err(PointerError::NotFound),
+        }
+    }
+
+    /// Removes the value at this location, returning it.
+    /// The empty pointer replaces the whole value by `null`.
+    pub fn remove(&self, target: &mut Value) -> Result<Value, PointerError> {
+        let Some((parent, token)) = self.parent_mut(target)? else {
+            return Ok(target.take());
+        };
+        match parent {
+            Value::Object(map) => map.remove(token).ok_or(PointerError::NotFound),
+            Value::Array(array) => {
+                let index = array_index(token).ok_or(PointerError::InvalidIndex)?;
+                if index < array.len() { Ok(array.remove(index)) } else { Err(PointerError::InvalidIndex) }
+            },
+            _ => Err(PointerError::NotFound),
+        }
+    }
+
+    /// Returns the parent value and the last token, or `None`
+    /// for the empty pointer.
+    pub(super) fn parent_mut<'a>(&'a self, target: &'a mut Value) -> Result<Option<(&'a mut Value, &'a String)>, PointerError> {
+        let Some((token, parent_tokens)) = self.tokens.split_last() else {
+            return Ok(None);
+        };
+        let mut parent = target;
+        for parent_token in parent_tokens {
+            parent = match parent {
+                Value::Object(map) => map.get_mut(parent_token),
+                Value::Array(array) => array_index(parent_token).and_then(|index| array.get_mut(index)),
+                _ => None,
+            }.ok_or(PointerError::NotFound)?;
+        }
+        Ok(Some((parent, token)))
+    }
+}
+
+/// Parses an array index, which has no leading zeros.
+fn array_index(token: &str) -> Option<usize> {
+    if token.is_empty() || (token.len() > 1 && token.starts_with('0')) || !token.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    token.parse().ok()
+}
+
+/// Parses the index at which an element is added to an array of the given length.
+pub(super) fn insertion_index(token: &str, length: usize) -> Result<usize, PointerError> {
+    let index = if token == "-" { length } else { array_index(token).ok_or(PointerError::InvalidIndex)? };
+    if index > length {
+        return Err(PointerError::InvalidIndex);
+    }
+    Ok(index)
+}
+
+impl Display for Pointer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for token in &self.tokens {
+            write!(f, "/{}", token.replace('~', "~0").replace('/', "~1"))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Pointer {
+    type Err = PointerError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Serialize for Pointer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Pointer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pointer = String::deserialize(deserializer)?;
+        Self::parse(&pointer).map_err(generic_deserialization::Error::custom)
+    }
+}
+
+/// Error returned by [`Pointer`] operations.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum PointerError {
+    /// A non-empty pointer does not start with `/`.
+    MissingSlash,
+    /// A `~` is not followed by `0` or `1`.
+    InvalidEscape,
+    /// A location does not exist.
+    NotFound,
+    /// A token is not a valid array index, or is out of bounds.
+    InvalidIndex,
+}
+
+impl Display for PointerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingSlash => write!(f, "JSON pointer must start with '/'"),
+            Self::InvalidEscape => write!(f, "'~' must be followed by '0' or '1' in JSON pointer"),
+            Self::NotFound => write!(f, "location does not exist"),
+            Self::InvalidIndex => write!(f, "invalid array index"),
+        }
+    }
+}
+
+impl std::error::Error for PointerError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::json;
+
+    #[test]
+    fn pointer() {
+        let value = json!({ "a/b": 1, "m~n": [10, 20], "": { "": 3 } });
+        assert_eq!(Pointer::parse("/a~1b").unwrap().get(&value), Some(&json!(1)));
+        assert_eq!(Pointer::parse("/m~0n/1").unwrap().get(&value), Some(&json!(20)));
+        assert_eq!(Pointer::parse("//").unwrap().get(&value), Some(&json!(3)));
+        assert_eq!(Pointer::parse("").unwrap().get(&value), Some(&value));
+        assert_eq!(Pointer::parse("/m~0n/01").unwrap().get(&value), None);
+        assert_eq!(Pointer::parse("/m~0n/1").unwrap().to_string(), "/m~0n/1");
+        assert_eq!(Pointer::parse("a"), Err(PointerError::MissingSlash));
+        assert_eq!(Pointer::parse("/~2"), Err(PointerError::InvalidEscape));
+
+        let mut value = value;
+        let pointer = Pointer::parse("/m~0n/2").unwrap();
+        assert_eq!(pointer.set(&mut value, json!(30)), Ok(None));
+        assert_eq!(pointer.set(&mut value, json!(31)), Ok(Some(json!(30))));
+        assert_eq!(Pointer::parse("/m~0n/4").unwrap().set(&mut value, json!(0)), Err(PointerError::InvalidIndex));
+        assert_eq!(Pointer::parse("/x/y").unwrap().set(&mut value, json!(0)), Err(PointerError::NotFound));
+        assert_eq!(Pointer::parse("/m~0n/0").unwrap().remove(&mut value), Ok(json!(10)));
+        assert_eq!(value["m~n"], json!([20, 31]));
+    }
+}
@@ -0,0 +1,616 @@
+use std::{cell::Cell, collections::HashMap, fmt::Display};
+use super::{Map, Pointer, Value};
+use crate::{reg_exp::RegExp, serialization::{Serialize, Serializer}};
+
+pub use schemars::JsonSchema;
+
+/// Limit of nested schema applications, guarding against
+/// references that refer to themselves.
+const MAXIMUM_DEPTH: usize = 256;
+
+/// Limit of schema applications per value in the validated value, guarding
+/// against combinations of references that branch exponentially.
+const STEPS_PER_VALUE: usize = 1024;
+
+/// A [JSON Schema](https://json-schema.org/draft/2020-12) describing
+/// the structure of JSON values.
+///
+/// A schema is either constructed from its JSON representation with
+/// [`Schema::new`], or generated from a type implementing [`JsonSchema`]
+/// with [`Schema::for_type`].
+///
+/// # Supported keywords
+///
+/// The following keywords of draft 2020-12 are validated:
+///
+/// - `type`, `enum`, `const`
+/// - `multipleOf`, `maximum`, `exclusiveMaximum`, `minimum`, `exclusiveMinimum`
+/// - `maxLength`, `minLength`, `pattern`
+/// - `prefixItems`, `items`, `contains`, `maxContains`, `minContains`,
+///   `maxItems`, `minItems`, `uniqueItems`
+/// - `properties`, `patternProperties`, `additionalProperties`, `propertyNames`,
+///   `required`, `dependentRequired`, `dependentSchemas`, `maxProperties`, `minProperties`
+/// - `allOf`, `anyOf`, `oneOf`, `not`, `if`, `then`, `else`
+/// - `$ref`, referring to a location within the same schema, such as `#/$defs/Item`
+///
+/// Other keywords, such as `format` and `description`, are ignored.
+/// Patterns use the syntax of the [`reg_exp`](crate::reg_exp) module.
+///
+/// # Validation
+///
+/// [`Schema::validate`] returns every violation, each with the location
+/// of the offending value as a [`Pointer`]. Validation gives up after a
+/// number of steps proportional to the size of the value, which bounds the
+/// time taken by schemas whose `anyOf` or `oneOf` branches nest deeply.
+///
+/// ```
+/// use rialight_util::serialization::json::{json, Schema};
+///
+/// let schema = Schema::new(json!({
+///     "type": "object",
+///     "properties": {
+///         "name": { "type": "string", "minLength": 3 },
+///         "health": { "type": "integer", "minimum": 0 }
+///     },
+///     "required": ["name", "health"]
+/// })).unwrap();
+///
+/// let errors = schema.validate(&json!({ "name": "Al", "health": -5 })).unwrap_err();
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors[0].path().to_string(), "/health");
+/// assert_eq!(errors[1].to_string(), "string is shorter than 3 characters at \"/name\"");
+/// ```
+///
+/// # Generating schemas
+///
+/// Deriving [`JsonSchema`] next to `Serialize` and `Deserialize` generates
+/// a schema following the same Serde attributes, which can be written to a
+/// file for modders and editors.
+///
+/// ```
+/// use rialight_util::serialization::{json::{self, json, JsonSchema, Schema}, Deserialize};
+///
+/// #[derive(Deserialize, JsonSchema)]
+/// struct Level {
+///     name: String,
+///     #[serde(default)]
+///     enemies: Vec<Enemy>,
+/// }
+///
+/// #[derive(Deserialize, JsonSchema)]
+/// #[serde(rename_all = "snake_case")]
+/// enum Enemy {
+///     Slime,
+///     Dragon,
+/// }
+///
+/// let schema = Schema::for_type::<Level>().unwrap();
+/// let _schema_file = json::serialize_pretty(&schema).unwrap();
+///
+/// let level = json!({ "name": "Cave", "enemies": ["slime", "goblin"] });
+/// let errors = schema.validate(&level).unwrap_err();
+/// assert_eq!(errors[0].path().to_string(), "/enemies/1");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Schema {
+    root: Value,
+    patterns: HashMap<String, RegExp>,
+}
+
+impl Schema {
+    /// Constructs a schema from its JSON representation.
+    pub fn new(schema: Value) -> Result<Self, SchemaError> {
+        let mut patterns = HashMap::new();
+        compile(&schema, &Pointer::root(), &schema, &mut patterns)?;
+        Ok(Self { root: schema, patterns })
+    }
+
+    /// Generates the schema of a type.
+    pub fn for_type<T>() -> Result<Self, SchemaError>
+        where T: ?Sized + JsonSchema
+    {
+        let generator = schemars::generate::SchemaSettings::draft2020_12().into_generator();
+        Self::new(generator.into_root_schema_for::<T>().to_value())
+    }
+
+    /// Returns the JSON representation of the schema.
+    pub fn as_value(&self) -> &Value {
+        &self.root
+    }
+
+    /// Validates a value, returning every violation if it is invalid.
+    pub fn validate(&self, value: &Value) -> Result<(), Vec<ValidationError>> {
+        let steps = Cell::new(0);
+        let budget = STEPS_PER_VALUE.saturating_mul(count_values(value));
+        let errors = self.violations(&self.root, &Pointer::root(), value, &Pointer::root(), Walk { depth: 0, steps: &steps, budget });
+        if steps.get() > budget {
+            return Err(vec![ValidationError { path: Pointer::root(), schema_path: Pointer::root(), message: "validation takes too many steps".into() }]);
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    /// Indicates whether a value is valid.
+    pub fn is_valid(&self, value: &Value) -> bool {
+        self.validate(value).is_ok()
+    }
+
+    fn violations(&self, schema: &Value, schema_path: &Pointer, value: &Value, path: &Pointer, walk: Walk<'_>) -> Vec<ValidationError> {
+        let mut errors = vec![];
+        self.validate_against(schema, schema_path, value, path, walk, &mut errors);
+        errors
+    }
+
+    fn validate_against(&self, schema: &Value, schema_path: &Pointer, value: &Value, path: &Pointer, walk: Walk<'_>, errors: &mut Vec<ValidationError>) {
+        let schema = match schema {
+            Value::Object(schema) => schema,
+            Value::Bool(false) => {
+                errors.push(ValidationError { path: path.clone(), schema_path: schema_path.clone(), message: "no value is allowed".into() });
+                return;
+            },
+            _ => return,
+        };
+        if walk.depth > MAXIMUM_DEPTH {
+            errors.push(violation(path, schema_path, "$ref", "schema nesting is too deep"));
+            return;
+        }
+        walk.steps.set(walk.steps.get() + 1);
+        if walk.steps.get() > walk.budget {
+            return;
+        }
+        let walk = Walk { depth: walk.depth + 1, ..walk };
+
+        if let Some(target) = schema.get("$ref").and_then(Value::as_str).and_then(|reference| resolve(&self.root, reference)) {
+            self.validate_against(target, &schema_path.join("$ref"), value, path, walk, errors);
+        }
+        let types: Vec<&str> = match schema.get("type") {
+            Some(Value::String(name)) => vec![name],
+            Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+        if !types.is_empty() && !types.iter().any(|name| has_type(value, name)) {
+            errors.push(violation(path, schema_path, "type", format_args!("expected {}, found {}", types.join(" or "), type_name(value))));
+        }
+        if let Some(Value::Array(values)) = schema.get("enum") {
+            if !values.iter().any(|allowed| equal(allowed, value)) {
+                errors.push(violation(path, schema_path, "enum", "value is not one of the allowed values"));
+            }
+        }
+        if let Some(constant) = schema.get("const") {
+            if !equal(constant, value) {
+                errors.push(violation(path, schema_path, "const", format_args!("value must be {constant}")));
+            }
+        }
+
+        match value {
+            Value::Number(number) => self.validate_number(schema, schema_path, number.as_f64().unwrap_or(f64::NAN), path, errors),
+            Value::String(string) => self.validate_string(schema, schema_path, string, path, errors),
+            Value::Array(items) => self.validate_array(schema, schema_path, items, path, walk, errors),
+            Value::Object(object) => self.validate_object(schema, schema_path, value, object, path, walk, errors),
+            _ => {},
+        }
+        self.validate_combinations(schema, schema_path, value, path, walk, errors);
+    }
+
+    fn validate_number(&self, schema: &Map<String, Value>, schema_path: &Pointer, number: f64, path: &Pointer, errors: &mut Vec<ValidationError>) {
+        let keyword = |name| schema.get(name).and_then(Value::as_f64);
+        if let Some(divisor) = keyword("multipleOf") {
+            let quotient = number / divisor;
+            if (quotient - quotient.round()).abs() > f64::EPSILON * quotient.abs().max(1.0) {
+                errors.push(violation(path, schema_path, "multipleOf", format_args!("{number} is not a multiple of {divisor}")));
+            }
+        }
+        if let Some(minimum) = keyword("minimum").filter(|minimum| number < *minimum) {
+            errors.push(violation(path, schema_path, "minimum", format_args!("{number} is less than the minimum of {minimum}")));
+        }
+        if let Some(minimum) = keyword("exclusiveMinimum").filter(|minimum| number <= *minimum) {
+            errors.push(violation(path, schema_path, "exclusiveMinimum", format_args!("{number} is not greater than {minimum}")));
+        }
+        if let Some(maximum) = keyword("maximum").filter(|maximum| number > *maximum) {
+            errors.push(violation(path, schema_path, "maximum", format_args!("{number} is greater than the maximum of {maximum}")));
+        }
+        if let Some(maximum) = keyword("exclusiveMaximum").filter(|maximum| number >= *maximum) {
+            errors.push(violation(path, schema_path, "exclusiveMaximum", format_args!("{number} is not less than {maximum}")));
+        }
+    }
+
+    fn validate_string(&self, schema: &Map<String, Value>, schema_path: &Pointer, string: &str, path: &Pointer, errors: &mut Vec<ValidationError>) {
+        let length = string.chars().count() as u64;
+        if let Some(minimum) = schema.get("minLength").and_then(Value::as_u64).filter(|minimum| length < *minimum) {
+            errors.push(violation(path, schema_path, "minLength", format_args!("string is shorter than {minimum} characters")));
+        }
+        if let Some(maximum) = schema.get("maxLength").and_then(Value::as_u64).filter(|maximum| length > *maximum) {
+            errors.push(violation(path, schema_path, "maxLength", format_args!("string is longer than {maximum} characters")));
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+            if !self.matches(pattern, string) {
+                errors.push(violation(path, schema_path, "pattern", format_args!("string does not match the pattern {pattern:?}")));
+            }
+        }
+    }
+
+    fn validate_array(&self, schema: &Map<String, Value>, schema_path: &Pointer, items: &[Value], path: &Pointer, walk: Walk<'_>, errors: &mut Vec<ValidationError>) {
+        let prefix = match schema.get("prefixItems") {
+            Some(Value::Array(prefix)) => prefix.as_slice(),
+            _ => &[],
+        };
+        for (index, (item, item_schema)) in items.iter().zip(prefix).enumerate() {
+            self.validate_against(item_schema, &schema_path.join("prefixItems").join(index), item, &path.join(index), walk, errors);
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (index, item) in items.iter().enumerate().skip(prefix.len()) {
+                self.validate_against(item_schema, &schema_path.join("items"), item, &path.join(index), walk, errors);
+            }
+        }
+        if let Some(contains) = schema.get("contains") {
+            let contains_path = schema_path.join("contains");
+            let count = items.iter().enumerate()
+                .filter(|(index, item)| self.violations(contains, &contains_path, item, &path.join(index), walk).is_empty())
+                .count() as u64;
+            let minimum = schema.get("minContains").and_then(Value::as_u64).unwrap_or(1);
+            if count < minimum {
+                errors.push(violation(path, schema_path, "contains", format_args!("array contains fewer than {minimum} matching items")));
+            }
+            if let Some(maximum) = schema.get("maxContains").and_then(Value::as_u64).filter(|maximum| count > *maximum) {
+                errors.push(violation(path, schema_path, "maxContains", format_args!("array contains more than {maximum} matching items")));
+            }
+        }
+        let length = items.len() as u64;
+        if let Some(minimum) = schema.get("minItems").and_then(Value::as_u64).filter(|minimum| length < *minimum) {
+            errors.push(violation(path, schema_path, "minItems", format_args!("array has fewer than {minimum} items")));
+        }
+        if let Some(maximum) = schema.get("maxItems").and_then(Value::as_u64).filter(|maximum| length > *maximum) {
+            errors.push(violation(path, schema_path, "maxItems", format_args!("array has more than {maximum} items")));
+        }
+        if schema.get("uniqueItems") == Some(&Value::Bool(true))
+            && items.iter().enumerate().any(|(index, item)| items[..index].iter().any(|other| equal(item, other)))
+        {
+            errors.push(violation(path, schema_path, "uniqueItems", "array items are not unique"));
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn validate_object(&self, schema: &Map<String, Value>, schema_path: &Pointer, value: &Value, object: &Map<String, Value>, path: &Pointer, walk: Walk<'_>, errors: &mut Vec<ValidationError>) {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let pattern_properties = schema.get("patternProperties").and_then(Value::as_object);
+        for (name, property) in object {
+            let property_path = path.join(name);
+            let mut evaluated = false;
+            if let Some(property_schema) = properties.and_then(|properties| properties.get(name)) {
+                evaluated = true;
+                self.validate_against(property_schema, &schema_path.join("properties").join(name), property, &property_path, walk, errors);
+            }
+            for (pattern, property_schema) in pattern_properties.into_iter().flatten() {
+                if self.matches(pattern, name) {
+                    evaluated = true;
+                    self.validate_against(property_schema, &schema_path.join("patternProperties").join(pattern), property, &property_path, walk, errors);
+                }
+            }
+            match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) if !evaluated => {
+                    errors.push(violation(path, schema_path, "additionalProperties", format_args!("property {name:?} is not allowed")));
+                },
+                Some(additional) if !evaluated => {
+                    self.validate_against(additional, &schema_path.join("additionalProperties"), property, &property_path, walk, errors);
+                },
+                _ => {},
+            }
+            if let Some(names) = schema.get("propertyNames") {
+                self.validate_against(names, &schema_path.join("propertyNames"), &Value::String(name.clone()), &property_path, walk, errors);
+            }
+        }
+        for name in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+            if !object.contains_key(name) {
+                errors.push(violation(path, schema_path, "required", format_args!("missing required property {name:?}")));
+            }
+        }
+        for (name, dependencies) in schema.get("dependentRequired").and_then(Value::as_object).into_iter().flatten() {
+            if !object.contains_key(name) {
+                continue;
+            }
+            for dependency in dependencies.as_array().into_iter().flatten().filter_map(Value::as_str) {
+                if !object.contains_key(dependency) {
+                    errors.push(violation(path, schema_path, "dependentRequired", format_args!("property {dependency:?} is required by property {name:?}")));
+                }
+            }
+        }
+        for (name, dependent_schema) in schema.get("dependentSchemas").and_then(Value::as_object).into_iter().flatten() {
+            if object.contains_key(name) {
+                self.validate_against(dependent_schema, &schema_path.join("dependentSchemas").join(name), value, path, walk, errors);
+            }
+        }
+        let length = object.len() as u64;
+        if let Some(minimum) = schema.get("minProperties").and_then(Value::as_u64).filter(|minimum| length < *minimum) {
+            errors.push(violation(path, schema_path, "minProperties", format_args!("object has fewer than {minimum} properties")));
+        }
+        if let Some(maximum) = schema.get("maxProperties").and_then(Value::as_u64).filter(|maximum| length > *maximum) {
+            errors.push(violation(path, schema_path, "maxProperties", format_args!("object has more than {maximum} properties")));
+        }
+    }
+
+    fn validate_combinations(&self, schema: &Map<String, Value>, schema_path: &Pointer, value: &Value, path: &Pointer, walk: Walk<'_>, errors: &mut Vec<ValidationError>) {
+        let matches = |keyword: &str, index: usize, subschema: &Value| self.violations(subschema, &schema_path.join(keyword).join(index), value, path, walk).is_empty();
+        if let Some(Value::Array(schemas)) = schema.get("allOf") {
+            for (index, subschema) in schemas.iter().enumerate() {
+                self.validate_against(subschema, &schema_path.join("allOf").join(index), value, path, walk, errors);
+            }
+        }
+        if let Some(Value::Array(schemas)) = schema.get("anyOf") {
+            if !schemas.iter().enumerate().any(|(index, subschema)| matches("anyOf", index, subschema)) {
+                errors.push(violation(path, schema_path, "anyOf", "value does not match any of the allowed schemas"));
+            }
+        }
+        if let Some(Value::Array(schemas)) = schema.get("oneOf") {
+            match schemas.iter().enumerate().filter(|&(index, subschema)| matches("oneOf", index, subschema)).count() {
+                1 => {},
+                0 => errors.push(violation(path, schema_path, "oneOf", "value does not match any of the allowed schemas")),
+                count => errors.push(violation(path, schema_path, "oneOf", format_args!("value matches {count} schemas instead of exactly one"))),
+            }
+        }
+        if let Some(not) = schema.get("not") {
+            if self.violations(not, &schema_path.join("not"), value, path, walk).is_empty() {
+                errors.push(violation(path, schema_path, "not", "value must not match the schema"));
+            }
+        }
+        if let Some(condition) = schema.get("if") {
+            let branch = if self.violations(condition, &schema_path.join("if"), value, path, walk).is_empty() { "then" } else { "else" };
+            if let Some(branch_schema) = schema.get(branch) {
+                self.validate_against(branch_schema, &schema_path.join(branch), value, path, walk, errors);
+            }
+        }
+    }
+
+    fn matches(&self, pattern: &str, string: &str) -> bool {
+        self.patterns.get(pattern).is_some_and(|pattern| pattern.is_match(string))
+    }
+}
+
+impl Serialize for Schema {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.root.serialize(serializer)
+    }
+}
+
+/// The state of a validation shared by nested schema applications.
+#[derive(Clone, Copy)]
+struct Walk<'a> {
+    depth: usize,
+    steps: &'a Cell<usize>,
+    budget: usize,
+}
+
+fn count_values(value: &Value) -> usize {
+    1 + match value {
+        Value::Array(items) => items.iter().map(count_values).sum(),
+        Value::Object(object) => object.values().map(count_values).sum(),
+        _ => 0,
+    }
+}
+
+fn violation(path: &Pointer, schema_path: &Pointer, keyword: &str, message: impl Display) -> ValidationError {
+    ValidationError { path: path.clone(), schema_path: schema_path.join(keyword), message: message.to_string() }
+}
+
+/// Checks the schema, compiling its patterns.
+fn compile(schema: &Value, path: &Pointer, root: &Value, patterns: &mut HashMap<String, RegExp>) -> Result<(), SchemaError> {
+    let error = |path: Pointer, message: String| SchemaError { path, message };
+    let schema = match schema {
+        Value::Object(schema) => schema,
+        Value::Bool(_) => return Ok(()),
+        _ => return Err(error(path.clone(), "schema must be an object or a boolean".into())),
+    };
+    for (keyword, value) in schema {
+        let keyword_path = path.join(keyword);
+        match (keyword.as_str(), value) {
+            ("properties" | "patternProperties" | "dependentSchemas" | "$defs" | "definitions", Value::Object(subschemas)) => {
+                for (name, subschema) in subschemas {
+                    if keyword == "patternProperties" {
+                        compile_pattern(name, keyword_path.join(name), patterns)?;
+                    }
+                    compile(subschema, &keyword_path.join(name), root, patterns)?;
+                }
+            },
+            ("prefixItems" | "allOf" | "anyOf" | "oneOf", Value::Array(subschemas)) => {
+                for (index, subschema) in subschemas.iter().enumerate() {
+                    compile(subschema, &keyword_path.join(index), root, patterns)?;
+                }
+            },
+            ("items" | "contains" | "additionalProperties" | "propertyNames" | "not" | "if" | "then" | "else", subschema) => {
+                compile(subschema, &keyword_path, root, patterns)?;
+            },
+            ("pattern", Value::String(pattern)) => compile_pattern(pattern, keyword_path, patterns)?,
+            ("$ref", Value::String(reference)) if resolve(root, reference).is_none() => {
+                return Err(error(keyword_path, format!("unresolved reference {reference:?}")));
+            },
+            _ => {},
+        }
+    }
+    Ok(())
+}
+
+fn compile_pattern(pattern: &str, path: Pointer, patterns: &mut HashMap<String, RegExp>) -> Result<(), SchemaError> {
+    if !patterns.contains_key(pattern) {
+        let compiled = RegExp::new(pattern).map_err(|e| SchemaError { path, message: format!("invalid pattern {pattern:?}: {e}") })?;
+        patterns.insert(pattern.to_owned(), compiled);
+    }
+    Ok(())
+}
+
+/// Resolves a reference to a location within the schema.
+fn resolve<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+    Pointer::parse(reference.strip_prefix('#')?).ok()?.get(root)
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|number| number.fract() == 0.0),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => false,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_i64() || number.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Compares values as JSON Schema does, where numbers are equal
+/// if they have the same mathematical value.
+fn equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a == b || a.as_f64() == b.as_f64(),
+        (Value::Array(a), Value::Array(b)) => a.len() == b.len() && a.iter().zip(b).all(|(a, b)| equal(a, b)),
+        (Value::Object(a), Value::Object(b)) => a.len() == b.len() && a.iter().all(|(key, a)| b.get(key).is_some_and(|b| equal(a, b))),
+        _ => a == b,
+    }
+}
+
+/// Error returned when constructing an invalid [`Schema`].
+#[derive(PartialEq, Clone, Debug)]
+pub struct SchemaError {
+    path: Pointer,
+    message: String,
+}
+
+impl SchemaError {
+    /// Returns the location of the error within the schema.
+    pub fn path(&self) -> &Pointer {
+        &self.path
+    }
+
+    /// Returns the description of the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at \"{}\"", self.message, self.path)
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// A violation of a [`Schema`].
+#[derive(PartialEq, Clone, Debug)]
+pub struct ValidationError {
+    path: Pointer,
+    schema_path: Pointer,
+    message: String,
+}
+
+impl ValidationError {
+    /// Returns the location of the invalid value.
+    pub fn path(&self) -> &Pointer {
+        &self.path
+    }
+
+    /// Returns the location of the violated keyword within the schema.
+    pub fn schema_path(&self) -> &Pointer {
+        &self.schema_path
+    }
+
+    /// Returns the description of the violation.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at \"{}\"", self.message, self.path)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::json;
+
+    #[test]
+    fn validation() {
+        let schema = Schema::new(json!({
+            "$defs": {
+                "position": { "type": "array", "prefixItems": [{ "type": "number" }, { "type": "number" }], "items": false }
+            },
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "pattern": "^[a-z_]+$" },
+                "spawn": { "$ref": "#/$defs/position" },
+                "tags": { "type": "array", "items": { "enum": ["boss", "flying"] }, "uniqueItems": true },
+                "speed": { "type": ["number", "null"], "exclusiveMinimum": 0, "multipleOf": 0.5 }
+            },
+            "patternProperties": { "^x-": true },
+            "additionalProperties": false,
+            "required": ["id"],
+            "oneOf": [{ "required": ["spawn"] }, { "required": ["tags"] }]
+        })).unwrap();
+
+        assert!(schema.is_valid(&json!({ "id": "bat", "spawn": [1, 2.5], "speed": 1.5, "x-note": 0 })));
+        let errors = schema.validate(&json!({
+            "id": "Bat",
+            "spawn": [1, "2", 3],
+            "tags": ["boss", "boss", "ghost"],
+            "speed": 0.7,
+            "size": 2
+        })).unwrap_err();
+        let errors: Vec<_> = errors.iter().map(|error| (error.path().to_string(), error.schema_path().to_string())).collect();
+        assert_eq!(errors, [
+            ("/id".into(), "/properties/id/pattern".into()),
+            ("".into(), "/additionalProperties".into()),
+            ("/spawn/1".into(), "/properties/spawn/$ref/prefixItems/1/type".into()),
+            ("/spawn/2".into(), "/properties/spawn/$ref/items".into()),
+            ("/speed".into(), "/properties/speed/multipleOf".into()),
+            ("/tags/2".into(), "/properties/tags/items/enum".into()),
+            ("/tags".into(), "/properties/tags/uniqueItems".into()),
+            ("".into(), "/oneOf".into()),
+        ]);
+
+        assert_eq!(Schema::new(json!({ "$ref": "#/$defs/missing" })).unwrap_err().to_string(), "unresolved reference \"#/$defs/missing\" at \"/$ref\"");
+        assert!(Schema::new(json!({ "properties": { "a": { "pattern": "(" } } })).is_err());
+    }
+
+    #[test]
+    fn branching_references() {
+        let mut definitions = Map::new();
+        for index in 0..40 {
+            let next = format!("#/$defs/{}", index + 1);
+            definitions.insert(index.to_string(), json!({ "anyOf": [{ "$ref": next }, { "$ref": next }] }));
+        }
+        definitions.insert("40".into(), json!({ "type": "string" }));
+        let schema = Schema::new(json!({ "$defs": definitions, "$ref": "#/$defs/0" })).unwrap();
+        assert!(schema.is_valid(&json!("text")));
+        let errors = schema.validate(&json!(1)).unwrap_err();
+        assert_eq!(errors[0].message(), "validation takes too many steps");
+    }
+
+    #[test]
+    fn generation() {
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Settings {
+            volume: u8,
+            language: Option<String>,
+        }
+
+        let schema = Schema::for_type::<Settings>().unwrap();
+        assert!(schema.is_valid(&json!({ "volume": 80, "language": null })));
+        let errors = schema.validate(&json!({ "volume": 300 })).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path().to_string(), "/volume");
+    }
+}
@@ -0,0 +1,615 @@
+use std::io::Read;
+use futures::io::{AsyncRead, AsyncReadExt};
+use super::{Map, Number, Value};
+use crate::{bytes::Buffer, serialization::{generic_deserialization::DeserializeOwned, FormatError}};
+
+type Result<T> = std::result::Result<T, FormatError>;
+
+/// Number of bytes read from the input at a time.
+const CHUNK_LENGTH: usize = 8 * 1024;
+
+/// An event read by a [`Tokenizer`] or an [`AsyncTokenizer`].
+#[derive(PartialEq, Clone, Debug)]
+pub enum Event {
+    StartObject,
+    EndObject,
+    StartArray,
+    EndArray,
+    /// The key of the next object member.
+    Key(String),
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+}
+
+/// A pull-based reader of JSON events.
+///
+/// A tokenizer reads a JSON document incrementally from an I/O stream,
+/// without building the whole value in memory. Events are pulled one at a
+/// time with [`Tokenizer::next_event`] or through the `Iterator` implementation,
+/// and [`Tokenizer::read_value`] reads a whole value at the current position,
+/// such as each element of a large array.
+///
+/// ```
+/// use rialight_util::serialization::{json::{Event, Tokenizer}, Deserialize};
+///
+/// #[derive(Deserialize)]
+/// struct Enemy {
+///     health: u32,
+/// }
+///
+/// let mut tokenizer = Tokenizer::new(r#"{ "enemies": [{ "health": 10 }, { "health": 500 }] }"#.as_bytes());
+/// assert_eq!(tokenizer.next_event().unwrap(), Some(Event::StartObject));
+/// assert_eq!(tokenizer.next_event().unwrap(), Some(Event::Key("enemies".into())));
+/// assert_eq!(tokenizer.next_event().unwrap(), Some(Event::StartArray));
+///
+/// let mut total_health = 0;
+/// while let Some(enemy) = tokenizer.deserialize_next::<Enemy>().unwrap() {
+///     total_health += enemy.health;
+/// }
+/// assert_eq!(total_health, 510);
+/// assert_eq!(tokenizer.next_event().unwrap(), Some(Event::EndObject));
+/// assert_eq!(tokenizer.next_event().unwrap(), None);
+/// ```
+///
+/// Errors give the line and column of the input where they occur.
+pub struct Tokenizer<R> {
+    reader: R,
+    scanner: Scanner,
+}
+
+impl<R: Read> Tokenizer<R> {
+    /// Constructs a tokenizer reading from an I/O stream.
+    pub fn new(reader: R) -> Self {
+        Self { reader, scanner: Scanner::new() }
+    }
+
+    /// Reads the next event, or returns `None` at the end of the document.
+    pub fn next_event(&mut self) -> Result<Option<Event>> {
+        loop {
+            match self.scanner.scan()? {
+                Scan::Event(event) => return Ok(Some(event)),
+                Scan::End => return Ok(None),
+                Scan::Incomplete => {
+                    let mut chunk = [0; CHUNK_LENGTH];
+                    let length = loop {
+                        match self.reader.read(&mut chunk) {
+                            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {},
+                            result => break result?,
+                        }
+                    };
+                    self.scanner.feed(&chunk[..length]);
+                },
+            }
+        }
+    }
+
+    /// Reads the value at the current position.
+    ///
+    /// Returns `None`, consuming the event, if the next event ends the
+    /// current array or object, or at the end of the document.
+    pub fn read_value(&mut self) -> Result<Option<Value>> {
+        let mut builder = ValueBuilder::default();
+        let Some(event) = self.next_event()? else {
+            return Ok(None);
+        };
+        if let Some(value) = builder.start(event).map_err(|message| self.scanner.error(message))? {
+            return Ok(value);
+        }
+        loop {
+            let event = self.next_event()?.ok_or_else(|| self.scanner.error("unexpected end of input"))?;
+            if let Some(value) = builder.push(event) {
+                return Ok(Some(value));
+            }
+        }
+    }
+
+    /// Reads the value at the current position as an instance of type `T`.
+    /// Returns `None` in the same cases as [`Tokenizer::read_value`].
+    pub fn deserialize_next<T>(&mut self) -> Result<Option<T>>
+        where T: DeserializeOwned
+    {
+        let Some(value) = self.read_value()? else {
+            return Ok(None);
+        };
+        serde_json::from_value(value).map(Some).map_err(|error| self.scanner.error(error))
+    }
+}
+
+impl<B: Buffer> Tokenizer<::bytes::buf::Reader<B>> {
+    /// Constructs a tokenizer reading from a buffer.
+    pub fn from_buffer(buffer: B) -> Self {
+        Self::new(buffer.reader())
+    }
+}
+
+impl<R: Read> Iterator for Tokenizer<R> {
+    type Item = Result<Event>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_event().transpose()
+    }
+}
+
+/// A pull-based reader of JSON events from an asynchronous I/O stream.
+///
+/// This is the asynchronous equivalent of [`Tokenizer`].
+///
+/// ```
+/// use rialight_util::{serialization::json::{AsyncTokenizer, Event}, timing::TestClock};
+///
+/// let mut tokenizer = AsyncTokenizer::new(&b"[1, 2]"[..]);
+/// TestClock::new().block_on(async move {
+///     assert_eq!(tokenizer.next_event().await.unwrap(), Some(Event::StartArray));
+///     assert_eq!(tokenizer.read_value().await.unwrap(), Some(1.into()));
+/// });
+/// ```
+pub struct AsyncTokenizer<R> {
+    reader: R,
+    scanner: Scanner,
+}
+
+impl<R: AsyncRead + Unpin> AsyncTokenizer<R> {
+    /// Constructs a tokenizer reading from an asynchronous I/O stream.
+    pub fn new(reader: R) -> Self {
+        Self { reader, scanner: Scanner::new() }
+    }
+
+    /// Reads the next event, or returns `None` at the end of the document.
+    pub async fn next_event(&mut self) -> Result<Option<Event>> {
+        loop {
+            match self.scanner.scan()? {
+                Scan::Event(event) => return Ok(Some(event)),
+                Scan::End => return Ok(None),
+                Scan::Incomplete => {
+                    let mut chunk = [0; CHUNK_LENGTH];
+                    let length = self.reader.read(&mut chunk).await?;
+                    self.scanner.feed(&chunk[..length]);
+                },
+            }
+        }
+    }
+
+    /// Reads the value at the current position.
+    ///
+    /// Returns `None`, consuming the event, if the next event ends the
+    /// current array or object, or at the end of the document.
+    pub async fn read_value(&mut self) -> Result<Option<Value>> {
+        let mut builder = ValueBuilder::default();
+        let Some(event) = self.next_event().await? else {
+            return Ok(None);
+        };
+        if let Some(value) = builder.start(event).map_err(|message| self.scanner.error(message))? {
+            return Ok(value);
+        }
+        loop {
+            let event = self.next_event().await?.ok_or_else(|| self.scanner.error("unexpected end of input"))?;
+            if let Some(value) = builder.push(event) {
+                return Ok(Some(value));
+            }
+        }
+    }
+
+    /// Reads the value at the current position as an instance of type `T`.
+    /// Returns `None` in the same cases as [`AsyncTokenizer::read_value`].
+    pub async fn deserialize_next<T>(&mut self) -> Result<Option<T>>
+        where T: DeserializeOwned
+    {
+        let Some(value) = self.read_value().await? else {
+            return Ok(None);
+        };
+        serde_json::from_value(value).map(Some).map_err(|error| self.scanner.error(error))
+    }
+}
+
+/// Builds a value from events.
+#[derive(Default)]
+struct ValueBuilder {
+    containers: Vec<(Value, Option<String>)>,
+}
+
+impl ValueBuilder {
+    /// Adds the first event. Returns the complete value, or `None`
+    /// as a value if the event ends a container.
+    fn start(&mut self, event: Event) -> std::result::Result<Option<Option<Value>>, &'static str> {
+        match event {
+            Event::EndObject | Event::EndArray => Ok(Some(None)),
+            Event::Key(_) => Err("expected value"),
+            event => Ok(self.push(event).map(Some)),
+        }
+    }
+
+    /// Adds an event, returning the value once it is complete.
+    fn push(&mut self, event: Event) -> Option<Value> {
+        let value = match event {
+            Event::StartObject => {
+                self.containers.push((Value::Object(Map::new()), None));
+                return None;
+            },
+            Event::StartArray => {
+                self.containers.push((Value::Array(vec![]), None));
+                return None;
+            },
+            Event::Key(key) => {
+                self.containers.last_mut()?.1 = Some(key);
+                return None;
+            },
+            Event::EndObject | Event::EndArray => self.containers.pop()?.0,
+            Event::Null => Value::Null,
+            Event::Bool(value) => Value::Bool(value),
+            Event::Number(value) => Value::Number(value),
+            Event::String(value) => Value::String(value),
+        };
+        match self.containers.last_mut() {
+            None => Some(value),
+            Some((Value::Object(map), key)) => {
+                map.insert(key.take().unwrap_or_default(), value);
+                None
+            },
+            Some((Value::Array(array), _)) => {
+                array.push(value);
+                None
+            },
+            Some(_) => None,
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum Container {
+    Object,
+    Array,
+}
+
+/// What the scanner expects to read next.
+#[derive(PartialEq, Clone, Copy)]
+enum Expect {
+    Value,
+    FirstItem,
+    FirstKey,
+    Key,
+    Separator,
+    End,
+}
+
+enum Scan {
+    Event(Event),
+    /// More input is needed to read the next event.
+    Incomplete,
+    End,
+}
+
+/// Reads events from input fed in chunks. When a token is incomplete,
+/// the scanner rewinds to its start, so that it is read again
+/// once more input is fed.
+struct Scanner {
+    buffer: Vec<u8>,
+    position: usize,
+    end_of_input: bool,
+    line: usize,
+    column: usize,
+    containers: Vec<Container>,
+    expect: Expect,
+}
+
+impl Scanner {
+    fn new() -> Self {
+        Self {
+            buffer: vec![],
+            position: 0,
+            end_of_input: false,
+            line: 1,
+            column: 1,
+            containers: vec![],
+            expect: Expect::Value,
+        }
+    }
+
+    /// Adds input. Empty input indicates the end of input.
+    fn feed(&mut self, bytes: &[u8]) {
+        if self.position * 2 >= self.buffer.len() {
+            self.buffer.drain(..self.position);
+            self.position = 0;
+        }
+        self.buffer.extend_from_slice(bytes);
+        self.end_of_input = bytes.is_empty();
+    }
+
+    fn error(&self, message: impl std::fmt::Display) -> FormatError {
+        FormatError::at(message, self.line, self.column)
+    }
+
+    fn incomplete<T>(&self) -> Result<Option<T>> {
+        if self.end_of_input { Err(self.error("unexpected end of input")) } else { Ok(None) }
+    }
+
+    fn remaining(&self) -> &[u8] {
+        &self.buffer[self.position..]
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.buffer.get(self.position).copied()
+    }
+
+    fn advance(&mut self) -> u8 {
+        let byte = self.buffer[self.position];
+        self.position += 1;
+        if byte == b'\n' {
+            self.line += 1;
+            self.column = 1;
+        } else if byte & 0xC0 != 0x80 {
+            self.column += 1;
+        }
+        byte
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.advance();
+        }
+    }
+
+    fn scan(&mut self) -> Result<Scan> {
+        loop {
+            self.skip_whitespace();
+            let start = (self.position, self.line, self.column);
+            match self.scan_token()? {
+                Some(Scan::Incomplete) => {
+                    (self.position, self.line, self.column) = start;
+                    return Ok(Scan::Incomplete);
+                },
+                Some(scan) => return Ok(scan),
+                None => {},
+            }
+        }
+    }
+
+    /// Scans a token, returning `None` after a separator.
+    fn scan_token(&mut self) -> Result<Option<Scan>> {
+        let Some(byte) = self.peek() else {
+            return match (self.end_of_input, self.expect) {
+                (false, _) => Ok(Some(Scan::Incomplete)),
+                (true, Expect::End) => Ok(Some(Scan::End)),
+                (true, _) => Err(self.error("unexpected end of input")),
+            };
+        };
+        let event = match (self.expect, byte) {
+            (Expect::End, _) => return Err(self.error("trailing characters after value")),
+            (Expect::FirstItem, b']') | (Expect::FirstKey, b'}') => {
+                self.advance();
+                Some(self.close())
+            },
+            (Expect::Value | Expect::FirstItem, _) => self.scan_value()?,
+            (Expect::FirstKey | Expect::Key, _) => self.scan_key()?,
+            (Expect::Separator, _) => {
+                let container = self.containers.last().copied();
+                match (byte, container) {
+                    (b',', Some(Container::Array)) => self.expect = Expect::Value,
+                    (b',', Some(Container::Object)) => self.expect = Expect::Key,
+                    (b']', Some(Container::Array)) | (b'}', Some(Container::Object)) => {
+                        self.advance();
+                        return Ok(Some(Scan::Event(self.close())));
+                    },
+                    (_, Some(Container::Object)) => return Err(self.error("expected ',' or '}'")),
+                    _ => return Err(self.error("expected ',' or ']'")),
+                }
+                self.advance();
+                return Ok(None);
+            },
+        };
+        Ok(Some(event.map_or(Scan::Incomplete, Scan::Event)))
+    }
+
+    fn close(&mut self) -> Event {
+        let container = self.containers.pop();
+        self.after_value();
+        if container == Some(Container::Object) { Event::EndObject } else { Event::EndArray }
+    }
+
+    fn after_value(&mut self) {
+        self.expect = if self.containers.is_empty() { Expect::End } else { Expect::Separator };
+    }
+
+    fn scan_key(&mut self) -> Result<Option<Event>> {
+        if self.peek() != Some(b'"') {
+            return Err(self.error("expected string key"));
+        }
+        let Some(key) = self.scan_string()? else {
+            return Ok(None);
+        };
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b':') => self.advance(),
+            Some(_) => return Err(self.error("expected ':'")),
+            None => return self.incomplete(),
+        };
+        self.expect = Expect::Value;
+        Ok(Some(Event::Key(key)))
+    }
+
+    fn scan_value(&mut self) -> Result<Option<Event>> {
+        let event = match self.peek() {
+            Some(b'{') => {
+                self.advance();
+                self.containers.push(Container::Object);
+                self.expect = Expect::FirstKey;
+                return Ok(Some(Event::StartObject));
+            },
+            Some(b'[') => {
+                self.advance();
+                self.containers.push(Container::Array);
+                self.expect = Expect::FirstItem;
+                return Ok(Some(Event::StartArray));
+            },
+            Some(b'"') => self.scan_string()?.map(Event::String),
+            Some(b't') => self.scan_literal(b"true")?.map(|_| Event::Bool(true)),
+            Some(b'f') => self.scan_literal(b"false")?.map(|_| Event::Bool(false)),
+            Some(b'n') => self.scan_literal(b"null")?.map(|_| Event::Null),
+            Some(b'-' | b'0'..=b'9') => self.scan_number()?.map(Event::Number),
+            _ => return Err(self.error("expected value")),
+        };
+        if event.is_some() {
+            self.after_value();
+        }
+        Ok(event)
+    }
+
+    fn scan_literal(&mut self, literal: &[u8]) -> Result<Option<()>> {
+        let length = self.remaining().len().min(literal.len());
+        if self.remaining()[..length] != literal[..length] {
+            return Err(self.error("expected value"));
+        }
+        if length < literal.len() {
+            return self.incomplete();
+        }
+        for _ in literal {
+            self.advance();
+        }
+        Ok(Some(()))
+    }
+
+    fn scan_number(&mut self) -> Result<Option<Number>> {
+        let remaining = self.remaining();
+        let length = remaining.iter()
+            .position(|byte| !matches!(byte, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E'))
+            .unwrap_or(remaining.len());
+        if length == remaining.len() && !self.end_of_input {
+            return Ok(None);
+        }
+        let number = std::str::from_utf8(&remaining[..length]).ok()
+            .and_then(|number| serde_json::from_str::<Number>(number).ok())
+            .ok_or_else(|| self.error("invalid number"))?;
+        for _ in 0..length {
+            self.advance();
+        }
+        Ok(Some(number))
+    }
+
+    fn scan_string(&mut self) -> Result<Option<String>> {
+        self.advance();
+        let mut bytes = vec![];
+        loop {
+            let Some(byte) = self.peek() else {
+                return self.incomplete();
+            };
+            match byte {
+                b'"' => {
+                    self.advance();
+                    break;
+                },
+                b'\\' => {
+                    if self.remaining().len() < 2 {
+                        return self.incomplete();
+                    }
+                    self.advance();
+                    match self.advance() {
+                        escape @ (b'"' | b'\\' | b'/') => bytes.push(escape),
+                        b'b' => bytes.push(0x08),
+                        b'f' => bytes.push(0x0C),
+                        b'n' => bytes.push(b'\n'),
+                        b'r' => bytes.push(b'\r'),
+                        b't' => bytes.push(b'\t'),
+                        b'u' => {
+                            let Some(ch) = self.scan_unicode_escape()? else {
+                                return Ok(None);
+                            };
+                            bytes.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes());
+                        },
+                        _ => return Err(self.error("invalid escape in string")),
+                    }
+                },
+                0..=0x1F => return Err(self.error("control character in string")),
+                _ => bytes.push(self.advance()),
+            }
+        }
+        String::from_utf8(bytes).map(Some).map_err(|_| self.error("invalid UTF-8 in string"))
+    }
+
+    /// Scans the digits of a `\u` escape, and of a following
+    /// escape for the second half of a surrogate pair.
+    fn scan_unicode_escape(&mut self) -> Result<Option<char>> {
+        let Some(unit) = self.scan_hex()? else {
+            return Ok(None);
+        };
+        let code_point = if (0xD800..0xDC00).contains(&unit) {
+            if self.remaining().len() < 6 {
+                return self.incomplete();
+            }
+            if !self.remaining().starts_with(b"\\u") {
+                return Err(self.error("unpaired surrogate in string"));
+            }
+            self.advance();
+            self.advance();
+            let Some(low) = self.scan_hex()?.filter(|low| (0xDC00..0xE000).contains(low)) else {
+                return Err(self.error("unpaired surrogate in string"));
+            };
+            0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            unit
+        };
+        char::from_u32(code_point).map(Some).ok_or_else(|| self.error("unpaired surrogate in string"))
+    }
+
+    fn scan_hex(&mut self) -> Result<Option<u32>> {
+        let Some(digits) = self.remaining().get(..4) else {
+            return self.incomplete();
+        };
+        if !digits.iter().all(u8::is_ascii_hexdigit) {
+            return Err(self.error("invalid unicode escape in string"));
+        }
+        let unit = digits.iter().fold(0, |unit, digit| unit * 16 + (*digit as char).to_digit(16).unwrap());
+        for _ in 0..4 {
+            self.advance();
+        }
+        Ok(Some(unit))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::serialization::json;
+
+    /// Reads input one byte at a time.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+            let Some((byte, rest)) = self.0.split_first() else {
+                return Ok(0);
+            };
+            buffer[0] = *byte;
+            self.0 = rest;
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn tokenizer() {
+        let input = r#" {"a": [true, null, -1.5e2, "x\"é😀"], "b": {}, "c": []} "#;
+        let events: Vec<Event> = Tokenizer::new(Trickle(input.as_bytes())).collect::<Result<_>>().unwrap();
+        assert_eq!(events, [
+            Event::StartObject,
+            Event::Key("a".into()),
+            Event::StartArray,
+            Event::Bool(true),
+            Event::Null,
+            Event::Number(json::deserialize("-150.0").unwrap()),
+            Event::String("x\"é😀".into()),
+            Event::EndArray,
+            Event::Key("b".into()),
+            Event::StartObject,
+            Event::EndObject,
+            Event::Key("c".into()),
+            Event::StartArray,
+            Event::EndArray,
+            Event::EndObject,
+        ]);
+        assert_eq!(Tokenizer::new(Trickle(input.as_bytes())).read_value().unwrap(), Some(json::deserialize(input).unwrap()));
+
+        let error = Tokenizer::from_buffer(&b"[1,\n  2 3]"[..]).collect::<Result<Vec<_>>>().unwrap_err();
+        assert_eq!(error.to_string(), "expected ',' or ']' (line 2, column 5)");
+        assert!(Tokenizer::new(&b"[1] 2"[..]).collect::<Result<Vec<_>>>().is_err());
+        assert!(Tokenizer::new(&b"{\"a\": tru"[..]).collect::<Result<Vec<_>>>().is_err());
+    }
+}
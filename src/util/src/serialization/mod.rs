@@ -67,11 +67,36 @@ fn main() {
     });
 }
 ```
+
+# Binary formats
+
+The [`msgpack`] and [`bincode`] modules provide the same functions as the
+[`json`] module for compact binary formats, serializing into [`Bytes`](crate::bytes::Bytes)
+and [`BytesMut`](crate::bytes::BytesMut) buffers:
+
+- [MessagePack](msgpack), which is self-describing and has an untyped representation.
+- A [bincode-based format](bincode), which is smaller but does not describe
+  its data, and supports versioning.
+
+# Text formats
+
+The [`toml`], [`yaml`] and [`ron`] modules provide the same functions as
+the [`json`] module for formats that are easier to write by hand, such as
+settings and game data files. Their errors are [`FormatError`]s, which
+give the line and column of the error in the text.
 */
 
 pub use serde::{Deserialize, Serialize, Deserializer, Serializer};
 
 pub mod json;
+pub mod msgpack;
+pub mod bincode;
+pub mod toml;
+pub mod yaml;
+pub mod ron;
+
+mod format_error;
+pub use format_error::FormatError;
 
 /// Work with generic deserialization.
 pub mod generic_deserialization {
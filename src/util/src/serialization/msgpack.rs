@@ -0,0 +1,172 @@
+/*!
+Working with MessagePack serialization.
+
+[MessagePack](https://msgpack.org) is a compact, self-describing binary
+format. By default, structures are serialized as arrays of their fields;
+[`serialize_named`] serializes them as maps keyed by field names instead,
+which tolerates fields being added or reordered between versions.
+
+```
+use rialight_util::serialization::{msgpack, Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Player {
+    name: String,
+    level: u32,
+}
+
+let player = Player { name: "Ada".into(), level: 7 };
+let bytes = msgpack::serialize(&player).unwrap();
+assert_eq!(msgpack::deserialize_from_slice::<Player>(&bytes).unwrap(), player);
+```
+
+# Untyped MessagePack
+
+Untyped MessagePack data is represented by the [`Value`] enumeration.
+*/
+
+use std::fmt::Display;
+use super::{generic_deserialization::DeserializeOwned, Deserialize, Serialize};
+use crate::bytes::{BufferMut, Bytes, BytesMut};
+
+pub use rmpv::{Integer, Utf8String, Value};
+
+/// Error returned by MessagePack serialization.
+#[derive(Debug)]
+pub enum Error {
+    /// A value could not be serialized.
+    Serialization(rmp_serde::encode::Error),
+    /// The data could not be deserialized.
+    Deserialization(rmp_serde::decode::Error),
+    /// An untyped value could not be converted.
+    Conversion(rmpv::ext::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Serialization(error) => write!(f, "{error}"),
+            Self::Deserialization(error) => write!(f, "{error}"),
+            Self::Conversion(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<rmp_serde::encode::Error> for Error {
+    fn from(error: rmp_serde::encode::Error) -> Self {
+        Self::Serialization(error)
+    }
+}
+
+impl From<rmp_serde::decode::Error> for Error {
+    fn from(error: rmp_serde::decode::Error) -> Self {
+        Self::Deserialization(error)
+    }
+}
+
+impl From<rmpv::ext::Error> for Error {
+    fn from(error: rmpv::ext::Error) -> Self {
+        Self::Conversion(error)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Deserializes MessagePack given as a sequence of bytes into a value.
+pub fn deserialize_from_slice<'a, T>(slice: &'a [u8]) -> Result<T>
+    where T: Deserialize<'a>
+{
+    Ok(rmp_serde::from_slice(slice)?)
+}
+
+/// Deserializes MessagePack from a reader into a value.
+pub fn deserialize_from_reader<R, T>(reader: R) -> Result<T>
+    where
+        R: std::io::Read,
+        T: DeserializeOwned
+{
+    Ok(rmp_serde::from_read(reader)?)
+}
+
+/// Interprets a `Value`, or untyped MessagePack data, as an instance of type `T`.
+pub fn untyped_to_typed<T>(value: Value) -> Result<T>
+    where T: DeserializeOwned
+{
+    Ok(rmpv::ext::from_value(value)?)
+}
+
+/// Converts `T` into untyped MessagePack data of type `Value`.
+pub fn typed_to_untyped<T>(value: T) -> Result<Value>
+    where T: Serialize
+{
+    Ok(rmpv::ext::to_value(value)?)
+}
+
+/// Serializes a value into MessagePack bytes.
+pub fn serialize<T>(value: &T) -> Result<Bytes>
+    where T: ?Sized + Serialize
+{
+    Ok(serialize_as_byte_vec(value)?.into())
+}
+
+/// Serializes a value into MessagePack bytes, with
+/// structures serialized as maps keyed by field names.
+pub fn serialize_named<T>(value: &T) -> Result<Bytes>
+    where T: ?Sized + Serialize
+{
+    Ok(rmp_serde::to_vec_named(value)?.into())
+}
+
+/// Serializes a value into MessagePack as a byte vector.
+pub fn serialize_as_byte_vec<T>(value: &T) -> Result<Vec<u8>>
+    where T: ?Sized + Serialize
+{
+    Ok(rmp_serde::to_vec(value)?)
+}
+
+/// Serializes a value into MessagePack using an I/O stream.
+pub fn serialize_with_writer<W, T>(mut writer: W, value: &T) -> Result<()>
+    where
+        W: std::io::Write,
+        T: ?Sized + Serialize
+{
+    Ok(rmp_serde::encode::write(&mut writer, value)?)
+}
+
+/// Serializes a value into MessagePack, appending it to a buffer.
+pub fn serialize_into_buffer<T>(buffer: &mut BytesMut, value: &T) -> Result<()>
+    where T: ?Sized + Serialize
+{
+    serialize_with_writer(buffer.writer(), value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Packet {
+        id: u16,
+        payload: Vec<u8>,
+    }
+
+    #[test]
+    fn msgpack() {
+        let packet = Packet { id: 7, payload: vec![1, 2] };
+        let mut buffer = BytesMut::new();
+        serialize_into_buffer(&mut buffer, &packet).unwrap();
+        assert_eq!(buffer.as_ref(), serialize(&packet).unwrap().as_ref());
+        assert_eq!(deserialize_from_reader::<_, Packet>(buffer.as_ref()).unwrap(), packet);
+
+        let named = serialize_named(&packet).unwrap();
+        let map: BTreeMap<String, Value> = deserialize_from_slice(&named).unwrap();
+        assert_eq!(map["id"], Value::from(7));
+
+        let value = typed_to_untyped(&packet).unwrap();
+        assert_eq!(untyped_to_typed::<Packet>(value).unwrap(), packet);
+        assert!(matches!(deserialize_from_slice::<Packet>(&[0xc1]), Err(Error::Deserialization(_))));
+    }
+}
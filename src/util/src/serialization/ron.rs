@@ -0,0 +1,143 @@
+/*!
+Working with RON serialization.
+
+[RON](https://github.com/ron-rs/ron), or Rusty Object Notation, resembles
+Rust syntax: structures, tuples and enumerations are written as in Rust.
+
+```
+use rialight_util::serialization::{ron, Deserialize};
+
+#[derive(Deserialize)]
+enum Shape {
+    Circle { radius: f32 },
+    Rectangle(f32, f32),
+}
+
+let shapes: Vec<Shape> = ron::deserialize("[Circle(radius: 1.0), Rectangle(2.0, 3.0)]").unwrap();
+assert_eq!(shapes.len(), 2);
+```
+
+# Untyped RON
+
+Untyped RON data is represented by the [`Value`] enumeration.
+*/
+
+use super::{generic_deserialization::DeserializeOwned, Deserialize, FormatError, Serialize};
+
+pub use ::ron::{Map, Number, Value};
+pub use ::ron::ser::PrettyConfig;
+
+pub type Result<T> = std::result::Result<T, FormatError>;
+
+fn deserialization_error(error: ::ron::error::SpannedError) -> FormatError {
+    FormatError::at(error.code, error.position.line, error.position.col)
+}
+
+/// Deserializes a RON string into a value.
+pub fn deserialize<'a, T>(string: &'a str) -> Result<T>
+    where T: Deserialize<'a>
+{
+    ::ron::from_str(string).map_err(deserialization_error)
+}
+
+/// Deserializes RON given as a sequence of bytes into a value.
+pub fn deserialize_from_slice<'a, T>(slice: &'a [u8]) -> Result<T>
+    where T: Deserialize<'a>
+{
+    ::ron::de::from_bytes(slice).map_err(deserialization_error)
+}
+
+/// Deserializes RON from a reader into a value.
+pub fn deserialize_from_reader<R, T>(reader: R) -> Result<T>
+    where
+        R: std::io::Read,
+        T: DeserializeOwned
+{
+    ::ron::de::from_reader(reader).map_err(deserialization_error)
+}
+
+/// Interprets a `Value`, or untyped RON data, as an instance of type `T`.
+pub fn untyped_to_typed<T>(value: Value) -> Result<T>
+    where T: DeserializeOwned
+{
+    value.into_rust().map_err(FormatError::new)
+}
+
+/// Converts `T` into untyped RON data of type `Value`.
+pub fn typed_to_untyped<T>(value: T) -> Result<Value>
+    where T: Serialize
+{
+    deserialize(&serialize(&value)?)
+}
+
+/// Serializes a value into a RON string.
+pub fn serialize<T>(value: &T) -> Result<String>
+    where T: ?Sized + Serialize
+{
+    ::ron::to_string(value).map_err(FormatError::new)
+}
+
+/// Serializes a value into a pretty-printed RON string.
+pub fn serialize_pretty<T>(value: &T) -> Result<String>
+    where T: ?Sized + Serialize
+{
+    ::ron::ser::to_string_pretty(value, PrettyConfig::default()).map_err(FormatError::new)
+}
+
+/// Serializes a value into RON as a byte vector.
+pub fn serialize_as_byte_vec<T>(value: &T) -> Result<Vec<u8>>
+    where T: ?Sized + Serialize
+{
+    Ok(serialize(value)?.into_bytes())
+}
+
+/// Serializes a value into pretty-printed RON as a byte vector.
+pub fn serialize_as_byte_vec_pretty<T>(value: &T) -> Result<Vec<u8>>
+    where T: ?Sized + Serialize
+{
+    Ok(serialize_pretty(value)?.into_bytes())
+}
+
+/// Serializes a value into RON using an I/O stream.
+pub fn serialize_with_writer<W, T>(writer: W, value: &T) -> Result<()>
+    where
+        W: std::io::Write,
+        T: ?Sized + Serialize
+{
+    ::ron::ser::to_writer(writer, value).map_err(FormatError::new)
+}
+
+/// Serializes a value into pretty-printed RON using an I/O stream.
+pub fn serialize_with_writer_pretty<W, T>(writer: W, value: &T) -> Result<()>
+    where
+        W: std::io::Write,
+        T: ?Sized + Serialize
+{
+    ::ron::ser::to_writer_pretty(writer, value, PrettyConfig::default()).map_err(FormatError::new)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Config {
+        name: String,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn ron() {
+        let config = Config { name: "game".into(), tags: vec!["a".into()] };
+        let text = serialize(&config).unwrap();
+        assert_eq!(text, "(name:\"game\",tags:[\"a\"])");
+        assert_eq!(deserialize_from_reader::<_, Config>(text.as_bytes()).unwrap(), config);
+        assert_eq!(deserialize::<Config>(&serialize_pretty(&config).unwrap()).unwrap(), config);
+
+        let value = typed_to_untyped(&config).unwrap();
+        assert_eq!(untyped_to_typed::<Config>(value).unwrap(), config);
+
+        let error = deserialize::<Config>("(\n  name: \"game\",\n  tags: [a],\n)").unwrap_err();
+        assert_eq!((error.line(), error.column()), (Some(3), Some(10)));
+    }
+}
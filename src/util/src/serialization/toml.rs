@@ -0,0 +1,153 @@
+/*!
+Working with TOML serialization.
+
+```
+use rialight_util::serialization::{toml, Deserialize};
+
+#[derive(Deserialize, Debug)]
+struct Settings {
+    volume: u8,
+    language: String,
+}
+
+let settings: Settings = toml::deserialize(r#"
+    volume = 80
+    language = "en-US"
+"#).unwrap();
+assert_eq!(settings.volume, 80);
+
+let error = toml::deserialize::<Settings>("volume = 80\nlanguage = ").unwrap_err();
+assert_eq!(error.line(), Some(2));
+```
+
+# Untyped TOML
+
+Untyped TOML data is represented by the [`Value`] enumeration,
+and TOML documents by the [`Table`] type.
+*/
+
+use super::{generic_deserialization::DeserializeOwned, FormatError, Serialize};
+
+pub use ::toml::{Table, Value};
+pub use ::toml::value::{Date, Datetime, Time};
+
+pub type Result<T> = std::result::Result<T, FormatError>;
+
+fn deserialization_error(error: ::toml::de::Error, text: &str) -> FormatError {
+    match error.span() {
+        Some(span) => FormatError::at_offset(error.message(), text, span.start),
+        None => FormatError::new(error.message()),
+    }
+}
+
+/// Deserializes a TOML string into a value.
+pub fn deserialize<T>(string: &str) -> Result<T>
+    where T: DeserializeOwned
+{
+    ::toml::from_str(string).map_err(|error| deserialization_error(error, string))
+}
+
+/// Deserializes TOML given as a sequence of bytes into a value.
+pub fn deserialize_from_slice<T>(slice: &[u8]) -> Result<T>
+    where T: DeserializeOwned
+{
+    deserialize(std::str::from_utf8(slice)?)
+}
+
+/// Deserializes TOML from a reader into a value.
+pub fn deserialize_from_reader<R, T>(mut reader: R) -> Result<T>
+    where
+        R: std::io::Read,
+        T: DeserializeOwned
+{
+    let mut string = String::new();
+    reader.read_to_string(&mut string)?;
+    deserialize(&string)
+}
+
+/// Interprets a `Value`, or untyped TOML data, as an instance of type `T`.
+pub fn untyped_to_typed<T>(value: Value) -> Result<T>
+    where T: DeserializeOwned
+{
+    value.try_into().map_err(|error: ::toml::de::Error| FormatError::new(error.message()))
+}
+
+/// Converts `T` into untyped TOML data of type `Value`.
+pub fn typed_to_untyped<T>(value: T) -> Result<Value>
+    where T: Serialize
+{
+    Value::try_from(value).map_err(FormatError::new)
+}
+
+/// Serializes a value into a TOML string.
+pub fn serialize<T>(value: &T) -> Result<String>
+    where T: ?Sized + Serialize
+{
+    ::toml::to_string(value).map_err(FormatError::new)
+}
+
+/// Serializes a value into a pretty-printed TOML string.
+pub fn serialize_pretty<T>(value: &T) -> Result<String>
+    where T: ?Sized + Serialize
+{
+    ::toml::to_string_pretty(value).map_err(FormatError::new)
+}
+
+/// Serializes a value into TOML as a byte vector.
+pub fn serialize_as_byte_vec<T>(value: &T) -> Result<Vec<u8>>
+    where T: ?Sized + Serialize
+{
+    Ok(serialize(value)?.into_bytes())
+}
+
+/// Serializes a value into pretty-printed TOML as a byte vector.
+pub fn serialize_as_byte_vec_pretty<T>(value: &T) -> Result<Vec<u8>>
+    where T: ?Sized + Serialize
+{
+    Ok(serialize_pretty(value)?.into_bytes())
+}
+
+/// Serializes a value into TOML using an I/O stream.
+pub fn serialize_with_writer<W, T>(mut writer: W, value: &T) -> Result<()>
+    where
+        W: std::io::Write,
+        T: ?Sized + Serialize
+{
+    Ok(writer.write_all(serialize(value)?.as_bytes())?)
+}
+
+/// Serializes a value into pretty-printed TOML using an I/O stream.
+pub fn serialize_with_writer_pretty<W, T>(mut writer: W, value: &T) -> Result<()>
+    where
+        W: std::io::Write,
+        T: ?Sized + Serialize
+{
+    Ok(writer.write_all(serialize_pretty(value)?.as_bytes())?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::serialization::Deserialize;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Config {
+        name: String,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn toml() {
+        let config = Config { name: "game".into(), tags: vec!["a".into()] };
+        let text = serialize(&config).unwrap();
+        assert_eq!(text, "name = \"game\"\ntags = [\"a\"]\n");
+        assert_eq!(deserialize_from_reader::<_, Config>(text.as_bytes()).unwrap(), config);
+
+        let value = typed_to_untyped(&config).unwrap();
+        assert_eq!(value["name"].as_str(), Some("game"));
+        assert_eq!(untyped_to_typed::<Config>(value).unwrap(), config);
+
+        let error = deserialize::<Config>("name = \"game\"\ntags = [1, ]x").unwrap_err();
+        assert_eq!((error.line(), error.column()), (Some(2), Some(13)));
+    }
+}
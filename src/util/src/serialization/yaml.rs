@@ -0,0 +1,132 @@
+/*!
+Working with YAML serialization.
+
+YAML is always serialized in the block style, which is readable
+without a separate pretty-printing variant.
+
+```
+use rialight_util::serialization::{yaml, Deserialize};
+
+#[derive(Deserialize)]
+struct Enemy {
+    name: String,
+    health: u32,
+}
+
+let enemies: Vec<Enemy> = yaml::deserialize("
+- name: Slime
+  health: 10
+- name: Dragon
+  health: 500
+").unwrap();
+assert_eq!(enemies[1].health, 500);
+```
+
+# Untyped YAML
+
+Untyped YAML data is represented by the [`Value`] enumeration.
+*/
+
+use super::{generic_deserialization::DeserializeOwned, Deserialize, FormatError, Serialize};
+
+pub use serde_norway::{Mapping, Number, Sequence, Value};
+
+pub type Result<T> = std::result::Result<T, FormatError>;
+
+fn format_error(error: serde_norway::Error) -> FormatError {
+    let message = error.to_string();
+    match error.location() {
+        Some(location) => {
+            let position = format!(" at line {} column {}", location.line(), location.column());
+            FormatError::at(message.replacen(&position, "", 1), location.line(), location.column())
+        },
+        None => FormatError::new(message),
+    }
+}
+
+/// Deserializes a YAML string into a value.
+pub fn deserialize<'a, T>(string: &'a str) -> Result<T>
+    where T: Deserialize<'a>
+{
+    serde_norway::from_str(string).map_err(format_error)
+}
+
+/// Deserializes YAML given as a sequence of bytes into a value.
+pub fn deserialize_from_slice<'a, T>(slice: &'a [u8]) -> Result<T>
+    where T: Deserialize<'a>
+{
+    serde_norway::from_slice(slice).map_err(format_error)
+}
+
+/// Deserializes YAML from a reader into a value.
+pub fn deserialize_from_reader<R, T>(reader: R) -> Result<T>
+    where
+        R: std::io::Read,
+        T: DeserializeOwned
+{
+    serde_norway::from_reader(reader).map_err(format_error)
+}
+
+/// Interprets a `Value`, or untyped YAML data, as an instance of type `T`.
+pub fn untyped_to_typed<T>(value: Value) -> Result<T>
+    where T: DeserializeOwned
+{
+    serde_norway::from_value(value).map_err(format_error)
+}
+
+/// Converts `T` into untyped YAML data of type `Value`.
+pub fn typed_to_untyped<T>(value: T) -> Result<Value>
+    where T: Serialize
+{
+    serde_norway::to_value(value).map_err(format_error)
+}
+
+/// Serializes a value into a YAML string.
+pub fn serialize<T>(value: &T) -> Result<String>
+    where T: ?Sized + Serialize
+{
+    serde_norway::to_string(value).map_err(format_error)
+}
+
+/// Serializes a value into YAML as a byte vector.
+pub fn serialize_as_byte_vec<T>(value: &T) -> Result<Vec<u8>>
+    where T: ?Sized + Serialize
+{
+    Ok(serialize(value)?.into_bytes())
+}
+
+/// Serializes a value into YAML using an I/O stream.
+pub fn serialize_with_writer<W, T>(writer: W, value: &T) -> Result<()>
+    where
+        W: std::io::Write,
+        T: ?Sized + Serialize
+{
+    serde_norway::to_writer(writer, value).map_err(format_error)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Config {
+        name: String,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn yaml() {
+        let config = Config { name: "game".into(), tags: vec!["a".into()] };
+        let text = serialize(&config).unwrap();
+        assert_eq!(text, "name: game\ntags:\n- a\n");
+        assert_eq!(deserialize_from_reader::<_, Config>(text.as_bytes()).unwrap(), config);
+
+        let value = typed_to_untyped(&config).unwrap();
+        assert_eq!(value["name"].as_str(), Some("game"));
+        assert_eq!(untyped_to_typed::<Config>(value).unwrap(), config);
+
+        let error = deserialize::<Config>("name: game\ntags: [a\n").unwrap_err();
+        assert_eq!(error.line(), Some(3));
+        assert_eq!(error.message(), "did not find expected ',' or ']', while parsing a flow sequence at line 2 column 7");
+    }
+}
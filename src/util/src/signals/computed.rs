@@ -0,0 +1,210 @@
+use std::{
+    fmt::Debug,
+    sync::{atomic::{AtomicU64, Ordering}, Arc, Mutex, Weak},
+};
+use crate::observable::Observable;
+use super::{
+    observe,
+    tracking::{next_id, resubscribe, run_tracked, track, Dependency, Source, State, Subscriber, Subscribers},
+};
+
+/// A memoized value derived from signals and other computed values.
+///
+/// The dependencies are tracked automatically: they are the signals and
+/// computed values read by the last computation. The value is computed
+/// lazily, when read after a dependency has changed, and dependents
+/// are only updated when it differs from the previous value.
+///
+/// Cloning a computed value returns a handle to the same value.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::signals::*;
+///
+/// let width = Signal::new(2);
+/// let height = Signal::new(3);
+/// let area = Computed::new({
+///     let (width, height) = (width.clone(), height.clone());
+///     move || width.get() * height.get()
+/// });
+/// assert_eq!(area.get(), 6);
+/// width.set(4);
+/// assert_eq!(area.get(), 12);
+/// ```
+pub struct Computed<T> {
+    inner: Arc<ComputedInner<T>>,
+}
+
+struct ComputedInner<T> {
+    id: u64,
+    this: Weak<ComputedInner<T>>,
+    compute: Box<dyn Fn() -> T + Send + Sync>,
+    state: Mutex<ComputedState<T>>,
+    version: AtomicU64,
+    subscribers: Subscribers,
+}
+
+struct ComputedState<T> {
+    value: Option<T>,
+    state: State,
+    dependencies: Vec<Dependency>,
+}
+
+impl<T: PartialEq + Send + Sync + 'static> ComputedInner<T> {
+    fn recompute(&self) {
+        let previous = {
+            let mut state = self.state.lock().unwrap();
+            // dependencies changing during the computation mark it again
+            state.state = State::Clean;
+            state.dependencies.clone()
+        };
+        let (value, dependencies, _) = run_tracked(false, &self.compute);
+        let mut state = self.state.lock().unwrap();
+        if state.value.as_ref() != Some(&value) {
+            state.value = Some(value);
+            self.version.fetch_add(1, Ordering::SeqCst);
+        }
+        state.dependencies = dependencies.clone();
+        drop(state);
+        resubscribe(self.id, self.this.clone(), &previous, &dependencies);
+    }
+}
+
+impl<T: PartialEq + Send + Sync + 'static> Source for ComputedInner<T> {
+    fn id(&self) -> u64 {
+        self.id
+    }
+
+    fn update(&self) -> u64 {
+        let (state, dependencies) = {
+            let state = self.state.lock().unwrap();
+            (state.state, state.dependencies.clone())
+        };
+        match state {
+            State::Clean => {},
+            State::Check => {
+                if dependencies.iter().any(Dependency::changed) {
+                    self.recompute();
+                } else {
+                    self.state.lock().unwrap().state = State::Clean;
+                }
+            },
+            State::Dirty => self.recompute(),
+        }
+        self.version.load(Ordering::SeqCst)
+    }
+
+    fn subscribers(&self) -> &Subscribers {
+        &self.subscribers
+    }
+}
+
+impl<T: PartialEq + Send + Sync + 'static> Subscriber for ComputedInner<T> {
+    fn notify(&self, state: State) {
+        let previous = {
+            let mut current = self.state.lock().unwrap();
+            let previous = current.state;
+            current.state = previous.max(state);
+            previous
+        };
+        if previous == State::Clean {
+            self.subscribers.notify(State::Check);
+        }
+    }
+}
+
+impl<T: PartialEq + Send + Sync + 'static> Computed<T> {
+    /// Creates a computed value.
+    pub fn new(compute: impl Fn() -> T + Send + Sync + 'static) -> Self {
+        Self {
+            inner: Arc::new_cyclic(|this| ComputedInner {
+                id: next_id(),
+                this: this.clone(),
+                compute: Box::new(compute),
+                state: Mutex::new(ComputedState { value: None, state: State::Dirty, dependencies: vec![] }),
+                version: AtomicU64::new(0),
+                subscribers: Subscribers::default(),
+            }),
+        }
+    }
+
+    /// Returns a clone of the value, computing it if needed.
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+
+    /// Calls a function with a reference to the value, computing it if
+    /// needed. The computed value must not be read from the function.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        let version = self.inner.update();
+        track(|| Arc::clone(&self.inner) as Arc<dyn Source>, version);
+        f(self.inner.state.lock().unwrap().value.as_ref().unwrap())
+    }
+
+    /// Returns an observable emitting the value on subscription
+    /// and whenever it changes.
+    pub fn to_observable(&self) -> Observable<T>
+    where
+        T: Clone,
+    {
+        let computed = self.clone();
+        observe(move || computed.get())
+    }
+}
+
+impl<T> Clone for Computed<T> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<T: Debug> Debug for Computed<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Computed").field(&self.inner.state.lock().unwrap().value).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::signals::Signal;
+
+    #[test]
+    fn memoization() {
+        let computations = Arc::new(AtomicU64::new(0));
+        let (flag, a, b) = (Signal::new(true), Signal::new(1), Signal::new(10));
+        let selected = Computed::new({
+            let (flag, a, b, computations) = (flag.clone(), a.clone(), b.clone(), Arc::clone(&computations));
+            move || {
+                computations.fetch_add(1, Ordering::SeqCst);
+                if flag.get() { a.get() } else { b.get() }
+            }
+        });
+        let parity = Computed::new({
+            let selected = selected.clone();
+            move || selected.get() % 2
+        });
+        assert_eq!(computations.load(Ordering::SeqCst), 0);
+        assert_eq!(parity.get(), 1);
+        assert_eq!(parity.get(), 1);
+        assert_eq!(computations.load(Ordering::SeqCst), 1);
+
+        // `b` is not a dependency until the flag changes
+        b.set(20);
+        assert_eq!(selected.get(), 1);
+        assert_eq!(computations.load(Ordering::SeqCst), 1);
+        flag.set(false);
+        assert_eq!(parity.get(), 0);
+        assert_eq!((a.subscriber_count(), b.subscriber_count()), (0, 1));
+
+        // unchanged values do not change the version of dependents
+        let version = parity.inner.update();
+        b.set(22);
+        assert_eq!(parity.get(), 0);
+        assert_eq!(parity.inner.update(), version);
+    }
+}
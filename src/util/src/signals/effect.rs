@@ -0,0 +1,245 @@
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex, Weak},
+};
+use super::tracking::{
+    batch, next_id, resubscribe, run_tracked, schedule, Cleanup, Dependency, State, Subscriber,
+};
+
+/// A function that runs immediately and then again whenever
+/// the signals and computed values it read change.
+///
+/// Effects run synchronously on the thread that changes their
+/// dependencies, after the outermost [`batch`](super::batch). The functions
+/// registered by the effect with [`on_cleanup`](super::on_cleanup) run before
+/// it runs again and when it is disposed.
+///
+/// The effect is disposed when the `Effect` is dropped.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::signals::*;
+///
+/// let name = Signal::new("world".to_owned());
+/// let effect = Effect::new({
+///     let name = name.clone();
+///     move || {
+///         println!("hello, {}", name.get());
+///         on_cleanup(|| println!("goodbye"));
+///     }
+/// });
+/// // prints "goodbye", then "hello, Rialight"
+/// name.set("Rialight".into());
+/// // prints "goodbye"
+/// drop(effect);
+/// ```
+pub struct Effect {
+    inner: Arc<EffectInner>,
+}
+
+struct EffectInner {
+    id: u64,
+    this: Weak<EffectInner>,
+    callback: Mutex<Box<dyn FnMut() + Send>>,
+    state: Mutex<EffectState>,
+}
+
+struct EffectState {
+    state: State,
+    dependencies: Vec<Dependency>,
+    cleanups: Vec<Cleanup>,
+    disposed: bool,
+}
+
+impl EffectInner {
+    fn run(&self) {
+        let (previous, cleanups) = {
+            let mut state = self.state.lock().unwrap();
+            if state.disposed {
+                return;
+            }
+            state.state = State::Clean;
+            (state.dependencies.clone(), std::mem::take(&mut state.cleanups))
+        };
+        for cleanup in cleanups {
+            cleanup();
+        }
+        let mut callback = self.callback.lock().unwrap();
+        let ((), dependencies, cleanups) = run_tracked(true, &mut *callback);
+        drop(callback);
+        let mut state = self.state.lock().unwrap();
+        if state.disposed {
+            drop(state);
+            for cleanup in cleanups {
+                cleanup();
+            }
+            resubscribe(self.id, self.this.clone(), &previous, &[]);
+            return;
+        }
+        state.dependencies = dependencies.clone();
+        state.cleanups = cleanups;
+        drop(state);
+        resubscribe(self.id, self.this.clone(), &previous, &dependencies);
+    }
+
+    fn run_if_needed(&self) {
+        let (state, dependencies) = {
+            let state = self.state.lock().unwrap();
+            (state.state, state.dependencies.clone())
+        };
+        match state {
+            State::Clean => {},
+            State::Check => {
+                if dependencies.iter().any(Dependency::changed) {
+                    self.run();
+                } else {
+                    self.state.lock().unwrap().state = State::Clean;
+                }
+            },
+            State::Dirty => self.run(),
+        }
+    }
+
+    fn dispose(&self) {
+        let (dependencies, cleanups) = {
+            let mut state = self.state.lock().unwrap();
+            if state.disposed {
+                return;
+            }
+            state.disposed = true;
+            (std::mem::take(&mut state.dependencies), std::mem::take(&mut state.cleanups))
+        };
+        resubscribe(self.id, self.this.clone(), &dependencies, &[]);
+        for cleanup in cleanups {
+            cleanup();
+        }
+    }
+}
+
+impl Subscriber for EffectInner {
+    fn notify(&self, state: State) {
+        let previous = {
+            let mut current = self.state.lock().unwrap();
+            if current.disposed {
+                return;
+            }
+            let previous = current.state;
+            current.state = previous.max(state);
+            previous
+        };
+        if previous == State::Clean {
+            let this = self.this.clone();
+            schedule(move || {
+                if let Some(this) = this.upgrade() {
+                    this.run_if_needed();
+                }
+            });
+        }
+    }
+}
+
+impl Effect {
+    /// Creates an effect, running it immediately.
+    pub fn new(callback: impl FnMut() + Send + 'static) -> Self {
+        let inner = Arc::new_cyclic(|this| EffectInner {
+            id: next_id(),
+            this: this.clone(),
+            callback: Mutex::new(Box::new(callback)),
+            state: Mutex::new(EffectState {
+                state: State::Dirty,
+                dependencies: vec![],
+                cleanups: vec![],
+                disposed: false,
+            }),
+        });
+        batch(|| inner.run());
+        Self { inner }
+    }
+
+    /// Disposes the effect, running its cleanups. The effect
+    /// no longer runs when its dependencies change.
+    pub fn dispose(&self) {
+        self.inner.dispose();
+    }
+
+    /// Indicates whether the effect has been disposed.
+    pub fn is_disposed(&self) -> bool {
+        self.inner.state.lock().unwrap().disposed
+    }
+}
+
+impl Drop for Effect {
+    fn drop(&mut self) {
+        self.inner.dispose();
+    }
+}
+
+impl Debug for Effect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Effect").field("disposed", &self.is_disposed()).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::signals::{batch, on_cleanup, untrack, Computed, Signal};
+
+    #[test]
+    fn effects() {
+        let log = Arc::new(Mutex::new(Vec::<String>::new()));
+        let (a, b) = (Signal::new(1), Signal::new(10));
+        let sum = Computed::new({
+            let (a, b) = (a.clone(), b.clone());
+            move || a.get() + b.get()
+        });
+        let effect = Effect::new({
+            let (log, sum, b) = (Arc::clone(&log), sum.clone(), b.clone());
+            move || {
+                let (sum, b) = (sum.get(), untrack(|| b.get()));
+                log.lock().unwrap().push(format!("run {sum} {b}"));
+                let log = Arc::clone(&log);
+                on_cleanup(move || log.lock().unwrap().push(format!("cleanup {sum}")));
+            }
+        });
+
+        a.set(2);
+        batch(|| {
+            a.set(3);
+            b.set(20);
+        });
+        // the sum does not change, so the effect does not run
+        batch(|| {
+            a.set(4);
+            b.set(19);
+        });
+        drop(effect);
+        a.set(5);
+
+        assert_eq!(*log.lock().unwrap(), [
+            "run 11 10",
+            "cleanup 11", "run 12 10",
+            "cleanup 12", "run 23 20",
+            "cleanup 23",
+        ]);
+        assert_eq!(a.subscriber_count(), 1);
+    }
+
+    #[test]
+    fn nested_updates() {
+        let (source, doubled) = (Signal::new(1), Signal::new(0));
+        let _double = Effect::new({
+            let (source, doubled) = (source.clone(), doubled.clone());
+            move || doubled.set(source.get() * 2)
+        });
+        let log = Arc::new(Mutex::new(vec![]));
+        let _log_effect = Effect::new({
+            let (doubled, log) = (doubled.clone(), Arc::clone(&log));
+            move || log.lock().unwrap().push(doubled.get())
+        });
+        source.set(2);
+        source.set(3);
+        assert_eq!(*log.lock().unwrap(), [2, 4, 6]);
+    }
+}
@@ -0,0 +1,74 @@
+/*!
+Work with fine-grained reactive signals.
+
+A [`Signal`] holds a value that changes over time. A [`Computed`] value
+is derived from signals and is memoized, and an [`Effect`] runs a function
+whenever the signals it read change. Dependencies are tracked automatically
+as signals and computed values are read, so only the computed values and
+effects that depend on a changed signal update.
+
+This module does not depend on a runtime: effects run synchronously,
+which makes reactive code deterministic and testable.
+
+# Example
+
+```
+use rialight_util::signals::*;
+
+let todos = Signal::new(vec!["write docs".to_owned()]);
+let count = Computed::new({
+    let todos = todos.clone();
+    move || todos.with(|todos| todos.len())
+});
+// prints "1 todos" when created
+let _render = Effect::new({
+    let count = count.clone();
+    move || println!("{} todos", count.get())
+});
+// prints "2 todos"
+todos.update(|todos| todos.push("write tests".into()));
+```
+
+# Batching and untracked reads
+
+[`batch`] defers effects until a group of changes is done, and
+[`untrack`] reads signals without depending on them.
+
+# Observables
+
+Signals and computed values convert to observables with their
+`to_observable` method, and [`Signal::from_observable`] creates
+a signal following an observable.
+*/
+
+use std::sync::Arc;
+use crate::observable::Observable;
+
+mod tracking;
+pub use tracking::{batch, untrack, on_cleanup};
+
+mod signal;
+pub use signal::Signal;
+
+mod computed;
+pub use computed::Computed;
+
+mod effect;
+pub use effect::Effect;
+
+/// Returns an observable emitting the result of `read` on subscription
+/// and whenever its dependencies change.
+fn observe<T>(read: impl Fn() -> T + Send + Sync + 'static) -> Observable<T>
+where
+    T: Send + Sync + 'static,
+{
+    let read = Arc::new(read);
+    Observable::new(move |observer| {
+        let read = Arc::clone(&read);
+        let effect = Effect::new(move || {
+            let value = read();
+            untrack(|| observer.next(value));
+        });
+        move || effect.dispose()
+    })
+}
@@ -0,0 +1,187 @@
+use std::{
+    fmt::Debug,
+    sync::{atomic::{AtomicU64, Ordering}, Arc, Mutex, RwLock},
+};
+use crate::observable::{Observable, Observer};
+use super::{
+    observe,
+    tracking::{batch, next_id, track, Source, State, Subscribers},
+};
+
+/// A reactive value. Reading a signal from a [`Computed`](super::Computed)
+/// or an [`Effect`](super::Effect) makes it a dependency of them, so that
+/// they update when the signal changes.
+///
+/// Cloning a signal returns a handle to the same value.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::signals::*;
+///
+/// let count = Signal::new(0);
+/// count.set(1);
+/// count.update(|count| *count += 1);
+/// assert_eq!(count.get(), 2);
+/// ```
+pub struct Signal<T> {
+    inner: Arc<SignalInner<T>>,
+}
+
+struct SignalInner<T> {
+    id: u64,
+    value: RwLock<T>,
+    version: AtomicU64,
+    subscribers: Subscribers,
+    /// Unsubscribes from the observable given to [`Signal::from_observable`].
+    unsubscribe: Mutex<Option<Box<dyn FnOnce() + Send>>>,
+}
+
+impl<T: Send + Sync + 'static> Source for SignalInner<T> {
+    fn id(&self) -> u64 {
+        self.id
+    }
+
+    fn update(&self) -> u64 {
+        self.version.load(Ordering::SeqCst)
+    }
+
+    fn subscribers(&self) -> &Subscribers {
+        &self.subscribers
+    }
+}
+
+impl<T> Drop for SignalInner<T> {
+    fn drop(&mut self) {
+        if let Some(unsubscribe) = self.unsubscribe.get_mut().unwrap().take() {
+            unsubscribe();
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> Signal<T> {
+    /// Creates a signal.
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: Arc::new(SignalInner {
+                id: next_id(),
+                value: RwLock::new(value),
+                version: AtomicU64::new(0),
+                subscribers: Subscribers::default(),
+                unsubscribe: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Returns a clone of the value.
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+
+    /// Calls a function with a reference to the value. The signal
+    /// must not be changed from the function.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        track(|| Arc::clone(&self.inner) as Arc<dyn Source>, self.inner.version.load(Ordering::SeqCst));
+        f(&self.inner.value.read().unwrap())
+    }
+
+    /// Sets the value, running the effects that depend on the signal.
+    pub fn set(&self, value: T) {
+        self.update(|current| *current = value);
+    }
+
+    /// Mutates the value, running the effects that depend on the signal.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.inner.value.write().unwrap());
+        self.inner.version.fetch_add(1, Ordering::SeqCst);
+        batch(|| self.inner.subscribers.notify(State::Dirty));
+    }
+
+    /// Returns an observable emitting the value on subscription
+    /// and whenever it changes.
+    pub fn to_observable(&self) -> Observable<T>
+    where
+        T: Clone,
+    {
+        let signal = self.clone();
+        observe(move || signal.get())
+    }
+
+    /// Creates a signal set to each value emitted by an observable.
+    /// The signal unsubscribes from the observable when dropped.
+    pub fn from_observable<Error>(observable: &Observable<T, Error>, initial: T) -> Self
+    where
+        Error: Send + Sync + 'static,
+    {
+        let signal = Self::new(initial);
+        let inner = Arc::downgrade(&signal.inner);
+        let subscription = observable.subscribe(Observer {
+            next: Box::new(move |value| {
+                if let Some(inner) = inner.upgrade() {
+                    Signal { inner }.set(value);
+                }
+            }),
+            ..Default::default()
+        });
+        *signal.inner.unsubscribe.lock().unwrap() = Some(Box::new(move || subscription.unsubscribe()));
+        signal
+    }
+
+    /// Returns the number of computed values and effects
+    /// depending on the signal.
+    pub fn subscriber_count(&self) -> usize {
+        self.inner.subscribers.len()
+    }
+}
+
+impl<T> Clone for Signal<T> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<T: Default + Send + Sync + 'static> Default for Signal<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Debug> Debug for Signal<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Signal").field(&*self.inner.value.read().unwrap()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::observable::Subject;
+
+    #[test]
+    fn observable_bridges() {
+        let subject = Subject::<u32>::new();
+        let signal = Signal::from_observable(&subject.observable(), 0);
+        subject.next(5);
+        assert_eq!(signal.get(), 5);
+        drop(signal);
+        assert_eq!(subject.observer_count(), 0);
+
+        let signal = Signal::new(1);
+        let emitted = Arc::new(Mutex::new(vec![]));
+        let subscription = signal.to_observable().subscribe(Observer {
+            next: Box::new({
+                let emitted = Arc::clone(&emitted);
+                move |value| emitted.lock().unwrap().push(value)
+            }),
+            ..Default::default()
+        });
+        signal.set(2);
+        subscription.unsubscribe();
+        signal.set(3);
+        assert_eq!(*emitted.lock().unwrap(), [1, 2]);
+        assert_eq!(signal.subscriber_count(), 0);
+    }
+}
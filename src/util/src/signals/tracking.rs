@@ -0,0 +1,256 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    sync::{atomic::{AtomicU64, Ordering}, Arc, Mutex, Weak},
+};
+
+/// Returns a unique identifier for a reactive node.
+pub(super) fn next_id() -> u64 {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Whether a computed value or effect is up to date. `Check` means that
+/// a transitive dependency has changed, so that the direct dependencies
+/// must be updated to find out whether they have changed.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub(super) enum State {
+    Clean,
+    Check,
+    Dirty,
+}
+
+/// A node that can be depended on.
+pub(super) trait Source: Send + Sync {
+    fn id(&self) -> u64;
+    /// Brings the value of the node up to date and returns its version,
+    /// which is incremented whenever the value changes.
+    fn update(&self) -> u64;
+    fn subscribers(&self) -> &Subscribers;
+}
+
+/// A node that depends on sources.
+pub(super) trait Subscriber: Send + Sync {
+    fn notify(&self, state: State);
+}
+
+#[derive(Default)]
+pub(super) struct Subscribers {
+    list: Mutex<Vec<(u64, Weak<dyn Subscriber>)>>,
+}
+
+impl Subscribers {
+    pub fn add(&self, id: u64, subscriber: Weak<dyn Subscriber>) {
+        let mut list = self.list.lock().unwrap();
+        if !list.iter().any(|(subscriber_id, _)| *subscriber_id == id) {
+            list.push((id, subscriber));
+        }
+    }
+
+    pub fn remove(&self, id: u64) {
+        self.list.lock().unwrap().retain(|(subscriber_id, _)| *subscriber_id != id);
+    }
+
+    pub fn notify(&self, state: State) {
+        let subscribers = {
+            let mut list = self.list.lock().unwrap();
+            list.retain(|(_, subscriber)| subscriber.strong_count() != 0);
+            list.iter().map(|(_, subscriber)| subscriber.clone()).collect::<Vec<_>>()
+        };
+        for subscriber in subscribers.iter().filter_map(Weak::upgrade) {
+            subscriber.notify(state);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.lock().unwrap().len()
+    }
+}
+
+/// A source read by a subscriber, with the version that was read.
+#[derive(Clone)]
+pub(super) struct Dependency {
+    pub source: Arc<dyn Source>,
+    pub version: u64,
+}
+
+impl Dependency {
+    pub fn changed(&self) -> bool {
+        self.source.update() != self.version
+    }
+}
+
+/// Replaces the subscriptions of a subscriber to the `previous`
+/// dependencies by subscriptions to the `current` dependencies.
+pub(super) fn resubscribe(id: u64, subscriber: Weak<dyn Subscriber>, previous: &[Dependency], current: &[Dependency]) {
+    for dependency in previous {
+        if !current.iter().any(|current| current.source.id() == dependency.source.id()) {
+            dependency.source.subscribers().remove(id);
+        }
+    }
+    for dependency in current {
+        dependency.source.subscribers().add(id, subscriber.clone());
+    }
+}
+
+pub(super) type Cleanup = Box<dyn FnOnce() + Send>;
+
+/// A scope of the tracking stack. Computed values and effects track
+/// dependencies; effects also collect cleanups. Untracked scopes do neither.
+struct Frame {
+    dependencies: Option<Vec<Dependency>>,
+    cleanups: Option<Vec<Cleanup>>,
+}
+
+#[derive(Default)]
+struct Batch {
+    depth: usize,
+    pending: VecDeque<Box<dyn FnOnce()>>,
+}
+
+thread_local! {
+    static FRAMES: RefCell<Vec<Frame>> = const { RefCell::new(vec![]) };
+    static BATCH: RefCell<Batch> = RefCell::new(Batch::default());
+}
+
+/// Pops the top frame of the tracking stack when dropped,
+/// including when unwinding.
+struct FrameGuard;
+
+impl FrameGuard {
+    fn push(frame: Frame) -> Self {
+        FRAMES.with(|frames| frames.borrow_mut().push(frame));
+        Self
+    }
+
+    fn pop(self) -> Frame {
+        let frame = FRAMES.with(|frames| frames.borrow_mut().pop().unwrap());
+        std::mem::forget(self);
+        frame
+    }
+}
+
+impl Drop for FrameGuard {
+    fn drop(&mut self) {
+        FRAMES.with(|frames| frames.borrow_mut().pop());
+    }
+}
+
+/// Records a dependency of the innermost tracking scope, if any.
+pub(super) fn track(source: impl FnOnce() -> Arc<dyn Source>, version: u64) {
+    FRAMES.with(|frames| {
+        if let Some(Frame { dependencies: Some(dependencies), .. }) = frames.borrow_mut().last_mut() {
+            let source = source();
+            if !dependencies.iter().any(|dependency| dependency.source.id() == source.id()) {
+                dependencies.push(Dependency { source, version });
+            }
+        }
+    });
+}
+
+/// Calls `f`, returning the dependencies it read and, if `with_cleanups`
+/// is given, the cleanups it registered with [`on_cleanup`].
+pub(super) fn run_tracked<R>(with_cleanups: bool, f: impl FnOnce() -> R) -> (R, Vec<Dependency>, Vec<Cleanup>) {
+    let guard = FrameGuard::push(Frame {
+        dependencies: Some(vec![]),
+        cleanups: with_cleanups.then(Vec::new),
+    });
+    let result = f();
+    let frame = guard.pop();
+    (result, frame.dependencies.unwrap_or_default(), frame.cleanups.unwrap_or_default())
+}
+
+/// Calls a function without tracking the signals it reads
+/// as dependencies of the current computed value or effect.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::signals::*;
+///
+/// let (a, b) = (Signal::new(1), Signal::new(2));
+/// // re-runs only when `a` changes
+/// let _effect = Effect::new(move || {
+///     println!("{}", a.get() + untrack(|| b.get()));
+/// });
+/// ```
+pub fn untrack<R>(f: impl FnOnce() -> R) -> R {
+    let guard = FrameGuard::push(Frame { dependencies: None, cleanups: None });
+    let result = f();
+    guard.pop();
+    result
+}
+
+/// Registers a function to run before the current effect runs
+/// again and when it is disposed. Does nothing outside of an effect.
+pub fn on_cleanup(cleanup: impl FnOnce() + Send + 'static) {
+    FRAMES.with(|frames| {
+        let mut frames = frames.borrow_mut();
+        if let Some(cleanups) = frames.iter_mut().rev().find_map(|frame| frame.cleanups.as_mut()) {
+            cleanups.push(Box::new(cleanup));
+        }
+    });
+}
+
+/// Calls a function, deferring the effects of the signals it
+/// changes until it returns, so that each effect runs at most once.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::signals::*;
+///
+/// let (first, last) = (Signal::new("Ada".to_owned()), Signal::new("Lovelace".to_owned()));
+/// let _effect = Effect::new({
+///     let (first, last) = (first.clone(), last.clone());
+///     move || println!("{} {}", first.get(), last.get())
+/// });
+/// // prints "Grace Hopper" once
+/// batch(|| {
+///     first.set("Grace".into());
+///     last.set("Hopper".into());
+/// });
+/// ```
+pub fn batch<R>(f: impl FnOnce() -> R) -> R {
+    BATCH.with(|batch| batch.borrow_mut().depth += 1);
+    let guard = BatchGuard;
+    let result = f();
+    drop(guard);
+    result
+}
+
+/// Leaves a batch when dropped, running the pending
+/// effects when leaving the outermost batch.
+struct BatchGuard;
+
+impl Drop for BatchGuard {
+    fn drop(&mut self) {
+        // leaves the batch even if an effect panics
+        struct Leave;
+        impl Drop for Leave {
+            fn drop(&mut self) {
+                BATCH.with(|batch| {
+                    let mut batch = batch.borrow_mut();
+                    batch.depth -= 1;
+                    if batch.depth == 0 {
+                        batch.pending.clear();
+                    }
+                });
+            }
+        }
+        let _leave = Leave;
+        let outermost = BATCH.with(|batch| batch.borrow().depth == 1);
+        if outermost && !std::thread::panicking() {
+            // the depth stays at one while running the effects, so that
+            // the effects they schedule run in this loop
+            while let Some(job) = BATCH.with(|batch| batch.borrow_mut().pending.pop_front()) {
+                job();
+            }
+        }
+    }
+}
+
+/// Schedules a job to run when leaving the outermost batch.
+pub(super) fn schedule(job: impl FnOnce() + 'static) {
+    BATCH.with(|batch| batch.borrow_mut().pending.push_back(Box::new(job)));
+}
@@ -1,5 +1,11 @@
 /*!
 Utilities for strings, including formatting incognitos.
+
+The [`unicode`] module provides Unicode text segmentation, display width,
+normalization and case folding.
+
+[`Template`] is a parsed, reusable template string with format specifications,
+and the [`template!`] macro checks its parameter names at compile time.
  */
 
 #[allow(unused_imports)]
@@ -12,6 +18,13 @@ use super::{
 mod code_points_reader;
 pub use code_points_reader::CodePointsReader;
 
+pub mod unicode;
+
+mod template;
+pub use template::{Template, TemplateError, template};
+#[doc(hidden)]
+pub use template::check_template_arguments;
+
 /// The `StringIncognitoFormat` trait allows formatting string parameters
 /// of arbitrary name that is computed at runtime.
 ///
@@ -27,14 +40,14 @@ pub use code_points_reader::CodePointsReader;
 /// Syntax description:
 ///
 /// - Whitespace is allowed around the parameter name or escaped form, such as
-/// `{ "foo" }` versus `{"foo"}`.
+///   `{ "foo" }` versus `{"foo"}`.
 /// - `{param_name}` expands to either an argument given in the map (whose key string is `param_name`) or
-/// the string `None` if not present. The parameter name may contain any of the following characters:
+///   the string `None` if not present. The parameter name may contain any of the following characters:
 /// ```plain
 /// A-Z a-z 0-9 . - _ $
 /// ```
 /// - `{"escaped"}` expands to the string `escaped`. It is often
-/// used for escaping the curly brackets.
+///   used for escaping the curly brackets.
 ///
 /// # Example
 /// 
@@ -0,0 +1,438 @@
+use std::{fmt::Display, str::FromStr};
+use crate::{
+    number::{CommaSeparated, NonNegBigInt},
+    serialization::{json::{self, Number, Value}, Serialize},
+};
+
+/// A parsed template string, which substitutes parameters with
+/// arguments when rendered.
+///
+/// The syntax extends that of [`StringIncognitoFormat`](super::StringIncognitoFormat):
+///
+/// ```plain
+/// {param_name}            # parameter to replace
+/// {param_name.field.0}    # field or array element of a parameter
+/// {param_name:spec}       # parameter with a format specification
+/// {"escaped"}             # escaped sequence
+/// ```
+///
+/// Parameter names may contain the characters `A-Z a-z 0-9 - _ $`.
+/// Unlike with `StringIncognitoFormat`, an unescaped `}` is an error.
+///
+/// # Format specifications
+///
+/// A format specification has the form `[[fill]align][width][,][.precision]`:
+///
+/// - `align` is `<`, `^` or `>`, for left, center or right alignment, padding
+///   with `fill`, which defaults to a space. Numbers are right-aligned
+///   and other values left-aligned by default.
+/// - `width` is the minimum number of characters.
+/// - `,` separates the thousands of a number with commas.
+/// - `precision` is the number of decimal places of a number, or the
+///   maximum number of characters of a string.
+///
+/// # Arguments
+///
+/// Arguments are given as a serializable value whose fields are the parameters,
+/// such as a structure or a JSON object. Rendering fails if a parameter
+/// is not given or if an argument is not used.
+///
+/// Strings are rendered as they are and other values as JSON.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::{serialization::json::json, string::Template};
+///
+/// let template = Template::parse("{player.name:>8}: {score:,.1} points").unwrap();
+/// let text = template.render(&json!({ "player": { "name": "Ana" }, "score": 12345.67 })).unwrap();
+/// assert_eq!(text, "     Ana: 12,345.7 points");
+///
+/// assert!(template.render(&json!({ "player": { "name": "Ana" } })).is_err());
+/// ```
+///
+/// The [`template!`](super::template) macro checks parameter names at compile time.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+enum Part {
+    Text(String),
+    Parameter { path: Vec<String>, spec: FormatSpec },
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+struct FormatSpec {
+    fill: char,
+    align: Option<Align>,
+    width: Option<usize>,
+    grouping: bool,
+    precision: Option<usize>,
+}
+
+impl Template {
+    /// Parses a template string.
+    pub fn parse(source: &str) -> Result<Self, TemplateError> {
+        let mut parts = vec![];
+        let mut text = String::new();
+        let mut start = 0;
+        while let Some(index) = source[start..].find(['{', '}']) {
+            let offset = start + index;
+            text.push_str(&source[start..offset]);
+            if source.as_bytes()[offset] == b'}' {
+                return Err(TemplateError::Syntax { offset, message: "unmatched '}'" });
+            }
+            let end = closing_bracket(source, offset)?;
+            let inner = source[offset + 1..end].trim();
+            if let Some(escaped) = inner.strip_prefix('"') {
+                text.push_str(escaped.strip_suffix('"').ok_or(TemplateError::Syntax { offset, message: "unterminated escaped sequence" })?);
+            } else {
+                if !text.is_empty() {
+                    parts.push(Part::Text(std::mem::take(&mut text)));
+                }
+                parts.push(parse_parameter(inner, offset)?);
+            }
+            start = end + 1;
+        }
+        text.push_str(&source[start..]);
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        Ok(Self { parts })
+    }
+
+    /// Returns the names of the parameters, without their fields, in order
+    /// of first occurrence.
+    pub fn parameters(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = vec![];
+        for part in &self.parts {
+            if let Part::Parameter { path, .. } = part {
+                if !names.contains(&path[0].as_str()) {
+                    names.push(&path[0]);
+                }
+            }
+        }
+        names
+    }
+
+    /// Renders the template with the fields of `arguments` as parameters.
+    pub fn render<T>(&self, arguments: &T) -> Result<String, TemplateError>
+        where T: ?Sized + Serialize
+    {
+        let arguments = json::typed_to_untyped(arguments).map_err(|error| TemplateError::InvalidArguments(error.to_string()))?;
+        let Value::Object(arguments) = arguments else {
+            return Err(TemplateError::InvalidArguments("arguments must serialize as an object".into()));
+        };
+        let parameters = self.parameters();
+        if let Some(name) = arguments.keys().find(|name| !parameters.contains(&name.as_str())) {
+            return Err(TemplateError::UnknownParameter(name.clone()));
+        }
+        let mut output = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => output.push_str(text),
+                Part::Parameter { path, spec } => {
+                    let mut value = arguments.get(&path[0]);
+                    for field in &path[1..] {
+                        value = value.and_then(|value| match value {
+                            Value::Array(elements) => field.parse::<usize>().ok().and_then(|index| elements.get(index)),
+                            value => value.get(field),
+                        });
+                    }
+                    let value = value.ok_or_else(|| TemplateError::MissingParameter(path.join(".")))?;
+                    output.push_str(&format_value(value, spec).map_err(|message| TemplateError::InvalidFormat { parameter: path.join("."), message })?);
+                },
+            }
+        }
+        Ok(output)
+    }
+}
+
+impl FromStr for Template {
+    type Err = TemplateError;
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        Self::parse(source)
+    }
+}
+
+/// Returns the offset of the `}` closing the parameter at `offset`,
+/// skipping an escaped sequence.
+fn closing_bracket(source: &str, offset: usize) -> Result<usize, TemplateError> {
+    let mut quoted = false;
+    for (index, ch) in source[offset + 1..].char_indices() {
+        match ch {
+            '"' => quoted = !quoted,
+            '}' if !quoted => return Ok(offset + 1 + index),
+            _ => {},
+        }
+    }
+    Err(TemplateError::Syntax { offset, message: "unterminated parameter" })
+}
+
+fn is_name_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '$')
+}
+
+fn parse_parameter(inner: &str, offset: usize) -> Result<Part, TemplateError> {
+    let (path, spec) = inner.split_once(':').unwrap_or((inner, ""));
+    let path: Vec<String> = path.trim_end().split('.').map(str::to_owned).collect();
+    if path.iter().any(|field| field.is_empty() || !field.chars().all(is_name_char)) {
+        return Err(TemplateError::Syntax { offset, message: "invalid parameter name" });
+    }
+    let spec = parse_spec(spec).ok_or(TemplateError::Syntax { offset, message: "invalid format specification" })?;
+    Ok(Part::Parameter { path, spec })
+}
+
+fn parse_spec(spec: &str) -> Option<FormatSpec> {
+    fn align(ch: char) -> Option<Align> {
+        match ch {
+            '<' => Some(Align::Left),
+            '^' => Some(Align::Center),
+            '>' => Some(Align::Right),
+            _ => None,
+        }
+    }
+    fn integer(chars: &[char], index: &mut usize) -> Option<usize> {
+        let start = *index;
+        while chars.get(*index).is_some_and(char::is_ascii_digit) {
+            *index += 1;
+        }
+        chars[start..*index].iter().collect::<String>().parse().ok()
+    }
+
+    let chars: Vec<char> = spec.chars().collect();
+    let mut result = FormatSpec { fill: ' ', align: None, width: None, grouping: false, precision: None };
+    let mut index = 0;
+    if let Some(a) = chars.get(1).copied().and_then(align) {
+        result.fill = chars[0];
+        result.align = Some(a);
+        index = 2;
+    } else if let Some(a) = chars.first().copied().and_then(align) {
+        result.align = Some(a);
+        index = 1;
+    }
+    result.width = integer(&chars, &mut index);
+    if chars.get(index) == Some(&',') {
+        result.grouping = true;
+        index += 1;
+    }
+    if chars.get(index) == Some(&'.') {
+        index += 1;
+        result.precision = Some(integer(&chars, &mut index)?);
+    }
+    (index == chars.len()).then_some(result)
+}
+
+fn format_value(value: &Value, spec: &FormatSpec) -> Result<String, &'static str> {
+    let (text, default_align) = match value {
+        Value::Number(number) => (format_number(number, spec), Align::Right),
+        _ if spec.grouping => return Err("number grouping applies only to numbers"),
+        Value::String(string) => (match spec.precision {
+            Some(precision) => string.chars().take(precision).collect(),
+            None => string.clone(),
+        }, Align::Left),
+        value => (value.to_string(), Align::Left),
+    };
+    let padding = spec.width.unwrap_or(0).saturating_sub(text.chars().count());
+    let (left, right) = match spec.align.unwrap_or(default_align) {
+        Align::Left => (0, padding),
+        Align::Center => (padding / 2, padding - padding / 2),
+        Align::Right => (padding, 0),
+    };
+    let fill = |count| std::iter::repeat_n(spec.fill, count);
+    Ok(fill(left).chain(text.chars()).chain(fill(right)).collect())
+}
+
+fn format_number(number: &Number, spec: &FormatSpec) -> String {
+    let text = match spec.precision {
+        Some(precision) => format!("{:.precision$}", number.as_f64().unwrap_or(f64::NAN)),
+        None => number.to_string(),
+    };
+    if !spec.grouping {
+        return text;
+    }
+    let (sign, unsigned) = text.strip_prefix('-').map_or(("", text.as_str()), |unsigned| ("-", unsigned));
+    let digits_end = unsigned.find(|ch: char| !ch.is_ascii_digit()).unwrap_or(unsigned.len());
+    match NonNegBigInt::from_str(&unsigned[..digits_end]) {
+        Ok(integer) => format!("{sign}{}{}", integer.comma_separated(), &unsigned[digits_end..]),
+        Err(_) => text,
+    }
+}
+
+/// Error produced when parsing or rendering a [`Template`].
+#[derive(PartialEq, Clone, Debug)]
+pub enum TemplateError {
+    /// The template is malformed at the given byte offset.
+    Syntax { offset: usize, message: &'static str },
+    /// A parameter, or a field of it, is not given.
+    MissingParameter(String),
+    /// An argument is not a parameter of the template.
+    UnknownParameter(String),
+    /// The arguments do not serialize as an object.
+    InvalidArguments(String),
+    /// A format specification does not apply to the argument.
+    InvalidFormat { parameter: String, message: &'static str },
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Syntax { offset, message } => write!(f, "{message} (offset {offset})"),
+            Self::MissingParameter(name) => write!(f, "missing template parameter \"{name}\""),
+            Self::UnknownParameter(name) => write!(f, "unknown template parameter \"{name}\""),
+            Self::InvalidArguments(message) => write!(f, "invalid template arguments: {message}"),
+            Self::InvalidFormat { parameter, message } => write!(f, "{message} at \"{parameter}\""),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// Renders a template string literal with named arguments, checking
+/// at compile time that every parameter is given and every argument is used.
+///
+/// Arguments may be of any serializable type, and the result is
+/// a `Result<String, TemplateError>`. See [`Template`] for the syntax.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::string::template;
+///
+/// let hp = [120, 80];
+/// let text = template!("{name} has {hp.0:>5} HP", name = "Ana", hp = hp).unwrap();
+/// assert_eq!(text, "Ana has   120 HP");
+/// ```
+///
+/// A parameter without argument fails to compile:
+///
+/// ```compile_fail
+/// use rialight_util::string::template;
+///
+/// let _ = template!("{name} has {hp} HP", name = "Ana");
+/// ```
+pub macro template {
+    ($template:literal $(, $name:ident = $value:expr)* $(,)?) => {
+        {
+            const _: () = $crate::string::check_template_arguments($template, &[$(::std::stringify!($name)),*]);
+            (|| {
+                let template = $crate::string::Template::parse($template)?;
+                let mut arguments = $crate::serialization::json::Map::new();
+                $(
+                    let value = $crate::serialization::json::typed_to_untyped(&$value)
+                        .map_err(|error| $crate::string::TemplateError::InvalidArguments(::std::string::ToString::to_string(&error)))?;
+                    arguments.insert(::std::stringify!($name).into(), value);
+                )*
+                template.render(&arguments)
+            })()
+        }
+    },
+}
+
+/// Panics, at compile time when used in a constant, if a parameter of `template`
+/// is not among `names` or a name is not a parameter.
+#[doc(hidden)]
+pub const fn check_template_arguments(template: &str, names: &[&str]) {
+    const MAX_ARGUMENTS: usize = 64;
+    assert!(names.len() <= MAX_ARGUMENTS, "too many template arguments");
+    let bytes = template.as_bytes();
+    let mut used = [false; MAX_ARGUMENTS];
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'{' {
+            i += 1;
+            continue;
+        }
+        i += 1;
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if i < bytes.len() && bytes[i] == b'"' {
+            i += 1;
+            while i < bytes.len() && bytes[i] != b'"' {
+                i += 1;
+            }
+            i += 1;
+        } else {
+            let start = i;
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || matches!(bytes[i], b'-' | b'_' | b'$')) {
+                i += 1;
+            }
+            let mut k = 0;
+            while k < names.len() && !bytes_eq(names[k].as_bytes(), bytes, start, i) {
+                k += 1;
+            }
+            assert!(k < names.len(), "template parameter is not given as an argument");
+            used[k] = true;
+        }
+        while i < bytes.len() && bytes[i] != b'}' {
+            i += 1;
+        }
+        i += 1;
+    }
+    let mut k = 0;
+    while k < names.len() {
+        assert!(used[k], "template argument is not used");
+        k += 1;
+    }
+}
+
+/// Compares `a` with `b[start..end]`.
+const fn bytes_eq(a: &[u8], b: &[u8], start: usize, end: usize) -> bool {
+    if a.len() != end - start {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[start + i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::serialization::json::json;
+
+    #[test]
+    fn parse_and_render() {
+        let template = Template::parse(r#"{ a }{"{}"}{b.list.1:*^7.2} [{c:<4}] {d:,}"#).unwrap();
+        assert_eq!(template.parameters(), ["a", "b", "c", "d"]);
+        let arguments = json!({ "a": true, "b": { "list": [0, 1.5] }, "c": "xy", "d": -1234567 });
+        assert_eq!(template.render(&arguments).unwrap(), "true{}*1.50** [xy  ] -1,234,567");
+        assert_eq!(Template::parse("{s:.2}").unwrap().render(&json!({ "s": "abc" })).unwrap(), "ab");
+
+        assert_eq!(template.render(&json!({ "a": 0, "b": {}, "c": 0, "d": 0 })), Err(TemplateError::MissingParameter("b.list.1".into())));
+        assert_eq!(template.render(&json!({ "e": 0 })), Err(TemplateError::UnknownParameter("e".into())));
+        assert!(matches!(template.render(&0), Err(TemplateError::InvalidArguments(_))));
+        assert!(matches!(Template::parse("{s:,}").unwrap().render(&json!({ "s": "" })), Err(TemplateError::InvalidFormat { .. })));
+
+        assert_eq!(Template::parse("a}"), Err(TemplateError::Syntax { offset: 1, message: "unmatched '}'" }));
+        assert_eq!(Template::parse("a {b"), Err(TemplateError::Syntax { offset: 2, message: "unterminated parameter" }));
+        assert_eq!(Template::parse("{a..b}"), Err(TemplateError::Syntax { offset: 0, message: "invalid parameter name" }));
+        assert_eq!(Template::parse("{a:5x}"), Err(TemplateError::Syntax { offset: 0, message: "invalid format specification" }));
+    }
+
+    #[test]
+    fn macro_arguments() {
+        #[derive(Serialize)]
+        struct Item {
+            name: &'static str,
+            price: f64,
+        }
+        let item = Item { name: "Sword", price: 1500.0 };
+        assert_eq!(template!("{item.name}: {item.price:,.2} {\"{\"}{ unit }{\"}\"}", item = item, unit = "gold").unwrap(), "Sword: 1,500.00 {gold}");
+    }
+}
@@ -0,0 +1,237 @@
+/*!
+Unicode text segmentation, display width, normalization and case folding.
+
+Segmentation functions return iterators of segments paired with their
+byte offsets in the string, which are the same offsets as returned by
+[`CodePointsReader::index`](super::CodePointsReader::index).
+
+# Graphemes
+
+A grapheme cluster is what a user perceives as a single character, which
+may consist of several code points, such as an emoji with a skin tone
+modifier. Carets move and text is truncated at grapheme boundaries.
+
+```
+use rialight_util::string::unicode;
+
+let text = "e\u{301}👍🏽!";
+let graphemes: Vec<_> = unicode::graphemes(text).collect();
+assert_eq!(graphemes, [(0, "e\u{301}"), (3, "👍🏽"), (11, "!")]);
+
+assert_eq!(unicode::next_grapheme_boundary(text, 3), Some(11));
+assert_eq!(unicode::previous_grapheme_boundary(text, 3), Some(0));
+```
+
+# Words, sentences and lines
+
+[`words`] returns the words of a string, as defined by [UAX #29](https://www.unicode.org/reports/tr29),
+and [`sentences`] its sentences. [`line_breaks`] returns the positions at which
+a line may or must be broken, as defined by [UAX #14](https://www.unicode.org/reports/tr14).
+
+```
+use rialight_util::string::unicode::{self, BreakOpportunity};
+
+let words: Vec<_> = unicode::words("Hello, world!").collect();
+assert_eq!(words, [(0, "Hello"), (7, "world")]);
+
+let breaks: Vec<_> = unicode::line_breaks("a b-c\nd").collect();
+assert_eq!(breaks, [
+    (2, BreakOpportunity::Allowed),
+    (4, BreakOpportunity::Allowed),
+    (6, BreakOpportunity::Mandatory),
+    (7, BreakOpportunity::Mandatory),
+]);
+```
+
+# Display width
+
+[`width`] returns the number of columns a string occupies in a monospace
+font, where East Asian wide characters and most emoji occupy two columns.
+
+```
+use rialight_util::string::unicode;
+
+assert_eq!(unicode::width("日本"), 4);
+assert_eq!(unicode::truncate_to_width("日本語", 5), "日本");
+```
+
+# Normalization and case folding
+
+```
+use rialight_util::string::unicode::{self, NormalizationForm};
+
+assert_eq!(unicode::normalize("e\u{301}", NormalizationForm::Nfc), "é");
+assert_eq!(unicode::case_fold("Straße"), "strasse");
+assert!(unicode::eq_ignore_case("STRASSE", "straße"));
+```
+*/
+
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+pub use unicode_linebreak::BreakOpportunity;
+
+/// Returns the extended grapheme clusters of a string.
+pub fn graphemes(string: &str) -> impl Iterator<Item = (usize, &str)> {
+    string.grapheme_indices(true)
+}
+
+/// Indicates whether an offset is a grapheme cluster boundary.
+///
+/// # Panics
+///
+/// Panics if `offset` is not a code point boundary.
+pub fn is_grapheme_boundary(string: &str, offset: usize) -> bool {
+    GraphemeCursor::new(offset, string.len(), true).is_boundary(string, 0).unwrap_or(false)
+}
+
+/// Returns the next grapheme cluster boundary after an offset,
+/// or `None` at the end of the string.
+///
+/// # Panics
+///
+/// Panics if `offset` is not a code point boundary.
+pub fn next_grapheme_boundary(string: &str, offset: usize) -> Option<usize> {
+    GraphemeCursor::new(offset, string.len(), true).next_boundary(string, 0).ok().flatten()
+}
+
+/// Returns the previous grapheme cluster boundary before an offset,
+/// or `None` at the start of the string.
+///
+/// # Panics
+///
+/// Panics if `offset` is not a code point boundary.
+pub fn previous_grapheme_boundary(string: &str, offset: usize) -> Option<usize> {
+    GraphemeCursor::new(offset, string.len(), true).prev_boundary(string, 0).ok().flatten()
+}
+
+/// Returns the words of a string, excluding whitespace and punctuation.
+pub fn words(string: &str) -> impl Iterator<Item = (usize, &str)> {
+    string.unicode_word_indices()
+}
+
+/// Returns the segments of a string between word boundaries, including
+/// whitespace and punctuation.
+pub fn word_segments(string: &str) -> impl Iterator<Item = (usize, &str)> {
+    string.split_word_bound_indices()
+}
+
+/// Returns the end of the word after an offset, used for moving
+/// the caret forward by a word, or `None` if no word follows.
+pub fn next_word_end(string: &str, offset: usize) -> Option<usize> {
+    words(string).map(|(start, word)| start + word.len()).find(|end| *end > offset)
+}
+
+/// Returns the start of the word before an offset, used for moving
+/// the caret backward by a word, or `None` if no word precedes.
+pub fn previous_word_start(string: &str, offset: usize) -> Option<usize> {
+    words(string).map(|(start, _)| start).take_while(|start| *start < offset).last()
+}
+
+/// Returns the sentences of a string.
+pub fn sentences(string: &str) -> impl Iterator<Item = (usize, &str)> {
+    string.split_sentence_bound_indices()
+}
+
+/// Returns the line break opportunities of a string, each being the offset
+/// at which the next line would start. The end of the string is always
+/// a mandatory break.
+pub fn line_breaks(string: &str) -> impl Iterator<Item = (usize, BreakOpportunity)> + '_ {
+    unicode_linebreak::linebreaks(string)
+}
+
+/// Returns the display width of a string in columns.
+pub fn width(string: &str) -> usize {
+    string.width()
+}
+
+/// Returns the display width of a code point in columns. Control
+/// characters have no width.
+pub fn char_width(ch: char) -> usize {
+    ch.width().unwrap_or(0)
+}
+
+/// Returns the longest prefix of a string, ending at a grapheme cluster
+/// boundary, whose display width does not exceed `max_width`.
+pub fn truncate_to_width(string: &str, max_width: usize) -> &str {
+    let mut total_width = 0;
+    for (offset, grapheme) in graphemes(string) {
+        total_width += width(grapheme);
+        if total_width > max_width {
+            return &string[..offset];
+        }
+    }
+    string
+}
+
+/// A Unicode normalization form.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum NormalizationForm {
+    /// Canonical decomposition followed by canonical composition.
+    Nfc,
+    /// Canonical decomposition.
+    Nfd,
+    /// Compatibility decomposition followed by canonical composition.
+    Nfkc,
+    /// Compatibility decomposition.
+    Nfkd,
+}
+
+/// Normalizes a string.
+pub fn normalize(string: &str, form: NormalizationForm) -> String {
+    match form {
+        NormalizationForm::Nfc => string.nfc().collect(),
+        NormalizationForm::Nfd => string.nfd().collect(),
+        NormalizationForm::Nfkc => string.nfkc().collect(),
+        NormalizationForm::Nfkd => string.nfkd().collect(),
+    }
+}
+
+/// Indicates whether a string is normalized.
+pub fn is_normalized(string: &str, form: NormalizationForm) -> bool {
+    match form {
+        NormalizationForm::Nfc => unicode_normalization::is_nfc(string),
+        NormalizationForm::Nfd => unicode_normalization::is_nfd(string),
+        NormalizationForm::Nfkc => unicode_normalization::is_nfkc(string),
+        NormalizationForm::Nfkd => unicode_normalization::is_nfkd(string),
+    }
+}
+
+/// Applies full Unicode case folding to a string, for comparing
+/// strings regardless of case.
+pub fn case_fold(string: &str) -> String {
+    caseless::default_case_fold_str(string)
+}
+
+/// Compares strings regardless of case and of canonically
+/// equivalent forms.
+pub fn eq_ignore_case(a: &str, b: &str) -> bool {
+    caseless::canonical_caseless_match_str(a, b)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn segmentation() {
+        let text = "🇧🇷 go\u{301}! Hi.";
+        assert_eq!(graphemes(text).map(|(_, grapheme)| grapheme).collect::<Vec<_>>(), ["🇧🇷", " ", "g", "o\u{301}", "!", " ", "H", "i", "."]);
+        assert!(is_grapheme_boundary(text, 8));
+        assert!(!is_grapheme_boundary(text, 4));
+        assert_eq!(next_grapheme_boundary(text, 10), Some(13));
+        assert_eq!(previous_grapheme_boundary(text, 0), None);
+
+        assert_eq!(next_word_end(text, 0), Some(13));
+        assert_eq!(next_word_end(text, 13), Some(17));
+        assert_eq!(previous_word_start(text, 17), Some(15));
+        assert_eq!(previous_word_start(text, 9), None);
+        assert_eq!(sentences(text).map(|(offset, _)| offset).collect::<Vec<_>>(), [0, 15]);
+
+        assert_eq!(width(text), 10);
+        assert_eq!(char_width('\n'), 0);
+        assert_eq!(truncate_to_width(text, 3), "🇧🇷 ");
+        assert!(is_normalized(&normalize(text, NormalizationForm::Nfkd), NormalizationForm::Nfd));
+    }
+}
@@ -0,0 +1,172 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}},
+    task::{Poll, Waker},
+};
+use crate::futures::Future;
+
+/// Error returned when an operation is cancelled through
+/// a [`CancellationToken`].
+#[derive(PartialEq, Clone, Debug, Copy)]
+pub struct CancelledError;
+
+impl Display for CancelledError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Operation cancelled")
+    }
+}
+
+impl std::error::Error for CancelledError {}
+
+/// A token used to cancel waits, timeouts and intervals from
+/// another task.
+///
+/// Cloning a token returns a handle to the same token; cancelling
+/// any of the clones cancels all of them. Once cancelled, a token
+/// remains cancelled.
+///
+/// # Examples
+///
+/// ```
+/// use rialight_util::{futures::exec_future, timing::*};
+///
+/// async fn example_fn() {
+///     let token = CancellationToken::new();
+///     exec_future({
+///         let token = token.clone();
+///         async move {
+///             wait(Duration::from_secs(1)).await;
+///             token.cancel();
+///         }
+///     });
+///     if let Err(CancelledError) = token.run(wait(Duration::from_secs(10))).await {
+///         println!("wait cancelled after a second");
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    inner: Arc<CancellationTokenInner>,
+}
+
+#[derive(Debug, Default)]
+struct CancellationTokenInner {
+    cancelled: AtomicBool,
+    waiters: Mutex<Waiters>,
+}
+
+/// Wakers of the tasks awaiting cancellation, by waiter identifier.
+#[derive(Debug, Default)]
+struct Waiters {
+    next_id: u64,
+    wakers: HashMap<u64, Waker>,
+}
+
+/// Registration of a waiter of [`CancellationToken::cancelled`],
+/// which removes its waker when dropped.
+struct Waiter<'a> {
+    inner: &'a CancellationTokenInner,
+    id: Option<u64>,
+}
+
+impl Drop for Waiter<'_> {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            self.inner.waiters.lock().unwrap().wakers.remove(&id);
+        }
+    }
+}
+
+impl CancellationToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the token, waking every task awaiting
+    /// its cancellation.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        let wakers = std::mem::take(&mut self.inner.waiters.lock().unwrap().wakers);
+        for waker in wakers.into_values() {
+            waker.wake();
+        }
+    }
+
+    /// Indicates whether the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Completes once the token is cancelled.
+    ///
+    /// The waker of a pending call is removed from the token when
+    /// the returned future completes or is dropped.
+    pub async fn cancelled(&self) {
+        let mut waiter = Waiter { inner: &self.inner, id: None };
+        std::future::poll_fn(|cx| {
+            if self.is_cancelled() {
+                return Poll::Ready(());
+            }
+            let mut waiters = self.inner.waiters.lock().unwrap();
+            // the token may have been cancelled before the lock was acquired
+            if self.is_cancelled() {
+                return Poll::Ready(());
+            }
+            let id = *waiter.id.get_or_insert_with(|| {
+                waiters.next_id += 1;
+                waiters.next_id
+            });
+            match waiters.wakers.get_mut(&id) {
+                Some(waker) if waker.will_wake(cx.waker()) => {},
+                Some(waker) => *waker = cx.waker().clone(),
+                None => {
+                    waiters.wakers.insert(id, cx.waker().clone());
+                },
+            }
+            Poll::Pending
+        }).await
+    }
+
+    /// Runs a future until it completes or the token is cancelled.
+    /// If the token is cancelled first, the future is dropped and
+    /// [`CancelledError`] is returned.
+    ///
+    /// The future is not polled if the token is already cancelled.
+    pub async fn run<F: Future>(&self, future: F) -> Result<F::Output, CancelledError> {
+        if self.is_cancelled() {
+            return Err(CancelledError);
+        }
+        let future = std::pin::pin!(future);
+        let cancelled = std::pin::pin!(self.cancelled());
+        match futures::future::select(future, cancelled).await {
+            futures::future::Either::Left((output, _)) => Ok(output),
+            futures::future::Either::Right(_) => Err(CancelledError),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dropped_waiters() {
+        let token = CancellationToken::new();
+        let waker = futures::task::noop_waker();
+        let mut cx = std::task::Context::from_waker(&waker);
+        for _ in 0..100 {
+            let mut run = std::pin::pin!(token.run(std::future::pending::<()>()));
+            assert!(run.as_mut().poll(&mut cx).is_pending());
+            assert_eq!(token.inner.waiters.lock().unwrap().wakers.len(), 1);
+        }
+        assert!(token.inner.waiters.lock().unwrap().wakers.is_empty());
+
+        let mut cancelled = std::pin::pin!(token.cancelled());
+        assert!(cancelled.as_mut().poll(&mut cx).is_pending());
+        token.cancel();
+        assert!(cancelled.as_mut().poll(&mut cx).is_ready());
+        assert!(token.inner.waiters.lock().unwrap().wakers.is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+use super::{animation_interval, Duration, Interval, MissedTickBehavior};
+
+/// Step yielded by [`FixedTimestep::step`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FixedTimestepStep {
+    /// Advance the simulation by the given fixed delta time.
+    Update(Duration),
+    /// Render the current frame. The value is the interpolation alpha,
+    /// in the range `0.0..1.0`, between the previous and current
+    /// simulation states.
+    Render(f64),
+}
+
+/// A game loop driver that runs fixed updates and variable renders,
+/// returned from the [`fixed_timestep`] function.
+///
+/// Each frame of the underlying animation interval adds the frame time to an
+/// accumulator, which is consumed by as many [`FixedTimestepStep::Update`] steps
+/// as fit in it, followed by one [`FixedTimestepStep::Render`] step carrying the
+/// interpolation alpha of the remaining time.
+///
+/// Two guards prevent the loop from falling behind indefinitely (the
+/// "spiral of death") after a stall:
+///
+/// - The frame time is clamped to the maximum frame time (250 ms by default).
+/// - At most a maximum number of updates (8 by default) run per frame;
+///   the remaining whole update periods are dropped.
+///
+/// The driver behaves the same on every Rialight runtime and
+/// on a [`TestClock`](super::TestClock).
+///
+/// # Examples
+///
+/// ```
+/// use rialight_util::timing::*;
+///
+/// async fn game_loop() {
+///     let mut timestep = fixed_timestep(Duration::from_millis(10), Duration::from_millis(16));
+///     loop {
+///         match timestep.step().await {
+///             FixedTimestepStep::Update(delta) => {
+///                 // advance physics by `delta`
+///             },
+///             FixedTimestepStep::Render(alpha) => {
+///                 // render interpolating by `alpha`
+///             },
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct FixedTimestep {
+    interval: Interval,
+    update_period: Duration,
+    max_frame_time: Duration,
+    max_updates_per_frame: u32,
+    accumulator: Duration,
+    pending_updates: u32,
+    pending_render: bool,
+}
+
+/// Creates a [`FixedTimestep`] that updates every `update_period`
+/// and renders on an animation interval of `frame_period`.
+///
+/// # Panics
+///
+/// This function panics if either period is zero.
+pub fn fixed_timestep(update_period: Duration, frame_period: Duration) -> FixedTimestep {
+    assert!(!update_period.is_zero(), "rialight::util::timing::fixed_timestep() must be called with non-zero update period");
+    let mut interval = animation_interval(frame_period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    FixedTimestep {
+        interval,
+        update_period,
+        max_frame_time: Duration::from_millis(250),
+        max_updates_per_frame: 8,
+        accumulator: Duration::ZERO,
+        pending_updates: 0,
+        pending_render: false,
+    }
+}
+
+impl FixedTimestep {
+    /// Completes with the next step of the loop.
+    ///
+    /// # Cancellation
+    ///
+    /// Dropping the future returned by `step` before it completes
+    /// does not consume any step.
+    pub async fn step(&mut self) -> FixedTimestepStep {
+        loop {
+            if self.pending_updates != 0 {
+                self.pending_updates -= 1;
+                return FixedTimestepStep::Update(self.update_period);
+            }
+            if self.pending_render {
+                self.pending_render = false;
+                return FixedTimestepStep::Render(self.alpha());
+            }
+            let frame_time = self.interval.tick().await.min(self.max_frame_time);
+            self.accumulator += frame_time;
+            let updates = self.accumulator.as_nanos() / self.update_period.as_nanos();
+            if updates > self.max_updates_per_frame.into() {
+                self.pending_updates = self.max_updates_per_frame;
+                self.accumulator = Duration::from_nanos((self.accumulator.as_nanos() % self.update_period.as_nanos()) as u64);
+            } else {
+                self.pending_updates = updates as u32;
+                self.accumulator -= self.update_period * self.pending_updates;
+            }
+            self.pending_render = true;
+        }
+    }
+
+    /// Returns the interpolation alpha of the time accumulated
+    /// for the next update.
+    pub fn alpha(&self) -> f64 {
+        self.accumulator.as_secs_f64() / self.update_period.as_secs_f64()
+    }
+
+    /// Returns the fixed update period.
+    pub fn update_period(&self) -> Duration {
+        self.update_period
+    }
+
+    /// Returns the period of the animation interval driving the frames.
+    pub fn frame_period(&self) -> Duration {
+        self.interval.period()
+    }
+
+    /// Changes the period of the animation interval driving the frames.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `period` is zero.
+    pub fn set_frame_period(&mut self, period: Duration) {
+        self.interval.set_period(period);
+    }
+
+    /// Returns the maximum frame time, above which frame times
+    /// are clamped.
+    pub fn max_frame_time(&self) -> Duration {
+        self.max_frame_time
+    }
+
+    /// Changes the maximum frame time, above which frame times
+    /// are clamped.
+    pub fn set_max_frame_time(&mut self, duration: Duration) {
+        self.max_frame_time = duration;
+    }
+
+    /// Returns the maximum number of updates run per frame.
+    pub fn max_updates_per_frame(&self) -> u32 {
+        self.max_updates_per_frame
+    }
+
+    /// Changes the maximum number of updates run per frame.
+    pub fn set_max_updates_per_frame(&mut self, count: u32) {
+        self.max_updates_per_frame = count;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::timing::TestClock;
+
+    async fn collect_frames(timestep: &mut FixedTimestep, frames: usize) -> Vec<(u32, f64)> {
+        let mut result = vec![];
+        let mut updates = 0;
+        while result.len() < frames {
+            match timestep.step().await {
+                FixedTimestepStep::Update(delta) => {
+                    assert_eq!(delta, timestep.update_period());
+                    updates += 1;
+                },
+                FixedTimestepStep::Render(alpha) => {
+                    result.push((updates, (alpha * 100.0).round() / 100.0));
+                    updates = 0;
+                },
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn accumulator() {
+        let clock = TestClock::new();
+        clock.resume();
+        let frames = clock.block_on(async {
+            let mut timestep = fixed_timestep(Duration::from_millis(10), Duration::from_millis(16));
+            collect_frames(&mut timestep, 4).await
+        });
+        assert_eq!(frames, [(0, 0.0), (1, 0.6), (2, 0.2), (1, 0.8)]);
+    }
+
+    #[test]
+    fn spiral_of_death_guard() {
+        let clock = TestClock::new();
+        clock.resume();
+        let frames = clock.block_on(async {
+            let mut timestep = fixed_timestep(Duration::from_millis(10), Duration::from_millis(10));
+            timestep.set_max_frame_time(Duration::from_millis(100));
+            timestep.set_max_updates_per_frame(4);
+            let mut frames = collect_frames(&mut timestep, 1).await;
+            // stall the loop for a second
+            clock.advance(Duration::from_millis(1005));
+            frames.extend(collect_frames(&mut timestep, 2).await);
+            frames
+        });
+        assert_eq!(frames, [(0, 0.0), (4, 0.0), (0, 0.5)]);
+    }
+}
@@ -1,14 +1,41 @@
 /*!
 Work with common timing and animation intervals.
+
+# Testing
+
+Code built on this module can be tested deterministically by
+entering a [`TestClock`], which replaces the host clock with
+a simulated clock that only moves forward when told to.
+
+```
+use rialight_util::timing::*;
+
+let clock = TestClock::new();
+clock.block_on(async {
+    let start = Instant::now();
+    clock.advance(Duration::from_secs(60));
+    assert_eq!(Instant::now().since(start), Duration::from_secs(60));
+});
+```
 */
 
 pub use std::time::Duration;
-use std::{fmt::Display, ops::{Add, AddAssign, Sub, SubAssign}, sync::{Arc, RwLock}};
+use std::{fmt::Display, ops::{Add, AddAssign, Sub, SubAssign}, sync::{Arc, atomic::{AtomicBool, AtomicU64, Ordering}}};
 
 use crate::futures::*;
+use futures::StreamExt;
 
 mod platform;
 
+mod test_clock;
+pub use test_clock::{TestClock, TestClockGuard};
+
+mod cancellation_token;
+pub use cancellation_token::{CancellationToken, CancelledError};
+
+mod fixed_timestep;
+pub use fixed_timestep::{fixed_timestep, FixedTimestep, FixedTimestepStep};
+
 /// Error returned by [`timeout`] and [`timeout_at`].
 /// 
 /// This error is returned when a timeout expires before the function
@@ -40,8 +67,8 @@ impl std::error::Error for ElapsedError {}
 /// the `Instant` type from the temporal API will be able to convert to the `Instant` type from the timeout API,
 /// usually by just calling `.into()`.
 /// 
-/// ```
-/// use rialight_util::{timeout::*, temporal};
+/// ```ignore
+/// use rialight_util::{timing::*, temporal};
 /// 
 /// let instant: Instant = temporal::now::instant().into();
 /// ```
@@ -50,68 +77,187 @@ impl std::error::Error for ElapsedError {}
 /// 
 /// This `Instant` type is not the same as the one from the Rust standard library.
 /// 
+/// # Test clock
+/// 
+/// While a [`TestClock`] is entered, [`Instant::now`] returns simulated instants.
+/// Simulated instants and host instants cannot be mixed; doing so panics.
+/// 
 #[derive(Copy, Clone, Debug, Eq, Ord, PartialEq, PartialOrd, Hash)]
 pub struct Instant {
-    inner: platform::Instant,
+    inner: InstantInner,
+}
+
+#[derive(Copy, Clone, Debug, Eq, Ord, PartialEq, PartialOrd, Hash)]
+enum InstantInner {
+    Host(platform::Instant),
+    /// Time elapsed since the creation of a test clock.
+    Simulated(Duration),
 }
 
 impl Instant {
     /// Returns the elapsed time since `other` or zero
     /// if the `self` instant is earlier than `other`.
     pub fn since(&self, other: Instant) -> Duration {
-        self.inner.since(other.inner)
+        match (self.inner, other.inner) {
+            (InstantInner::Host(a), InstantInner::Host(b)) => a.since(b),
+            (InstantInner::Simulated(a), InstantInner::Simulated(b)) => a.saturating_sub(b),
+            _ => mixed_clocks_panic(),
+        }
     }
 
-    /// Returns the current instant from the host environment.
+    /// Returns the current instant from the host environment,
+    /// or from the entered [`TestClock`] if any.
     pub fn now() -> Instant {
-        Self { inner: platform::Instant::now() }
+        if let Some(clock) = TestClock::current() {
+            return clock.now();
+        }
+        Self { inner: InstantInner::Host(platform::Instant::now()) }
     }
 
     /// Adds a duration to the instant, returning a new instant.
     /// `None` is returned if the result is earlier or later than
     /// the range that `Instant` can represent.
     pub fn try_add(&self, duration: Duration) -> Option<Instant> {
-        Some(Self { inner: self.inner.try_add(duration)? })
+        Some(Self {
+            inner: match self.inner {
+                InstantInner::Host(instant) => InstantInner::Host(instant.try_add(duration)?),
+                InstantInner::Simulated(elapsed) => InstantInner::Simulated(elapsed.checked_add(duration)?),
+            },
+        })
     }
 
     /// Subtracts a duration from the instant, returning a new instant.
     /// `None` is returned if the result is earlier or later than
     /// the range that `Instant` can represent.
     pub fn try_subtract(&self, duration: Duration) -> Option<Instant> {
-        Some(Self { inner: self.inner.try_subtract(duration)? })
+        Some(Self {
+            inner: match self.inner {
+                InstantInner::Host(instant) => InstantInner::Host(instant.try_subtract(duration)?),
+                InstantInner::Simulated(elapsed) => InstantInner::Simulated(elapsed.checked_sub(duration)?),
+            },
+        })
+    }
+
+    pub(crate) fn simulated(elapsed: Duration) -> Instant {
+        Self { inner: InstantInner::Simulated(elapsed) }
+    }
+
+    /// Returns the time elapsed since the creation of the test clock.
+    /// Panics for host instants.
+    pub(crate) fn simulated_elapsed(&self) -> Duration {
+        match self.inner {
+            InstantInner::Simulated(elapsed) => elapsed,
+            InstantInner::Host(_) => mixed_clocks_panic(),
+        }
+    }
+
+    /// Returns the host instant. Panics for simulated instants.
+    #[cfg(feature = "rialight_default_export")]
+    fn host(&self) -> platform::Instant {
+        match self.inner {
+            InstantInner::Host(instant) => instant,
+            InstantInner::Simulated(_) => mixed_clocks_panic(),
+        }
     }
 }
 
+fn mixed_clocks_panic() -> ! {
+    panic!("Instants from a TestClock cannot be mixed with instants from the host clock");
+}
+
 impl Add<Duration> for Instant {
     type Output = Instant;
     fn add(self, rhs: Duration) -> Self::Output {
-        Self { inner: self.inner + rhs }
+        Self {
+            inner: match self.inner {
+                InstantInner::Host(instant) => InstantInner::Host(instant + rhs),
+                InstantInner::Simulated(elapsed) => InstantInner::Simulated(elapsed + rhs),
+            },
+        }
     }
 }
 
 impl AddAssign<Duration> for Instant {
     fn add_assign(&mut self, rhs: Duration) {
-        self.inner += rhs;
+        *self = *self + rhs;
     }
 }
 
 impl Sub<Duration> for Instant {
     type Output = Instant;
     fn sub(self, rhs: Duration) -> Self::Output {
-        Self { inner: self.inner - rhs }
+        Self {
+            inner: match self.inner {
+                InstantInner::Host(instant) => InstantInner::Host(instant - rhs),
+                InstantInner::Simulated(elapsed) => InstantInner::Simulated(elapsed - rhs),
+            },
+        }
     }
 }
 
 impl Sub<Instant> for Instant {
     type Output = Duration;
     fn sub(self, rhs: Instant) -> Self::Output {
-        self.inner - rhs.inner
+        match (self.inner, rhs.inner) {
+            (InstantInner::Host(a), InstantInner::Host(b)) => a - b,
+            (InstantInner::Simulated(a), InstantInner::Simulated(b)) => a.saturating_sub(b),
+            _ => mixed_clocks_panic(),
+        }
     }
 }
 
 impl SubAssign<Duration> for Instant {
     fn sub_assign(&mut self, rhs: Duration) {
-        self.inner -= rhs;
+        *self = *self - rhs;
+    }
+}
+
+/// Defines the behavior of an [`Interval`] when it misses a tick.
+/// 
+/// An interval misses ticks when the code awaiting [`Interval::tick`] takes
+/// longer than the period to call it again, such as when an animation frame stalls.
+/// The behavior is the same for every Rialight runtime.
+/// 
+/// The default behavior is [`MissedTickBehavior::Burst`].
+/// 
+/// # Examples
+/// 
+/// ```
+/// use rialight_util::timing::*;
+/// 
+/// async fn example_fn() {
+///     let mut interval = animation_interval(Duration::from_millis(16));
+///     interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+/// }
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum MissedTickBehavior {
+    /// Ticks as fast as possible until caught up with the original schedule.
+    /// Useful for frame-locked logic that must run once per period.
+    #[default]
+    Burst,
+    /// Ticks once and schedules the next tick one period after
+    /// the time the missed tick was yielded.
+    Delay,
+    /// Ticks once and skips the missed ticks, scheduling the next tick
+    /// on the next multiple of the period from the original schedule.
+    /// Useful for heartbeats that must stay aligned to their schedule.
+    Skip,
+}
+
+impl MissedTickBehavior {
+    /// Returns the instant of the tick following one that was
+    /// scheduled at `scheduled` and yielded at `now`.
+    fn next_tick(&self, scheduled: Instant, now: Instant, period: Duration) -> Instant {
+        match self {
+            Self::Burst => scheduled + period,
+            Self::Delay => now + period,
+            Self::Skip => {
+                let late = now.since(scheduled).as_nanos();
+                let missed_periods: u32 = (late / period.as_nanos()).try_into().unwrap_or(u32::MAX);
+                scheduled + period * (missed_periods + 1)
+            },
+        }
     }
 }
 
@@ -120,35 +266,113 @@ impl SubAssign<Duration> for Instant {
 /// [`animation_interval_at`].
 #[derive(Debug)]
 pub struct Interval {
-    inner: platform::Interval,
+    for_animation: bool,
+    period: Duration,
+    next: Instant,
+    last_tick: Option<Instant>,
+    missed_tick_behavior: MissedTickBehavior,
 }
 
 impl Interval {
+    fn new(start: Instant, period: Duration, for_animation: bool) -> Self {
+        assert!(!period.is_zero(), "rialight::util::timing interval must be created with non-zero period");
+        Self {
+            for_animation,
+            period,
+            next: start,
+            last_tick: None,
+            missed_tick_behavior: MissedTickBehavior::default(),
+        }
+    }
+
     /// Completes when the next instant in the interval has been reached,
-    /// yielding the time elapsed since the last tick.
+    /// yielding the time elapsed since the last tick. The first tick
+    /// yields zero.
+    /// 
+    /// # Cancellation
+    /// 
+    /// Dropping the future returned by `tick` before it completes does not
+    /// consume the tick.
     pub async fn tick(&mut self) -> Duration {
-        self.inner.tick().await
+        wait_until(self.next).await;
+        #[cfg(feature = "rialight_browser_export")] {
+            if self.for_animation && TestClock::current().is_none() {
+                platform::browser_runtime::animation_frame().await;
+            }
+        }
+        let now = Instant::now();
+        let delta = self.last_tick.map_or(Duration::ZERO, |last_tick| now.since(last_tick));
+        self.last_tick = Some(now);
+        self.next = self.missed_tick_behavior.next_tick(self.next, now, self.period);
+        delta
+    }
+
+    /// Indicates whether the interval is meant for animations, in which
+    /// case ticks are synchronized with animation frames in the browser.
+    pub fn is_for_animation(&self) -> bool {
+        self.for_animation
+    }
+
+    /// Returns the period of the interval.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Changes the period of the interval. If the interval has already
+    /// ticked, the next tick is rescheduled to one new period after the last tick.
+    /// 
+    /// # Panics
+    /// 
+    /// This method panics if `period` is zero.
+    pub fn set_period(&mut self, period: Duration) {
+        assert!(!period.is_zero(), "rialight::util::timing interval must be given non-zero period");
+        self.period = period;
+        if let Some(last_tick) = self.last_tick {
+            self.next = last_tick + period;
+        }
+    }
+
+    /// Returns the behavior of the interval when it misses a tick.
+    pub fn missed_tick_behavior(&self) -> MissedTickBehavior {
+        self.missed_tick_behavior
+    }
+
+    /// Changes the behavior of the interval when it misses a tick.
+    pub fn set_missed_tick_behavior(&mut self, behavior: MissedTickBehavior) {
+        self.missed_tick_behavior = behavior;
+    }
+
+    /// Resets the interval so that the next tick completes
+    /// one period from now.
+    pub fn reset(&mut self) {
+        self.next = Instant::now() + self.period;
+    }
+
+    /// Resets the interval so that the next tick completes immediately.
+    pub fn reset_immediately(&mut self) {
+        self.next = Instant::now();
+    }
+
+    /// Resets the interval so that the next tick completes at `deadline`.
+    pub fn reset_at(&mut self, deadline: Instant) {
+        self.next = deadline;
     }
 }
 
-/*
 /// Requires for a `Future` to complete before the given
 /// `duration` has elapsed.
 /// 
 /// If the future completes before the duration has elapsed,
-/// then `Ok(())` is returned.
+/// then its output is returned in an [`Ok`] variant.
 /// Otherwise, an error is returned and the future is canceled.
 /// 
-/// This function returns a future whose return type is [`Result`]`<(),`[`ElapsedError`]`>`.
-/// 
 /// If the provided future completes immediately, then the future returned from
 /// this function is guaranteed to complete immediately with an [`Ok`] variant
 /// no matter the provided duration.
 /// 
 /// # Cancellation
 ///
-/// Canceling a timeout being awaited for via the `.await` operator is not possible.
-/// Use [`background_timeout`] for such a purpose.
+/// Use [`CancellationToken::run`] to cancel a timeout from another task.
 /// 
 /// # Examples
 /// 
@@ -156,43 +380,23 @@ impl Interval {
 /// use rialight_util::timing::*;
 /// 
 /// async fn example_fn() {
-///     if let Err(_) = timeout(Duration::from_millis(10), f()).await {
-///         println!("did not receive value within 10 ms");
+///     match timeout(Duration::from_millis(10), f()).await {
+///         Ok(value) => println!("received {value}"),
+///         Err(_) => println!("did not receive value within 10 ms"),
 ///     }
 /// }
 /// 
 /// async fn f() -> u64 { 10 }
 /// ```
 /// 
-pub async fn timeout<F>(duration: Duration, future: F) -> Result<(), ElapsedError>
-where
-    F: Future<Output = ()> + Send + 'static,
-{
-    #[cfg(feature = "rialight_default_export")] {
-        crate::futures::not_sendable_async!();
-        return match tokio::time::timeout(duration, future).await {
-            Err(_) => Err(ElapsedError),
-            Ok(_) => Ok(()),
-        };
-    }
-    #[cfg(feature = "rialight_browser_export")] {
-        return platform::browser_runtime::timeout(duration, future).await;
-    }
-    #[cfg(not(any(feature = "rialight_default_export", feature = "rialight_browser_export")))] {
-        crate::futures::not_sendable_async!();
-        let _ = (duration, future);
-        panic!("Incorrectly configured Rialight runtime");
-    }
+pub async fn timeout<F: Future>(duration: Duration, future: F) -> Result<F::Output, ElapsedError> {
+    timeout_at(Instant::now() + duration, future).await
 }
-*/
 
-/*
 /// Requires a `Future` to complete before the specified instant in time.
 ///
-/// If the future completes before the instant is reached, then `Ok(())`
-/// is returned. Otherwise, an error is returned.
-///
-/// This function returns a future whose return type is [`Result`]`<(),`[`ElapsedError`]`>`.
+/// If the future completes before the instant is reached, then its output
+/// is returned in an [`Ok`] variant. Otherwise, an error is returned.
 ///
 /// If the provided future completes immediately, then the future returned from
 /// this function is guaranteed to complete immediately with an [`Ok`] variant
@@ -214,30 +418,16 @@ where
 ///
 /// # Cancellation
 ///
-/// Canceling a timeout being awaited for via the `.await` operator is not possible.
-/// Use [`background_timeout`] for such a purpose.
+/// Use [`CancellationToken::run`] to cancel a timeout from another task.
 /// 
-pub async fn timeout_at<F>(deadline: Instant, future: F) -> Result<(), ElapsedError>
-where
-    F: Future<Output = ()> + Send + 'static,
-{
-    #[cfg(feature = "rialight_default_export")] {
-        crate::futures::not_sendable_async!();
-        return match tokio::time::timeout_at(deadline.inner.0, future).await {
-            Err(_) => Err(ElapsedError),
-            Ok(_) => Ok(()),
-        };
-    }
-    #[cfg(feature = "rialight_browser_export")] {
-        return platform::browser_runtime::timeout(deadline.since(Instant::now()), future).await;
-    }
-    #[cfg(not(any(feature = "rialight_default_export", feature = "rialight_browser_export")))] {
-        crate::futures::not_sendable_async!();
-        let _ = (deadline, future);
-        panic!("Incorrectly configured Rialight runtime");
+pub async fn timeout_at<F: Future>(deadline: Instant, future: F) -> Result<F::Output, ElapsedError> {
+    let future = std::pin::pin!(future);
+    let elapsed = std::pin::pin!(wait_until(deadline));
+    match futures::future::select(future, elapsed).await {
+        futures::future::Either::Left((output, _)) => Ok(output),
+        futures::future::Either::Right(_) => Err(ElapsedError),
     }
 }
-*/
 
 /// Asynchronously waits until `duration` has elapsed.
 ///
@@ -253,8 +443,8 @@ where
 /// 
 /// # Cancellation
 ///
-/// Canceling a wait being awaited for via the `.await` operator is not possible.
-/// Use [`background_timeout`] for such a purpose.
+/// Use [`CancellationToken::run`] to cancel a wait from another task,
+/// or [`background_timeout`] to execute a function after the wait.
 /// 
 /// # Examples
 /// 
@@ -270,6 +460,10 @@ where
 /// ```
 /// 
 pub async fn wait(duration: Duration) {
+    if let Some(clock) = TestClock::current() {
+        clock.sleep_until(clock.now() + duration).await;
+        return;
+    }
     #[cfg(feature = "rialight_default_export")] {
         crate::futures::not_sendable_async!();
         tokio::time::sleep(duration).await;
@@ -296,8 +490,8 @@ pub async fn wait(duration: Duration) {
 ///
 /// # Cancellation
 ///
-/// Canceling a wait being awaited for via the `.await` operator is not possible.
-/// Use [`background_timeout`] for such a purpose.
+/// Use [`CancellationToken::run`] to cancel a wait from another task,
+/// or [`background_timeout`] to execute a function after the wait.
 /// 
 /// # Examples
 /// 
@@ -307,18 +501,22 @@ pub async fn wait(duration: Duration) {
 /// use rialight_util::timing::*;
 ///
 /// async fn example_fn() {
-///     wait(Instant::now() + Duration::from_millis(100)).await;
+///     wait_until(Instant::now() + Duration::from_millis(100)).await;
 ///     println!("100 ms have elapsed");
 /// }
 /// ```
 /// 
 pub async fn wait_until(deadline: Instant) {
+    if let Some(clock) = TestClock::current() {
+        clock.sleep_until(deadline).await;
+        return;
+    }
     #[cfg(feature = "rialight_default_export")] {
         crate::futures::not_sendable_async!();
-        tokio::time::sleep_until(deadline.inner.0).await;
+        tokio::time::sleep_until(deadline.host().0).await;
     }
     #[cfg(feature = "rialight_browser_export")] {
-        platform::browser_runtime::wait(deadline.since(Instant::now())).await;
+        platform::browser_runtime::wait_until(deadline).await;
     }
     #[cfg(not(any(feature = "rialight_default_export", feature = "rialight_browser_export")))] {
         crate::futures::not_sendable_async!();
@@ -340,7 +538,8 @@ pub async fn wait_until(deadline: Instant) {
 /// # Cancellation
 ///
 /// An interval is disposed when its variable is dropped.
-/// Use [`background_default_interval`] if you need an interval that runs
+/// Use [`CancellationToken::run`] to cancel a tick from another task, or
+/// [`background_default_interval`] if you need an interval that runs
 /// separately and can be cancelled dynamically.
 ///
 /// # Panics
@@ -393,26 +592,7 @@ pub async fn wait_until(deadline: Instant) {
 /// [`.tick().await`]: Interval::tick
 ///
 pub fn default_interval(period: Duration) -> Interval {
-    #[cfg(feature = "rialight_default_export")] {
-        return Interval {
-            inner: platform::tokio_runtime::Interval(tokio::time::interval(period)),
-        };
-    }
-    #[cfg(feature = "rialight_browser_export")] {
-        assert!(period.as_millis() != 0, "rialight::util::timing::interval() must be called with non-zero period");
-        return Interval {
-            inner: platform::browser_runtime::Interval {
-                for_animation: false,
-                period,
-                start: Instant::now(),
-                ticker: None,
-            },
-        };
-    }
-    #[cfg(not(any(feature = "rialight_default_export", feature = "rialight_browser_export")))] {
-        let _ = period;
-        panic!("Incorrectly configured Rialight runtime");
-    }
+    Interval::new(Instant::now(), period, false)
 }
 
 /// Creates a new [`Interval`] that yields with interval of `period` with the
@@ -426,7 +606,8 @@ pub fn default_interval(period: Duration) -> Interval {
 /// # Cancellation
 ///
 /// An interval is disposed when its variable is dropped.
-/// Use [`background_default_interval`] if you need an interval that runs
+/// Use [`CancellationToken::run`] to cancel a tick from another task, or
+/// [`background_default_interval`] if you need an interval that runs
 /// separately and can be cancelled dynamically.
 /// 
 /// # Panics
@@ -451,26 +632,7 @@ pub fn default_interval(period: Duration) -> Interval {
 /// ```
 /// 
 pub fn default_interval_at(start: Instant, period: Duration) -> Interval {
-    #[cfg(feature = "rialight_default_export")] {
-        return Interval {
-            inner: platform::tokio_runtime::Interval(tokio::time::interval_at(start.inner.0, period)),
-        };
-    }
-    #[cfg(feature = "rialight_browser_export")] {
-        assert!(period.as_millis() != 0, "rialight::util::timing::interval_at() must be called with non-zero period");
-        return Interval {
-            inner: platform::browser_runtime::Interval {
-                for_animation: false,
-                period,
-                start: start,
-                ticker: None,
-            },
-        };
-    }
-    #[cfg(not(any(feature = "rialight_default_export", feature = "rialight_browser_export")))] {
-        let _ = (start, period);
-        panic!("Incorrectly configured Rialight runtime");
-    }
+    Interval::new(start, period, false)
 }
 
 /// Creates a new [`Interval`] that yields with interval of `period`. The first
@@ -481,7 +643,8 @@ pub fn default_interval_at(start: Instant, period: Duration) -> Interval {
 /// # Cancellation
 ///
 /// An interval is disposed when its variable is dropped.
-/// Use [`background_animation_interval`] if you need an interval that runs
+/// Use [`CancellationToken::run`] to cancel a tick from another task, or
+/// [`background_animation_interval`] if you need an interval that runs
 /// separately and can be cancelled dynamically.
 ///
 /// # Panics
@@ -506,26 +669,7 @@ pub fn default_interval_at(start: Instant, period: Duration) -> Interval {
 /// [`.tick().await`]: Interval::tick
 ///
 pub fn animation_interval(period: Duration) -> Interval {
-    #[cfg(feature = "rialight_default_export")] {
-        return Interval {
-            inner: platform::tokio_runtime::Interval(tokio::time::interval(period)),
-        };
-    }
-    #[cfg(feature = "rialight_browser_export")] {
-        assert!(period.as_millis() != 0, "rialight::util::timing::interval() must be called with non-zero period");
-        return Interval {
-            inner: platform::browser_runtime::Interval {
-                for_animation: true,
-                period,
-                start: Instant::now(),
-                ticker: None,
-            },
-        };
-    }
-    #[cfg(not(any(feature = "rialight_default_export", feature = "rialight_browser_export")))] {
-        let _ = period;
-        panic!("Incorrectly configured Rialight runtime");
-    }
+    Interval::new(Instant::now(), period, true)
 }
 
 /// Creates a new [`Interval`] that yields with interval of `period` with the
@@ -534,7 +678,8 @@ pub fn animation_interval(period: Duration) -> Interval {
 /// # Cancellation
 ///
 /// An interval is disposed when its variable is dropped.
-/// Use [`background_animation_interval`] if you need an interval that runs
+/// Use [`CancellationToken::run`] to cancel a tick from another task, or
+/// [`background_animation_interval`] if you need an interval that runs
 /// separately and can be cancelled dynamically.
 /// 
 /// # Panics
@@ -559,44 +704,46 @@ pub fn animation_interval(period: Duration) -> Interval {
 /// ```
 /// 
 pub fn animation_interval_at(start: Instant, period: Duration) -> Interval {
-    #[cfg(feature = "rialight_default_export")] {
-        return Interval {
-            inner: platform::tokio_runtime::Interval(tokio::time::interval_at(start.inner.0, period)),
-        };
-    }
-    #[cfg(feature = "rialight_browser_export")] {
-        assert!(period.as_millis() != 0, "rialight::util::timing::interval_at() must be called with non-zero period");
-        return Interval {
-            inner: platform::browser_runtime::Interval {
-                for_animation: true,
-                period,
-                start: start,
-                ticker: None,
-            },
-        };
-    }
-    #[cfg(not(any(feature = "rialight_default_export", feature = "rialight_browser_export")))] {
-        let _ = (start, period);
-        panic!("Incorrectly configured Rialight runtime");
-    }
+    Interval::new(start, period, true)
 }
 
 /// Executes a given function after some elapsed time. This function
 /// returns a `BackgroundTimeout` object with a `stop()` method that can
-/// be used to stop the execution of the function.
-pub fn background_timeout(callback: Box<(dyn Fn() + Send + Sync + 'static)>, duration: Duration) -> BackgroundTimeout {
-    let mut stopped = Arc::new(RwLock::new(false));
+/// be used to stop the execution of the function and a `join()` method
+/// that can be used to await the result of the function.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use rialight_util::timing::*;
+/// 
+/// async fn example_fn() {
+///     let timeout = background_timeout(|| 10, Duration::from_secs(1));
+///     assert_eq!(timeout.join().await, Ok(10));
+/// }
+/// ```
+pub fn background_timeout<T, F>(callback: F, duration: Duration) -> BackgroundTimeout<T>
+where
+    F: FnOnce() -> T + 'static,
+    T: 'static,
+{
+    let token = CancellationToken::new();
+    let fired = Arc::new(AtomicBool::new(false));
+    let (sender, result) = futures::channel::oneshot::channel();
     exec_future({
-        let stopped = Arc::clone(&mut stopped);
+        let token = token.clone();
+        let fired = Arc::clone(&fired);
         async move {
-            wait(duration).await;
-            if !*stopped.read().unwrap() {
-                callback();
+            if token.run(wait(duration)).await.is_ok() {
+                fired.store(true, Ordering::SeqCst);
+                let _ = sender.send(callback());
             }
         }
     });
     BackgroundTimeout {
-        stopped,
+        token,
+        fired,
+        result,
     }
 }
 
@@ -604,14 +751,34 @@ pub fn background_timeout(callback: Box<(dyn Fn() + Send + Sync + 'static)>, dur
 /// from the [`background_timeout`] function.
 /// 
 /// To stop the timeout, call `timeout.stop`.
-pub struct BackgroundTimeout {
-    // inner: platform::BackgroundTimeout,
-    stopped: Arc<RwLock<bool>>,
+pub struct BackgroundTimeout<T = ()> {
+    token: CancellationToken,
+    fired: Arc<AtomicBool>,
+    result: futures::channel::oneshot::Receiver<T>,
 }
 
-impl BackgroundTimeout {
+impl<T> BackgroundTimeout<T> {
+    /// Stops the timeout. The function is not executed if
+    /// it has not been executed yet.
     pub fn stop(&self) {
-        *self.stopped.write().unwrap() = true;
+        self.token.cancel();
+    }
+
+    /// Indicates whether the function has been executed.
+    pub fn has_fired(&self) -> bool {
+        self.fired.load(Ordering::SeqCst)
+    }
+
+    /// Returns the token that stops the timeout when cancelled.
+    /// Useful for stopping the timeout after calling `join()`.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// Waits for the function to be executed, returning its result, or
+    /// [`CancelledError`] if the timeout is stopped before.
+    pub async fn join(self) -> Result<T, CancelledError> {
+        self.result.await.map_err(|_| CancelledError)
     }
 }
 
@@ -621,25 +788,14 @@ impl BackgroundTimeout {
 /// 
 /// The callback function receives the elapsed time since the last time
 /// it was called by this function.
-pub fn background_animation_interval(callback: Box<(dyn Fn(Duration) + Send + Sync + 'static)>, period: Duration) -> BackgroundInterval {
-    let mut stopped = Arc::new(RwLock::new(false));
-    exec_future({
-        let stopped = Arc::clone(&mut stopped);
-        async move {
-            let mut interval = animation_interval(period);
-            interval.tick().await;
-            loop {
-                let delta = interval.tick().await;
-                if *stopped.read().unwrap() {
-                    break;
-                }
-                callback(delta);
-            }
-        }
-    });
-    BackgroundInterval {
-        stopped,
-    }
+/// 
+/// The period and missed tick behavior of the interval can be changed
+/// through the returned `BackgroundInterval`.
+pub fn background_animation_interval<F>(callback: F, period: Duration) -> BackgroundInterval
+where
+    F: Fn(Duration) + Send + Sync + 'static,
+{
+    background_interval(callback, period, true)
 }
 
 /// Executes a given function after each period. This function
@@ -649,25 +805,63 @@ pub fn background_animation_interval(callback: Box<(dyn Fn(Duration) + Send + Sy
 /// The callback function receives the elapsed time since the last time
 /// it was called by this function.
 /// 
+/// The period and missed tick behavior of the interval can be changed
+/// through the returned `BackgroundInterval`.
+/// 
 /// For animations, consider using [`background_animation_interval`] instead.
-pub fn background_default_interval(callback: Box<(dyn Fn(Duration) + Send + Sync + 'static)>, period: Duration) -> BackgroundInterval {
-    let mut stopped = Arc::new(RwLock::new(false));
+pub fn background_default_interval<F>(callback: F, period: Duration) -> BackgroundInterval
+where
+    F: Fn(Duration) + Send + Sync + 'static,
+{
+    background_interval(callback, period, false)
+}
+
+fn background_interval<F>(callback: F, period: Duration, for_animation: bool) -> BackgroundInterval
+where
+    F: Fn(Duration) + Send + Sync + 'static,
+{
+    let token = CancellationToken::new();
+    let times_fired = Arc::new(AtomicU64::new(0));
+    let (commands, mut receiver) = futures::channel::mpsc::unbounded::<BackgroundIntervalCommand>();
+    let (finished, finished_receiver) = futures::channel::oneshot::channel();
+    let mut interval = Interval::new(Instant::now(), period, for_animation);
     exec_future({
-        let stopped = Arc::clone(&mut stopped);
+        let token = token.clone();
+        let times_fired = Arc::clone(&times_fired);
         async move {
-            let mut interval = default_interval(period);
-            interval.tick().await;
-            loop {
-                let delta = interval.tick().await;
-                if *stopped.read().unwrap() {
-                    break;
+            let _ = token.run(async {
+                interval.tick().await;
+                let mut receiver = Some(&mut receiver);
+                loop {
+                    let command = {
+                        let tick = std::pin::pin!(interval.tick());
+                        match receiver.as_mut() {
+                            None => Err(tick.await),
+                            Some(receiver) => match futures::future::select(tick, receiver.next()).await {
+                                futures::future::Either::Left((delta, _)) => Err(delta),
+                                futures::future::Either::Right((command, _)) => Ok(command),
+                            },
+                        }
+                    };
+                    match command {
+                        Ok(Some(command)) => command.apply(&mut interval),
+                        // the `BackgroundInterval` has been dropped
+                        Ok(None) => receiver = None,
+                        Err(delta) => {
+                            times_fired.fetch_add(1, Ordering::SeqCst);
+                            callback(delta);
+                        },
+                    }
                 }
-                callback(delta);
-            }
+            }).await;
+            let _ = finished.send(());
         }
     });
     BackgroundInterval {
-        stopped,
+        token,
+        times_fired,
+        commands,
+        finished: finished_receiver,
     }
 }
 
@@ -676,12 +870,210 @@ pub fn background_default_interval(callback: Box<(dyn Fn(Duration) + Send + Sync
 /// 
 /// To stop the interval, call `interval.stop`.
 pub struct BackgroundInterval {
-    // inner: platform::BackgroundTimeout,
-    stopped: Arc<RwLock<bool>>,
+    token: CancellationToken,
+    times_fired: Arc<AtomicU64>,
+    commands: futures::channel::mpsc::UnboundedSender<BackgroundIntervalCommand>,
+    finished: futures::channel::oneshot::Receiver<()>,
 }
 
 impl BackgroundInterval {
+    /// Stops the interval, disposing of it immediately.
     pub fn stop(&self) {
-        *self.stopped.write().unwrap() = true;
+        self.token.cancel();
+    }
+
+    /// Indicates whether the function has been executed at least once.
+    pub fn has_fired(&self) -> bool {
+        self.times_fired() != 0
+    }
+
+    /// Returns the number of times the function has been executed.
+    pub fn times_fired(&self) -> u64 {
+        self.times_fired.load(Ordering::SeqCst)
+    }
+
+    /// Returns the token that stops the interval when cancelled.
+    /// Useful for stopping the interval after calling `join()`.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// Waits for the interval to be stopped and disposed of.
+    pub async fn join(self) {
+        let _ = self.finished.await;
+    }
+
+    /// Changes the period of the interval. See [`Interval::set_period`].
+    /// 
+    /// # Panics
+    /// 
+    /// This method panics if `period` is zero.
+    pub fn set_period(&self, period: Duration) {
+        assert!(!period.is_zero(), "rialight::util::timing interval must be given non-zero period");
+        let _ = self.commands.unbounded_send(BackgroundIntervalCommand::SetPeriod(period));
+    }
+
+    /// Changes the behavior of the interval when it misses a tick.
+    pub fn set_missed_tick_behavior(&self, behavior: MissedTickBehavior) {
+        let _ = self.commands.unbounded_send(BackgroundIntervalCommand::SetMissedTickBehavior(behavior));
+    }
+
+    /// Resets the interval so that the next call to the function happens
+    /// one period from now.
+    pub fn reset(&self) {
+        let _ = self.commands.unbounded_send(BackgroundIntervalCommand::Reset);
+    }
+}
+
+enum BackgroundIntervalCommand {
+    SetPeriod(Duration),
+    SetMissedTickBehavior(MissedTickBehavior),
+    Reset,
+}
+
+impl BackgroundIntervalCommand {
+    fn apply(self, interval: &mut Interval) {
+        match self {
+            Self::SetPeriod(period) => interval.set_period(period),
+            Self::SetMissedTickBehavior(behavior) => interval.set_missed_tick_behavior(behavior),
+            Self::Reset => interval.reset(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ticks_after_stall(behavior: MissedTickBehavior) -> Vec<Duration> {
+        let clock = TestClock::new();
+        clock.resume();
+        clock.block_on(async {
+            let start = Instant::now();
+            let mut interval = default_interval(Duration::from_millis(10));
+            interval.set_missed_tick_behavior(behavior);
+            interval.tick().await;
+            // stall for three and a half periods
+            clock.advance(Duration::from_millis(35));
+            let mut ticks = vec![];
+            for _ in 0..4 {
+                interval.tick().await;
+                ticks.push(Instant::now() - start);
+            }
+            ticks
+        })
+    }
+
+    #[test]
+    fn missed_tick_behavior() {
+        let ms = Duration::from_millis;
+        assert_eq!(ticks_after_stall(MissedTickBehavior::Burst), [ms(35), ms(35), ms(35), ms(40)]);
+        assert_eq!(ticks_after_stall(MissedTickBehavior::Delay), [ms(35), ms(45), ms(55), ms(65)]);
+        assert_eq!(ticks_after_stall(MissedTickBehavior::Skip), [ms(35), ms(40), ms(50), ms(60)]);
+    }
+
+    #[test]
+    fn change_period() {
+        let clock = TestClock::new();
+        clock.resume();
+        let ticks = clock.block_on(async {
+            let start = Instant::now();
+            let mut interval = default_interval(Duration::from_millis(10));
+            interval.tick().await;
+            interval.tick().await;
+            interval.set_period(Duration::from_millis(50));
+            interval.tick().await;
+            let mut ticks = vec![Instant::now() - start];
+            interval.reset();
+            interval.tick().await;
+            ticks.push(Instant::now() - start);
+            ticks
+        });
+        assert_eq!(ticks, [Duration::from_millis(60), Duration::from_millis(110)]);
+    }
+
+    #[test]
+    fn timeouts() {
+        let clock = TestClock::new();
+        clock.resume();
+        clock.block_on(async {
+            assert_eq!(timeout(Duration::from_secs(1), async { 10 }).await, Ok(10));
+            assert_eq!(timeout(Duration::from_secs(1), wait(Duration::from_secs(2))).await, Err(ElapsedError));
+            assert_eq!(clock.elapsed(), Duration::from_secs(1));
+        });
+    }
+
+    #[test]
+    fn cancellation() {
+        let clock = TestClock::new();
+        clock.resume();
+        clock.block_on(async {
+            let token = CancellationToken::new();
+            exec_future({
+                let token = token.clone();
+                async move {
+                    wait(Duration::from_secs(1)).await;
+                    token.cancel();
+                }
+            });
+            let mut interval = default_interval(Duration::from_secs(5));
+            interval.tick().await;
+            assert_eq!(token.run(interval.tick()).await, Err(CancelledError));
+            assert_eq!(clock.elapsed(), Duration::from_secs(1));
+            assert_eq!(token.run(async { 10 }).await, Err(CancelledError));
+        });
+    }
+
+    #[test]
+    fn background_timeout_join() {
+        let clock = TestClock::new();
+        clock.resume();
+        clock.block_on(async {
+            let timeout = background_timeout(|| "fired", Duration::from_secs(1));
+            let stopped = background_timeout(|| "fired", Duration::from_secs(1));
+            stopped.stop();
+            assert!(!timeout.has_fired());
+            assert_eq!(timeout.join().await, Ok("fired"));
+            assert_eq!(stopped.join().await, Err(CancelledError));
+            assert_eq!(clock.pending_timers(), 0);
+        });
+    }
+
+    #[test]
+    fn background_interval_join() {
+        let clock = TestClock::new();
+        clock.resume();
+        clock.block_on(async {
+            let interval = background_default_interval(|_| {}, Duration::from_secs(1));
+            let token = interval.cancellation_token();
+            exec_future(async move {
+                wait(Duration::from_millis(3500)).await;
+                token.cancel();
+            });
+            wait(Duration::from_millis(2500)).await;
+            assert_eq!(interval.times_fired(), 2);
+            interval.join().await;
+            assert_eq!(clock.elapsed(), Duration::from_millis(3500));
+        });
+    }
+
+    #[test]
+    fn background_interval_commands() {
+        let clock = TestClock::new();
+        let deltas = Arc::new(std::sync::RwLock::new(vec![]));
+        let interval = {
+            let _guard = clock.enter();
+            background_default_interval({
+                let deltas = Arc::clone(&deltas);
+                move |delta| deltas.write().unwrap().push(delta)
+            }, Duration::from_millis(10))
+        };
+        clock.advance(Duration::from_millis(20));
+        interval.set_period(Duration::from_millis(30));
+        clock.advance(Duration::from_millis(60));
+        interval.stop();
+        clock.advance(Duration::from_millis(60));
+        let ms = Duration::from_millis;
+        assert_eq!(*deltas.read().unwrap(), [ms(10), ms(10), ms(30), ms(30)]);
     }
 }
\ No newline at end of file
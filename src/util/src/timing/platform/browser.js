@@ -53,46 +53,10 @@ export function animationInterval(callback, ms) {
     return controller;
 }
 
-export class Ticker {
-    constructor(forAnimation, ms) {
-        this.start = forAnimation
-            ? (document.timeline ? document.timeline.currentTime : performance.now())
-            : Date.now();
-        this.ms = ms;
-        this.lastInstant = this.start;
-        this._tickMethod = forAnimation ? this._animationTick.bind(this) : this._nonAnimationTick.bind(this);
-    }
-
-    tick(callback) {
-        this._tickMethod(callback);
-    }
-
-    tickInJSPromise() {
-        return new Promise((resolve, _) => {
-            this.tick(delta => {
-                resolve(delta);
-            });
+export function animationFrameInJSPromise() {
+    return new Promise((resolve, _) => {
+        requestAnimationFrame(time => {
+            resolve(time);
         });
-    }
-
-    _animationTick(callback) {
-        const elapsed = this.lastInstant - this.start;
-        const roundedElapsed = Math.round(elapsed / this.ms) * this.ms;
-        const targetNext = this.start + roundedElapsed + this.ms;
-        const delay = targetNext - performance.now();
-        const frame = time => {
-            let prevLastInstant = this.lastInstant;
-            this.lastInstant = time;
-            callback(time - prevLastInstant);
-        };
-        setTimeout(() => requestAnimationFrame(frame), delay);
-    }
-
-    _nonAnimationTick(callback) {
-        setTimeout(() => {
-            let prevLastInstant = this.lastInstant;
-            this.lastInstant = Date.now();
-            callback(this.lastInstant - prevLastInstant);
-        }, this.ms);
-    }
+    });
 }
\ No newline at end of file
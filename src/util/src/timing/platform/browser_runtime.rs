@@ -2,7 +2,7 @@
 When the Rialight runtime is targetting the browser.
 */
 
-use std::{time::Duration, ops::{Add, AddAssign, Sub, SubAssign}, fmt::Debug};
+use std::{time::Duration, ops::{Add, AddAssign, Sub, SubAssign}};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
@@ -21,28 +21,8 @@ extern "C" {
     #[wasm_bindgen(js_name = animationInterval)]
     fn animation_interval(closure: &Closure<dyn FnMut(f64)>, ms: f64) -> web_sys::AbortController;
 
-    // Ticker
-
-    pub type Ticker;
-
-    #[wasm_bindgen(constructor)]
-    fn new(for_animation: bool, ms: f64) -> Ticker;
-
-    #[wasm_bindgen(method, js_name = tickInJSPromise)]
-    fn tick_in_js_promise(this: &Ticker) -> js_sys::Promise;
-}
-
-impl Debug for Ticker {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("Ticker()")
-    }
-}
-
-impl Ticker {
-    async fn tick_in_future(&self) -> Duration {
-        let delta = wasm_bindgen_futures::JsFuture::from(self.tick_in_js_promise()).await;
-        Duration::from_millis(unsafe { delta.unwrap().as_f64().unwrap().to_int_unchecked::<u64>() })
-    }
+    #[wasm_bindgen(js_name = animationFrameInJSPromise)]
+    fn animation_frame_in_js_promise() -> js_sys::Promise;
 }
 
 pub async fn wait(duration: Duration) {
@@ -113,39 +93,7 @@ impl SubAssign<Duration> for Instant {
     }
 }
 
-/*
-pub async fn timeout<F: Future<Output = ()> + Send + 'static>(duration: Duration, future: F) -> Result<(), crate::timing::ElapsedError> {
-    let (_, i) = future_race([
-        future,
-        wait(duration),
-    ]).await;
-
-    match i {
-        0 => Ok(()),
-        1 => Err(crate::timing::ElapsedError),
-    }
-}
-*/
-
-#[derive(Debug)]
-pub struct Interval {
-    pub for_animation: bool,
-    pub period: Duration,
-    pub start: crate::timing::Instant,
-    pub ticker: Option<Ticker>,
-}
-
-impl Interval {
-    pub async fn tick(&mut self) -> Duration {
-        match self.ticker.as_ref() {
-            Some(ticker) => ticker.tick_in_future().await,
-            None => {
-                // initial tick
-                wait_until(self.start).await;
-                let ms: u32 = self.period.as_millis().try_into().expect("Developer has given too large period for interval");
-                self.ticker = Some(Ticker::new(self.for_animation, ms.into()));
-                return Duration::from_millis(0);
-            },
-        }
-    }
+/// Waits for the next animation frame.
+pub async fn animation_frame() {
+    wasm_bindgen_futures::JsFuture::from(animation_frame_in_js_promise()).await.unwrap();
 }
\ No newline at end of file
@@ -57,20 +57,4 @@ impl SubAssign<Duration> for Instant {
     fn sub_assign(&mut self, _rhs: Duration) {
         incorrect_runtime_panic!();
     }
-}
-
-#[derive(Debug)]
-pub struct Interval;
-
-impl Interval {
-    pub async fn tick(&mut self) -> Duration {
-        crate::futures::not_sendable_async!();
-        incorrect_runtime_panic!();
-    }
-}
-
-impl Drop for Interval {
-    fn drop(&mut self) {
-        incorrect_runtime_panic!();
-    }
 }
\ No newline at end of file
@@ -57,21 +57,4 @@ impl SubAssign<Duration> for Instant {
     fn sub_assign(&mut self, rhs: Duration) {
         self.0 = self.0 - rhs;
     }
-}
-
-#[derive(Debug)]
-pub struct Interval(pub tokio::time::Interval);
-
-impl Interval {
-    pub async fn tick(&mut self) -> Duration {
-        crate::futures::not_sendable_async!();
-        let last_tick_instant = tokio::time::Instant::now();
-        self.0.tick().await;
-        tokio::time::Instant::now() - last_tick_instant
-    }
-}
-
-impl Drop for Interval {
-    fn drop(&mut self) {
-    }
 }
\ No newline at end of file
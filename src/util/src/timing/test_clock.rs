@@ -0,0 +1,389 @@
+/*!
+Simulated clock used for deterministically testing code built on the timing API.
+*/
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    fmt::Debug,
+    pin::Pin,
+    rc::Rc,
+    sync::{Arc, atomic::{AtomicBool, Ordering}},
+    task::{Context, Poll, Waker},
+};
+use futures::{
+    executor::{LocalPool, LocalSpawner},
+    task::{ArcWake, LocalSpawnExt},
+};
+use super::{Duration, Instant};
+use crate::futures::Future;
+
+thread_local! {
+    static CURRENT_TEST_CLOCK: RefCell<Option<TestClock>> = const { RefCell::new(None) };
+}
+
+/// A manually driven clock that replaces the host clock
+/// for the timing API.
+///
+/// While a `TestClock` is entered (either through [`TestClock::enter`] or
+/// [`TestClock::block_on`]), [`Instant::now`] returns simulated instants and
+/// every wait, interval and background timeout created on the current thread is
+/// scheduled on the test clock instead of the Tokio or browser clock.
+/// Futures given to [`exec_future`](crate::futures::exec_future) are executed by the
+/// test clock as well.
+///
+/// Simulated time starts at zero and only moves forward:
+///
+/// - Explicitly, through [`TestClock::advance`], which wakes every pending
+///   wait, interval and timeout whose deadline is reached in deadline order,
+///   running the woken tasks after each one.
+/// - Automatically, when the clock is resumed and [`TestClock::block_on`]
+///   has no more work to do until the next deadline.
+///
+/// A `TestClock` is created paused.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::timing::*;
+///
+/// let clock = TestClock::new();
+/// clock.block_on(async {
+///     let start = Instant::now();
+///     let mut interval = default_interval(Duration::from_secs(1));
+///     interval.tick().await;
+///     clock.advance(Duration::from_secs(3));
+///     interval.tick().await;
+///     assert_eq!(Instant::now().since(start), Duration::from_secs(3));
+/// });
+/// ```
+#[derive(Clone)]
+pub struct TestClock {
+    inner: Rc<TestClockInner>,
+}
+
+struct TestClockInner {
+    elapsed: Cell<Duration>,
+    paused: Cell<bool>,
+    timers: RefCell<BTreeMap<(Duration, u64), Option<Waker>>>,
+    next_timer_id: Cell<u64>,
+    pool: RefCell<LocalPool>,
+    spawner: LocalSpawner,
+}
+
+impl TestClock {
+    /// Creates a paused test clock whose simulated time is zero.
+    pub fn new() -> Self {
+        let pool = LocalPool::new();
+        let spawner = pool.spawner();
+        Self {
+            inner: Rc::new(TestClockInner {
+                elapsed: Cell::new(Duration::ZERO),
+                paused: Cell::new(true),
+                timers: RefCell::new(BTreeMap::new()),
+                next_timer_id: Cell::new(0),
+                pool: RefCell::new(pool),
+                spawner,
+            }),
+        }
+    }
+
+    /// Returns the test clock entered on the current thread, if any.
+    pub fn current() -> Option<TestClock> {
+        CURRENT_TEST_CLOCK.with(|current| current.borrow().clone())
+    }
+
+    /// Returns the current simulated instant.
+    pub fn now(&self) -> Instant {
+        Instant::simulated(self.inner.elapsed.get())
+    }
+
+    /// Returns the simulated time elapsed since the clock was created.
+    pub fn elapsed(&self) -> Duration {
+        self.inner.elapsed.get()
+    }
+
+    /// Indicates whether the clock is paused.
+    pub fn is_paused(&self) -> bool {
+        self.inner.paused.get()
+    }
+
+    /// Pauses the clock. A paused clock only moves forward
+    /// through [`TestClock::advance`].
+    pub fn pause(&self) {
+        self.inner.paused.set(true);
+    }
+
+    /// Resumes the clock, allowing [`TestClock::block_on`] to
+    /// automatically advance to the next deadline whenever
+    /// there is no more work to do.
+    pub fn resume(&self) {
+        self.inner.paused.set(false);
+    }
+
+    /// Returns the number of waits, intervals and timeouts
+    /// currently waiting on the clock.
+    pub fn pending_timers(&self) -> usize {
+        self.inner.timers.borrow().len()
+    }
+
+    /// Makes this clock the current clock of the thread until
+    /// the returned guard is dropped.
+    pub fn enter(&self) -> TestClockGuard {
+        let previous = CURRENT_TEST_CLOCK.with(|current| current.replace(Some(self.clone())));
+        TestClockGuard { previous }
+    }
+
+    /// Moves the simulated time forward by `duration`.
+    ///
+    /// Every pending wait, interval and timeout whose deadline is reached
+    /// is woken in deadline order, and the tasks spawned on the clock
+    /// run until they are stalled after each wake up.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a task spawned on the clock itself.
+    pub fn advance(&self, duration: Duration) {
+        let target = self.inner.elapsed.get() + duration;
+        loop {
+            self.run_until_stalled();
+            if !self.fire_next_timer(Some(target)) {
+                break;
+            }
+        }
+        self.inner.elapsed.set(target);
+        self.run_until_stalled();
+    }
+
+    /// Runs the tasks spawned on the clock until none of them
+    /// can make progress without the simulated time moving.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a task spawned on the clock itself.
+    pub fn run_until_stalled(&self) {
+        let _guard = self.enter();
+        self.inner.pool.try_borrow_mut()
+            .expect("TestClock cannot be driven from one of its own tasks")
+            .run_until_stalled();
+    }
+
+    /// Runs a future to completion on the current thread with
+    /// this clock entered, together with any task spawned on the clock.
+    ///
+    /// The future itself may call [`TestClock::advance`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the future can no longer make progress, which happens
+    /// when the clock is paused and every task is waiting for a deadline, or
+    /// when there are no pending timers at all.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        let _guard = self.enter();
+        let mut future = std::pin::pin!(future);
+        let woken = Arc::new(WakeFlag(AtomicBool::new(true)));
+        let waker = futures::task::waker(Arc::clone(&woken));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if woken.0.swap(false, Ordering::SeqCst) {
+                if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                    return output;
+                }
+            }
+            self.run_until_stalled();
+            if woken.0.load(Ordering::SeqCst) {
+                continue;
+            }
+            assert!(!self.is_paused(), "TestClock::block_on() cannot make progress while the clock is paused; call advance() or resume()");
+            assert!(self.fire_next_timer(None), "TestClock::block_on() cannot make progress as there are no pending timers");
+        }
+    }
+
+    pub(crate) fn spawn<F>(&self, future: F)
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        self.inner.spawner.spawn_local(future).expect("TestClock task pool is gone");
+    }
+
+    pub(crate) fn sleep_until(&self, deadline: Instant) -> Sleep {
+        Sleep {
+            clock: self.clone(),
+            deadline: deadline.simulated_elapsed(),
+            timer_id: None,
+        }
+    }
+
+    /// Wakes the earliest pending timer, if it is not
+    /// later than `limit`.
+    fn fire_next_timer(&self, limit: Option<Duration>) -> bool {
+        let mut timers = self.inner.timers.borrow_mut();
+        let Some(&(deadline, id)) = timers.keys().next() else {
+            return false;
+        };
+        if limit.is_some_and(|limit| deadline > limit) {
+            return false;
+        }
+        let waker = timers.remove(&(deadline, id)).flatten();
+        drop(timers);
+        if deadline > self.inner.elapsed.get() {
+            self.inner.elapsed.set(deadline);
+        }
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        true
+    }
+}
+
+impl Default for TestClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for TestClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestClock")
+            .field("elapsed", &self.elapsed())
+            .field("paused", &self.is_paused())
+            .finish()
+    }
+}
+
+/// Guard returned by [`TestClock::enter`]. Restores the previously
+/// entered clock when dropped.
+pub struct TestClockGuard {
+    previous: Option<TestClock>,
+}
+
+impl Drop for TestClockGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT_TEST_CLOCK.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+struct WakeFlag(AtomicBool);
+
+impl ArcWake for WakeFlag {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Future that completes once the simulated time reaches a deadline.
+pub(crate) struct Sleep {
+    clock: TestClock,
+    deadline: Duration,
+    timer_id: Option<u64>,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        let inner = &this.clock.inner;
+        if inner.elapsed.get() >= this.deadline {
+            if let Some(id) = this.timer_id.take() {
+                inner.timers.borrow_mut().remove(&(this.deadline, id));
+            }
+            return Poll::Ready(());
+        }
+        let id = *this.timer_id.get_or_insert_with(|| {
+            let id = inner.next_timer_id.get();
+            inner.next_timer_id.set(id + 1);
+            id
+        });
+        inner.timers.borrow_mut().insert((this.deadline, id), Some(cx.waker().clone()));
+        Poll::Pending
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some(id) = self.timer_id.take() {
+            self.clock.inner.timers.borrow_mut().remove(&(self.deadline, id));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{futures::exec_future, timing::*};
+    use std::sync::RwLock;
+
+    #[test]
+    fn deadline_order() {
+        let clock = TestClock::new();
+        let log = Rc::new(RefCell::new(vec![]));
+        {
+            let _guard = clock.enter();
+            for (name, ms) in [("c", 30), ("a", 10), ("b", 20)] {
+                let log = Rc::clone(&log);
+                exec_future(async move {
+                    wait(Duration::from_millis(ms)).await;
+                    log.borrow_mut().push((name, TestClock::current().unwrap().elapsed()));
+                });
+            }
+        }
+        clock.advance(Duration::from_millis(15));
+        assert_eq!(log.borrow().len(), 1);
+        clock.advance(Duration::from_millis(100));
+        assert_eq!(*log.borrow(), [
+            ("a", Duration::from_millis(10)),
+            ("b", Duration::from_millis(20)),
+            ("c", Duration::from_millis(30)),
+        ]);
+        assert_eq!(clock.elapsed(), Duration::from_millis(115));
+        assert_eq!(clock.pending_timers(), 0);
+    }
+
+    #[test]
+    fn intervals() {
+        let clock = TestClock::new();
+        let deltas = clock.block_on(async {
+            let mut interval = default_interval_at(Instant::now() + Duration::from_millis(5), Duration::from_millis(10));
+            let mut deltas = vec![];
+            clock.advance(Duration::from_millis(25));
+            for _ in 0..3 {
+                deltas.push(interval.tick().await);
+            }
+            deltas
+        });
+        assert_eq!(deltas, [Duration::ZERO, Duration::ZERO, Duration::ZERO]);
+
+        clock.resume();
+        let elapsed = clock.block_on(async {
+            let start = Instant::now();
+            let mut interval = animation_interval(Duration::from_millis(10));
+            interval.tick().await;
+            assert_eq!(interval.tick().await, Duration::from_millis(10));
+            Instant::now() - start
+        });
+        assert_eq!(elapsed, Duration::from_millis(10));
+    }
+
+    #[test]
+    fn background_timeouts() {
+        let clock = TestClock::new();
+        let fired = Arc::new(RwLock::new(vec![]));
+        let timeout = {
+            let _guard = clock.enter();
+            let first = background_timeout(Box::new({
+                let fired = Arc::clone(&fired);
+                move || fired.write().unwrap().push(1)
+            }), Duration::from_secs(1));
+            background_timeout(Box::new({
+                let fired = Arc::clone(&fired);
+                move || fired.write().unwrap().push(2)
+            }), Duration::from_secs(2));
+            first
+        };
+        timeout.stop();
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(*fired.read().unwrap(), [2]);
+    }
+}
@@ -0,0 +1,108 @@
+use std::fmt::Display;
+use url::Url;
+use crate::bytes::Bytes;
+
+/// A decoded `data:` URL.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::uri::DataUrl;
+///
+/// let url = DataUrl::parse("data:text/plain;charset=utf-8;base64,aGVsbG8=").unwrap();
+/// assert_eq!(url.mime_type(), "text/plain;charset=utf-8");
+/// assert_eq!(url.charset(), Some("utf-8"));
+/// assert_eq!(url.body().as_ref(), b"hello");
+/// ```
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct DataUrl {
+    mime_type: String,
+    charset: Option<String>,
+    body: Bytes,
+}
+
+impl DataUrl {
+    /// Parses and decodes a `data:` URL, as specified by the
+    /// [Fetch Standard](https://fetch.spec.whatwg.org/#data-url-processor).
+    /// A missing MIME type defaults to `text/plain;charset=US-ASCII`.
+    pub fn parse(url: &str) -> Result<Self, DataUrlError> {
+        let url = ::data_url::DataUrl::process(url).map_err(|error| match error {
+            ::data_url::DataUrlError::NotADataUrl => DataUrlError::NotADataUrl,
+            ::data_url::DataUrlError::NoComma => DataUrlError::NoComma,
+        })?;
+        let mime_type = url.mime_type();
+        let charset = mime_type.get_parameter("charset").map(|charset| charset.to_owned());
+        let mime_type = mime_type.to_string();
+        let (body, _) = url.decode_to_vec().map_err(|_| DataUrlError::InvalidBase64)?;
+        Ok(Self { mime_type, charset, body: body.into() })
+    }
+
+    /// Returns the serialized MIME type, including its parameters.
+    pub fn mime_type(&self) -> &str {
+        &self.mime_type
+    }
+
+    /// Returns the `charset` parameter of the MIME type.
+    pub fn charset(&self) -> Option<&str> {
+        self.charset.as_deref()
+    }
+
+    /// Returns the decoded body.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// Returns the decoded body, consuming the URL.
+    pub fn into_body(self) -> Bytes {
+        self.body
+    }
+}
+
+impl TryFrom<&Url> for DataUrl {
+    type Error = DataUrlError;
+    fn try_from(url: &Url) -> Result<Self, Self::Error> {
+        Self::parse(url.as_str())
+    }
+}
+
+/// Error returned by [`DataUrl::parse`].
+#[derive(PartialEq, Clone, Debug, Copy)]
+pub enum DataUrlError {
+    /// The URL does not use the `data:` scheme.
+    NotADataUrl,
+    /// The URL has no comma separating the MIME type from the body.
+    NoComma,
+    /// The body is not valid Base64.
+    InvalidBase64,
+}
+
+impl Display for DataUrlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotADataUrl => write!(f, "Not a data URL"),
+            Self::NoComma => write!(f, "Data URL has no comma separating its body"),
+            Self::InvalidBase64 => write!(f, "Data URL has an invalid Base64 body"),
+        }
+    }
+}
+
+impl std::error::Error for DataUrlError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn data_urls() {
+        let url = DataUrl::parse("data:,a%20b").unwrap();
+        assert_eq!(url.mime_type(), "text/plain;charset=US-ASCII");
+        assert_eq!(url.into_body().as_ref(), b"a b");
+
+        let url = Url::parse("data:image/png;base64,AAEC").unwrap();
+        assert_eq!(DataUrl::try_from(&url).unwrap().body().as_ref(), [0, 1, 2]);
+
+        assert_eq!(DataUrl::parse("https://example.com"), Err(DataUrlError::NotADataUrl));
+        assert_eq!(DataUrl::parse("data:text/plain"), Err(DataUrlError::NoComma));
+        assert_eq!(DataUrl::parse("data:;base64,%%%"), Err(DataUrlError::InvalidBase64));
+    }
+}
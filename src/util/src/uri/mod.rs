@@ -1,9 +1,67 @@
 /*!
 Work with URIs.
+
+# URLs
+
+The [`Url`] type parses, normalizes and serializes URLs as specified by
+the [WHATWG URL Standard](https://url.spec.whatwg.org), giving access to
+the scheme, authority, path, query and fragment of a URL.
+
+```
+use rialight_util::uri::Url;
+
+let url = Url::parse("HTTPS://Example.COM:443/a/./b/../c?x=1#top").unwrap();
+assert_eq!(url.as_str(), "https://example.com/a/c?x=1#top");
+assert_eq!(url.host_str(), Some("example.com"));
+assert_eq!(url.port(), None);
+assert_eq!(url.fragment(), Some("top"));
+```
+
+Relative references are resolved against a base URL with [`Url::join`]:
+
+```
+use rialight_util::uri::Url;
+
+let base = Url::parse("app://assets/images/icon.png").unwrap();
+assert_eq!(base.join("../fonts/main.ttf").unwrap().as_str(), "app://assets/fonts/main.ttf");
+```
+
+# Query parameters
+
+[`SearchParams`] reads and mutates query parameters, and the
+[`UrlSearchParams`] trait converts them from and to the query of a [`Url`].
+
+```
+use rialight_util::uri::{Url, UrlSearchParams};
+
+let mut url = Url::parse("https://example.com/search?q=rust").unwrap();
+let mut params = url.search_params();
+params.append("page", "2");
+url.set_search_params(&params);
+assert_eq!(url.as_str(), "https://example.com/search?q=rust&page=2");
+```
+
+# Data URLs
+
+[`DataUrl`] decodes `data:` URLs into their MIME type and body.
+
+# Encoding
+
+The [`encode_uri`], [`decode_uri`], [`encode_uri_component`] and
+[`decode_uri_component`] functions escape and unescape text in the
+manner of the JavaScript functions of the same names.
 */
 
 use super::reg_exp::*;
 
+pub use url::{Url, ParseError as UrlParseError, Host, Origin, Position};
+
+mod search_params;
+pub use search_params::{SearchParams, UrlSearchParams};
+
+mod data_url;
+pub use data_url::{DataUrl, DataUrlError};
+
 /// Escapes certain URI characters. Escapes all characters except:
 /// ```text
 /// A–Z a–z 0–9 - _ . ! ~ * ' ( )
@@ -0,0 +1,172 @@
+use std::fmt::Display;
+use url::{form_urlencoded, Url};
+
+/// A list of query parameters in the `application/x-www-form-urlencoded`
+/// format, similar to the `URLSearchParams` interface of the web.
+///
+/// Parameters keep their insertion order and names may repeat.
+///
+/// # Example
+///
+/// ```
+/// use rialight_util::uri::SearchParams;
+///
+/// let mut params = SearchParams::parse("?q=rust&page=2");
+/// assert_eq!(params.get("q"), Some("rust"));
+/// params.set("page", "3");
+/// params.append("tag", "a b");
+/// assert_eq!(params.to_string(), "q=rust&page=3&tag=a+b");
+/// ```
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct SearchParams {
+    pairs: Vec<(String, String)>,
+}
+
+impl SearchParams {
+    /// Creates an empty parameter list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a query string. A leading `?` is ignored.
+    pub fn parse(query: &str) -> Self {
+        let query = query.strip_prefix('?').unwrap_or(query);
+        form_urlencoded::parse(query.as_bytes()).into_owned().collect()
+    }
+
+    /// Returns the value of the first parameter with the given name.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.pairs.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_ref())
+    }
+
+    /// Returns the values of every parameter with the given name.
+    pub fn get_all(&self, name: &str) -> Vec<&str> {
+        self.pairs.iter().filter(|(n, _)| n == name).map(|(_, v)| v.as_ref()).collect()
+    }
+
+    /// Indicates whether a parameter with the given name exists.
+    pub fn has(&self, name: &str) -> bool {
+        self.pairs.iter().any(|(n, _)| n == name)
+    }
+
+    /// Appends a parameter.
+    pub fn append(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.pairs.push((name.into(), value.into()));
+    }
+
+    /// Sets the value of the first parameter with the given name,
+    /// removing the others. The parameter is appended if it does not exist.
+    pub fn set(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        let (name, value) = (name.into(), value.into());
+        let mut found = false;
+        self.pairs.retain_mut(|(n, v)| {
+            if *n != name {
+                return true;
+            }
+            if found {
+                return false;
+            }
+            found = true;
+            v.clone_from(&value);
+            true
+        });
+        if !found {
+            self.pairs.push((name, value));
+        }
+    }
+
+    /// Removes every parameter with the given name.
+    pub fn delete(&mut self, name: &str) {
+        self.pairs.retain(|(n, _)| n != name);
+    }
+
+    /// Sorts the parameters by name, keeping the relative order
+    /// of parameters with the same name.
+    pub fn sort(&mut self) {
+        self.pairs.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+    }
+
+    /// Iterates the parameters as `(name, value)` pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.pairs.iter().map(|(n, v)| (n.as_ref(), v.as_ref()))
+    }
+
+    /// Returns the number of parameters.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Indicates whether there are no parameters.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
+
+impl Display for SearchParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let serialized = form_urlencoded::Serializer::new(String::new()).extend_pairs(&self.pairs).finish();
+        write!(f, "{serialized}")
+    }
+}
+
+impl<N: Into<String>, V: Into<String>> FromIterator<(N, V)> for SearchParams {
+    fn from_iter<T: IntoIterator<Item = (N, V)>>(iter: T) -> Self {
+        Self { pairs: iter.into_iter().map(|(n, v)| (n.into(), v.into())).collect() }
+    }
+}
+
+impl<N: Into<String>, V: Into<String>> Extend<(N, V)> for SearchParams {
+    fn extend<T: IntoIterator<Item = (N, V)>>(&mut self, iter: T) {
+        self.pairs.extend(iter.into_iter().map(|(n, v)| (n.into(), v.into())));
+    }
+}
+
+/// Extension methods for reading and writing the query of a [`Url`]
+/// as [`SearchParams`].
+pub trait UrlSearchParams {
+    /// Returns the parameters of the query.
+    fn search_params(&self) -> SearchParams;
+    /// Replaces the query by the given parameters.
+    /// Empty parameters remove the query.
+    fn set_search_params(&mut self, params: &SearchParams);
+}
+
+impl UrlSearchParams for Url {
+    fn search_params(&self) -> SearchParams {
+        self.query_pairs().into_owned().collect()
+    }
+
+    fn set_search_params(&mut self, params: &SearchParams) {
+        if params.is_empty() {
+            self.set_query(None);
+        } else {
+            self.set_query(Some(&params.to_string()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn search_params() {
+        let mut params = SearchParams::parse("a=1&b=2&a=3&c=%20x+y");
+        assert_eq!(params.get_all("a"), ["1", "3"]);
+        assert_eq!(params.get("c"), Some(" x y"));
+        params.set("a", "4");
+        assert_eq!(params.to_string(), "a=4&b=2&c=+x+y");
+        params.delete("b");
+        params.append("B", "5");
+        params.sort();
+        assert_eq!(params.iter().map(|(n, _)| n).collect::<Vec<_>>(), ["B", "a", "c"]);
+
+        let mut url = Url::parse("https://example.com/?x=1").unwrap();
+        let mut params = url.search_params();
+        params.append("y", "&");
+        url.set_search_params(&params);
+        assert_eq!(url.as_str(), "https://example.com/?x=1&y=%26");
+        url.set_search_params(&SearchParams::new());
+        assert_eq!(url.as_str(), "https://example.com/");
+    }
+}